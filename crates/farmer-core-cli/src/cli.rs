@@ -0,0 +1,718 @@
+use anchor_lang::prelude::Pubkey;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use farmer_core::states::ZipPrefix;
+use farmer_core_client::zip;
+use std::path::PathBuf;
+
+/// Admin CLI for the farmer-core program.
+///
+/// The RPC URL and keypair default to `[provider]` in the nearest Anchor.toml.
+#[derive(Parser)]
+#[command(name = "farmer-core-cli", version)]
+pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Args)]
+pub struct GlobalArgs {
+    /// RPC URL or cluster name (localnet, devnet, testnet, mainnet)
+    #[arg(long, short = 'u', global = true)]
+    pub url: Option<String>,
+
+    /// Path to the signer / fee payer keypair
+    #[arg(long, short = 'k', global = true)]
+    pub keypair: Option<PathBuf>,
+
+    /// Simulate the transaction and print it instead of sending it
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Public key to simulate as with --dry-run, without reading the keypair
+    #[arg(long, global = true, requires = "dry_run")]
+    pub signer: Option<Pubkey>,
+
+    /// Output format
+    #[arg(long, short = 'o', global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Program configuration
+    #[command(subcommand)]
+    Config(ConfigCommand),
+
+    /// Payment mint allowlist
+    #[command(subcommand)]
+    Mint(MintCommand),
+
+    /// Fulfillment warehouses
+    #[command(subcommand)]
+    Warehouse(WarehouseCommand),
+
+    /// Farmer profiles (signed by the farmer key)
+    #[command(subcommand)]
+    Farmer(FarmerCommand),
+
+    /// Customer profiles (signed by the customer key)
+    #[command(subcommand)]
+    Customer(CustomerCommand),
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Create the config (signer must be the upgrade authority)
+    Init {
+        /// Wallet that receives the protocol fee cut
+        #[arg(long)]
+        logistics_wallet: Pubkey,
+
+        /// Initial pause flags (see `config pause`)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        pause: Vec<PauseFlag>,
+    },
+
+    /// Print the current config
+    Show,
+
+    /// Change config fields; omitted flags keep their value
+    Update {
+        /// New logistics wallet
+        #[arg(long)]
+        logistics_wallet: Option<Pubkey>,
+
+        /// Seconds a warehouse fee increase waits before it applies
+        #[arg(long)]
+        fee_notice_period: Option<i64>,
+
+        /// Protocol cut of warehouse fees sent to the logistics wallet, in bps
+        #[arg(long)]
+        protocol_fee_bps: Option<u16>,
+    },
+
+    /// Replace the pause mask with exactly the given flags
+    Pause {
+        /// Flags to pause; `none` resumes everything
+        #[arg(value_enum, value_delimiter = ',', required = true)]
+        flags: Vec<PauseFlag>,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PauseFlag {
+    NewOrders,
+    NewOffers,
+    Onboarding,
+    Settlements,
+    Refunds,
+    All,
+    None,
+}
+
+#[derive(Subcommand)]
+pub enum MintCommand {
+    /// Allow a mint for payments
+    Add {
+        /// SPL Token or Token-2022 mint
+        mint: Pubkey,
+
+        /// Smallest accepted order subtotal, in minor units
+        #[arg(long, default_value_t = 0)]
+        min_order: u64,
+
+        /// Largest accepted order subtotal, in minor units
+        #[arg(long)]
+        max_order: u64,
+
+        /// Minimum service fee per order, in minor units
+        #[arg(long)]
+        fee_floor: Option<u64>,
+    },
+
+    /// Remove a mint from the allowlist
+    Remove { mint: Pubkey },
+
+    /// List every allowed mint
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum WarehouseCommand {
+    /// Create a warehouse (admin only)
+    Create {
+        /// Unique warehouse id
+        #[arg(long)]
+        id: u64,
+
+        /// Operator key that runs the warehouse
+        #[arg(long)]
+        operator: Pubkey,
+
+        #[arg(long)]
+        name: String,
+
+        /// Public pickup instructions
+        #[arg(long, default_value = "")]
+        pickup_notes: String,
+
+        /// Service fee in basis points
+        #[arg(long)]
+        fee_bps: u16,
+
+        /// ZIP prefixes served, e.g. `123,04567` (leading zeros count)
+        #[arg(long, value_delimiter = ',', value_parser = parse_zip_prefix)]
+        zip: Vec<ZipPrefix>,
+
+        /// Public delivery fee rules document
+        #[arg(long)]
+        fee_rules_uri: Option<String>,
+    },
+
+    /// Change warehouse fields (operator or admin); omitted flags keep their value
+    Update {
+        /// Warehouse id
+        #[arg(long)]
+        id: u64,
+
+        #[arg(long)]
+        name: Option<String>,
+
+        #[arg(long)]
+        pickup_notes: Option<String>,
+
+        /// New service fee; increases apply after the config's notice period
+        #[arg(long)]
+        fee_bps: Option<u16>,
+
+        /// Replacement ZIP prefix list, e.g. `123,04567`
+        #[arg(long, value_delimiter = ',', value_parser = parse_zip_prefix)]
+        zip: Option<Vec<ZipPrefix>>,
+
+        /// Remove every ZIP prefix
+        #[arg(long, conflicts_with = "zip")]
+        clear_zip: bool,
+
+        /// New delivery fee rules document; `""` clears it
+        #[arg(long)]
+        fee_rules_uri: Option<String>,
+
+        /// Rotate the operator key
+        #[arg(long)]
+        operator: Option<Pubkey>,
+
+        /// Wallet that receives the warehouse's fees at settlement
+        #[arg(long)]
+        fee_receiver: Option<Pubkey>,
+
+        /// Default lifetime of customer confirmations in seconds; 0 means they do not expire
+        #[arg(long)]
+        confirmation_validity: Option<i64>,
+
+        /// X25519 public key customers seal their address to, 64 hex characters
+        #[arg(long, value_parser = parse_hash)]
+        encryption_key: Option<[u8; 32]>,
+
+        /// Stop accepting address envelopes
+        #[arg(long, conflicts_with = "encryption_key")]
+        clear_encryption_key: bool,
+
+        /// Sign as staff with the `manage` role (details only)
+        #[arg(
+            long,
+            conflicts_with_all = [
+                "fee_bps",
+                "operator",
+                "fee_receiver",
+                "encryption_key",
+                "clear_encryption_key"
+            ]
+        )]
+        as_staff: bool,
+    },
+
+    /// Set the warehouse status (admin only)
+    Status {
+        /// Warehouse id
+        #[arg(long)]
+        id: u64,
+
+        /// `closed` is final: only refunds are processed afterwards
+        #[arg(value_enum)]
+        status: StatusArg,
+    },
+
+    /// Staff role grants
+    #[command(subcommand)]
+    Staff(StaffCommand),
+
+    /// Farmer affiliations (operator only)
+    #[command(subcommand)]
+    Affiliation(AffiliationCommand),
+
+    /// Customer confirmations (operator or staff with the `confirm-customers` role)
+    #[command(subcommand)]
+    Customer(WarehouseCustomerCommand),
+
+    /// List the warehouses that deliver to a ZIP code or prefix
+    Find {
+        /// ZIP code or prefix, e.g. `12345` or `0123`
+        #[arg(long, value_parser = parse_zip_prefix)]
+        zip: ZipPrefix,
+    },
+
+    /// Print a warehouse
+    Show {
+        /// Warehouse id
+        id: u64,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StatusArg {
+    Active,
+    Suspended,
+    Closed,
+}
+
+#[derive(Subcommand)]
+pub enum StaffCommand {
+    /// Add roles to a staff member (operator or admin)
+    Grant {
+        /// Warehouse id
+        #[arg(long)]
+        id: u64,
+
+        /// Staff key
+        #[arg(long)]
+        member: Pubkey,
+
+        #[arg(long, value_enum, value_delimiter = ',', required = true)]
+        roles: Vec<StaffRole>,
+    },
+
+    /// Remove roles from a staff member; the grant is closed when none are left
+    Revoke {
+        /// Warehouse id
+        #[arg(long)]
+        id: u64,
+
+        /// Staff key
+        #[arg(long)]
+        member: Pubkey,
+
+        #[arg(long, value_enum, value_delimiter = ',', required = true)]
+        roles: Vec<StaffRole>,
+    },
+
+    /// List every staff member of a warehouse
+    List {
+        /// Warehouse id
+        id: u64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AffiliationCommand {
+    /// Accept a farmer's pending request; they can then publish offers here
+    Approve {
+        /// Warehouse id
+        #[arg(long)]
+        id: u64,
+
+        /// Farmer key
+        #[arg(long)]
+        farmer: Pubkey,
+    },
+
+    /// Decline a farmer's pending request
+    Reject {
+        /// Warehouse id
+        #[arg(long)]
+        id: u64,
+
+        /// Farmer key
+        #[arg(long)]
+        farmer: Pubkey,
+    },
+
+    /// End a farmer's affiliation (no active offers here); the rent goes to the farmer
+    Remove {
+        /// Warehouse id
+        #[arg(long)]
+        id: u64,
+
+        /// Farmer key
+        #[arg(long)]
+        farmer: Pubkey,
+    },
+
+    /// List every affiliation of a warehouse
+    List {
+        /// Warehouse id
+        id: u64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum WarehouseCustomerCommand {
+    /// Confirm a pending customer after verifying the address off-chain
+    Confirm {
+        /// Warehouse id
+        #[arg(long)]
+        id: u64,
+
+        /// Customer key
+        #[arg(long)]
+        customer: Pubkey,
+
+        /// Hash of the off-chain record, 64 hex characters
+        #[arg(long, value_parser = parse_hash)]
+        notes_hash: Option<[u8; 32]>,
+
+        /// Unix timestamp the confirmation expires at (defaults to the warehouse's validity)
+        #[arg(long)]
+        valid_until: Option<i64>,
+
+        /// Ciphertext hash of the customer's address envelope, as printed by `open`
+        #[arg(long, value_parser = parse_hash)]
+        envelope_hash: Option<[u8; 32]>,
+
+        /// Sign as staff with the `confirm-customers` role
+        #[arg(long)]
+        as_staff: bool,
+    },
+
+    /// Confirm up to 25 pending customers in one transaction; all or none are confirmed
+    ConfirmBatch {
+        /// Warehouse id
+        #[arg(long)]
+        id: u64,
+
+        /// Customers as `KEY`, or `KEY:ENVELOPE_HASH` for requests with an address envelope
+        /// (each hash takes 32 bytes, so only about 13 of those fit)
+        #[arg(long, required = true, value_delimiter = ',', value_parser = parse_batch_entry)]
+        customers: Vec<(Pubkey, Option<[u8; 32]>)>,
+
+        /// Unix timestamp every confirmation expires at (defaults to the warehouse's validity)
+        #[arg(long)]
+        valid_until: Option<i64>,
+
+        /// Compute unit limit requested for the transaction
+        #[arg(long, default_value_t = 400_000)]
+        compute_units: u32,
+
+        /// Sign as staff with the `confirm-customers` role
+        #[arg(long)]
+        as_staff: bool,
+    },
+
+    /// Extend a confirmation, expired or not, after re-verifying the address
+    Renew {
+        /// Warehouse id
+        #[arg(long)]
+        id: u64,
+
+        /// Customer key
+        #[arg(long)]
+        customer: Pubkey,
+
+        /// Hash of the new off-chain record (keeps the current one if omitted)
+        #[arg(long, value_parser = parse_hash)]
+        notes_hash: Option<[u8; 32]>,
+
+        /// Unix timestamp the confirmation expires at (defaults to the warehouse's validity)
+        #[arg(long)]
+        valid_until: Option<i64>,
+
+        /// Sign as staff with the `confirm-customers` role
+        #[arg(long)]
+        as_staff: bool,
+    },
+
+    /// Decline a pending customer or withdraw a confirmation
+    Revoke {
+        /// Warehouse id
+        #[arg(long)]
+        id: u64,
+
+        /// Customer key
+        #[arg(long)]
+        customer: Pubkey,
+
+        /// Sign as staff with the `confirm-customers` role
+        #[arg(long)]
+        as_staff: bool,
+    },
+
+    /// List every customer of a warehouse
+    List {
+        /// Warehouse id
+        id: u64,
+    },
+
+    /// Generate an address envelope key pair; publish the public key with `warehouse update`
+    Keygen {
+        /// File the secret key is written to (hex)
+        #[arg(long)]
+        out: PathBuf,
+    },
+
+    /// Decrypt a customer's address envelope fetched from its URI
+    Open {
+        /// Warehouse id
+        #[arg(long)]
+        id: u64,
+
+        /// Customer key
+        #[arg(long)]
+        customer: Pubkey,
+
+        /// File holding the secret key written by `keygen`
+        #[arg(long)]
+        secret_key: PathBuf,
+
+        /// File holding the ciphertext
+        #[arg(long)]
+        ciphertext: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StaffRole {
+    ConfirmCustomers,
+    Quote,
+    Dispatch,
+    Complete,
+    Refund,
+    Manage,
+    All,
+}
+
+#[derive(Subcommand)]
+pub enum FarmerCommand {
+    /// Register the signer as a farmer
+    Register {
+        #[arg(long)]
+        name: String,
+
+        /// Public profile page (https://, ipfs:// or ar://)
+        #[arg(long, default_value = "")]
+        profile_uri: String,
+    },
+
+    /// Change the signer's profile; omitted flags keep their value
+    Update {
+        #[arg(long)]
+        name: Option<String>,
+
+        /// New profile page; `""` clears it
+        #[arg(long)]
+        profile_uri: Option<String>,
+
+        /// Wallet whose token accounts receive settlements
+        #[arg(long)]
+        payout_wallet: Option<Pubkey>,
+
+        /// Key allowed to publish and deactivate offers
+        #[arg(long)]
+        delegate: Option<Pubkey>,
+
+        /// Remove the delegate
+        #[arg(long, conflicts_with = "delegate")]
+        clear_delegate: bool,
+    },
+
+    /// Close the signer's profile (all offers closed, no open orders) and reclaim rent
+    Close,
+
+    /// Ask a warehouse to fulfil the signer's offers; its operator approves or rejects
+    Affiliate {
+        /// Warehouse id
+        #[arg(long)]
+        warehouse: u64,
+    },
+
+    /// End the signer's affiliation with a warehouse (no active offers there)
+    Leave {
+        /// Warehouse id
+        #[arg(long)]
+        warehouse: u64,
+    },
+
+    /// The signer's offers
+    #[command(subcommand)]
+    Offer(OfferCommand),
+
+    /// Print a farmer profile and its affiliations
+    Show {
+        /// Farmer key (defaults to the signer)
+        farmer: Option<Pubkey>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum OfferCommand {
+    /// Publish an offer fulfilled by one of the farmer's approved warehouses
+    Publish {
+        /// Farmer key, when signing as its delegate (defaults to the signer)
+        #[arg(long)]
+        farmer: Option<Pubkey>,
+
+        /// Warehouse id
+        #[arg(long)]
+        warehouse: u64,
+
+        /// Allowed payment mint
+        #[arg(long)]
+        mint: Pubkey,
+
+        #[arg(long)]
+        crop: String,
+
+        #[arg(long)]
+        cultivar: Option<String>,
+
+        /// Unit mapping shared with clients, e.g. 2 = kg stored as grams
+        #[arg(long)]
+        unit_code: u16,
+
+        /// Quantity in base units
+        #[arg(long)]
+        qty: u64,
+
+        /// Price per base unit, in the mint's minor units
+        #[arg(long)]
+        price: u64,
+
+        /// Unix timestamp after which the offer stops selling
+        #[arg(long)]
+        expires_at: Option<i64>,
+
+        /// Public notes
+        #[arg(long)]
+        notes: Option<String>,
+    },
+
+    /// Stop purchases on an offer
+    Deactivate {
+        /// Offer id
+        id: u64,
+
+        /// Farmer key, when signing as its delegate (defaults to the signer)
+        #[arg(long)]
+        farmer: Option<Pubkey>,
+    },
+
+    /// Close one of the signer's deactivated offers and reclaim rent
+    Close {
+        /// Offer id
+        id: u64,
+    },
+
+    /// Print an offer
+    Show {
+        /// Offer id
+        id: u64,
+
+        /// Farmer key (defaults to the signer)
+        #[arg(long)]
+        farmer: Option<Pubkey>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CustomerCommand {
+    /// Register the signer as a customer
+    Register {
+        /// Public profile page (https://, ipfs:// or ar://)
+        #[arg(long, default_value = "")]
+        profile_uri: String,
+    },
+
+    /// Ask a warehouse to confirm the signer; share the address off-chain or as a sealed envelope
+    Request {
+        /// Warehouse id
+        #[arg(long)]
+        warehouse: u64,
+
+        /// Where the sealed address is stored (see `seal`)
+        #[arg(long, requires = "envelope_hash")]
+        envelope_uri: Option<String>,
+
+        /// Ciphertext hash printed by `seal`
+        #[arg(long, requires = "envelope_uri", value_parser = parse_hash)]
+        envelope_hash: Option<[u8; 32]>,
+    },
+
+    /// Seal a delivery address to a warehouse's encryption key
+    Seal {
+        /// Warehouse id
+        #[arg(long)]
+        warehouse: u64,
+
+        /// The delivery address
+        #[arg(long)]
+        address: String,
+
+        /// File the ciphertext is written to; store it where the warehouse can fetch it
+        #[arg(long)]
+        out: PathBuf,
+    },
+
+    /// Leave a warehouse (no open orders there); it deletes the address it holds
+    Withdraw {
+        /// Warehouse id
+        #[arg(long)]
+        warehouse: u64,
+    },
+
+    /// Close the signer's profile (withdrawn from every warehouse, no open orders) and reclaim rent
+    Close,
+
+    /// Print a customer profile and its warehouse confirmations
+    Show {
+        /// Customer key (defaults to the signer)
+        customer: Option<Pubkey>,
+    },
+}
+
+/// Parses 64 hex characters into a 32-byte hash.
+pub fn parse_hash(s: &str) -> Result<[u8; 32], String> {
+    let invalid = || format!("`{s}` is not a 32-byte hex hash");
+    if s.len() != 64 || !s.is_ascii() {
+        return Err(invalid());
+    }
+    let mut hash = [0u8; 32];
+    for (byte, pair) in hash.iter_mut().zip(s.as_bytes().chunks(2)) {
+        let pair = std::str::from_utf8(pair).map_err(|_| invalid())?;
+        *byte = u8::from_str_radix(pair, 16).map_err(|_| invalid())?;
+    }
+    Ok(hash)
+}
+
+/// Parses `KEY` or `KEY:ENVELOPE_HASH` into a customer and its envelope hash.
+pub fn parse_batch_entry(s: &str) -> Result<(Pubkey, Option<[u8; 32]>), String> {
+    let (key, hash) = match s.split_once(':') {
+        Some((key, hash)) => (key, Some(parse_hash(hash)?)),
+        None => (s, None),
+    };
+    let customer = key
+        .parse()
+        .map_err(|_| format!("`{key}` is not a customer key"))?;
+    Ok((customer, hash))
+}
+
+/// Parses `"0123"` as `ZipPrefix { prefix: 123, len: 4 }`.
+pub fn parse_zip_prefix(s: &str) -> Result<ZipPrefix, String> {
+    zip::parse(s).ok_or_else(|| format!("`{s}` is not a 3-5 digit ZIP prefix"))
+}
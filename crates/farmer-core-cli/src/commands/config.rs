@@ -0,0 +1,102 @@
+use anyhow::{bail, Result};
+use farmer_core::states::{
+    ProgramConfig, PAUSE_ALL, PAUSE_LEGACY_ALL, PAUSE_NEW_OFFERS, PAUSE_NEW_ORDERS,
+    PAUSE_ONBOARDING, PAUSE_REFUNDS, PAUSE_SETTLEMENTS,
+};
+use farmer_core_client::{accounts, instructions, pda};
+use serde_json::{json, Value};
+
+use super::Context;
+use crate::cli::{ConfigCommand, PauseFlag};
+
+const FLAG_NAMES: [(u8, &str); 6] = [
+    (PAUSE_LEGACY_ALL, "legacy-all"),
+    (PAUSE_NEW_ORDERS, "new-orders"),
+    (PAUSE_NEW_OFFERS, "new-offers"),
+    (PAUSE_ONBOARDING, "onboarding"),
+    (PAUSE_SETTLEMENTS, "settlements"),
+    (PAUSE_REFUNDS, "refunds"),
+];
+
+pub fn run(ctx: &Context, command: ConfigCommand) -> Result<()> {
+    match command {
+        ConfigCommand::Init {
+            logistics_wallet,
+            pause,
+        } => ctx.submit(&[instructions::init_config(
+            &ctx.signer()?,
+            logistics_wallet,
+            pause_mask(&pause),
+        )]),
+        ConfigCommand::Show => {
+            let Some(config) = accounts::fetch_program_config(&ctx.rpc)? else {
+                bail!("config {} is not initialized", pda::config().0);
+            };
+            ctx.print(&config_json(&config));
+            Ok(())
+        }
+        ConfigCommand::Update {
+            logistics_wallet,
+            fee_notice_period,
+            protocol_fee_bps,
+        } => ctx.submit(&[instructions::update_config(
+            &ctx.signer()?,
+            logistics_wallet,
+            fee_notice_period,
+            protocol_fee_bps,
+        )]),
+        ConfigCommand::Pause { flags } => ctx.submit(&[instructions::set_pause_flags(
+            &ctx.signer()?,
+            pause_mask(&flags),
+        )]),
+    }
+}
+
+pub fn pause_mask(flags: &[PauseFlag]) -> u8 {
+    flags.iter().fold(0, |mask, flag| {
+        mask | match flag {
+            PauseFlag::NewOrders => PAUSE_NEW_ORDERS,
+            PauseFlag::NewOffers => PAUSE_NEW_OFFERS,
+            PauseFlag::Onboarding => PAUSE_ONBOARDING,
+            PauseFlag::Settlements => PAUSE_SETTLEMENTS,
+            PauseFlag::Refunds => PAUSE_REFUNDS,
+            PauseFlag::All => PAUSE_ALL,
+            PauseFlag::None => 0,
+        }
+    })
+}
+
+fn config_json(config: &ProgramConfig) -> Value {
+    let paused: Vec<&str> = FLAG_NAMES
+        .iter()
+        .filter(|(bit, _)| config.pause_flags & bit != 0)
+        .map(|(_, name)| *name)
+        .collect();
+
+    json!({
+        "address": pda::config().0.to_string(),
+        "admin": config.admin.to_string(),
+        "pending_admin": config.pending_admin.map(|key| key.to_string()),
+        "logistics_wallet": config.logistics_wallet.to_string(),
+        "pause_flags": config.pause_flags,
+        "paused": paused,
+        "fee_notice_period": config.fee_notice_period,
+        "protocol_fee_bps": config.protocol_fee_bps,
+        "version": config.version,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pause_mask_combines_flags() {
+        assert_eq!(
+            pause_mask(&[PauseFlag::NewOrders, PauseFlag::Refunds]),
+            PAUSE_NEW_ORDERS | PAUSE_REFUNDS
+        );
+        assert_eq!(pause_mask(&[PauseFlag::All]), PAUSE_ALL);
+        assert_eq!(pause_mask(&[PauseFlag::None]), 0);
+    }
+}
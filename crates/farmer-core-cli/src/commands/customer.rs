@@ -0,0 +1,157 @@
+use anchor_lang::Discriminator;
+use anyhow::{bail, Context as _, Result};
+use farmer_core::states::{CustomerProfile, WarehouseCustomer, WarehouseCustomerStatus};
+use farmer_core_client::{accounts, envelope, instructions, pda, AddressEnvelope};
+use serde_json::{json, Value};
+use std::fs;
+
+use super::Context;
+use crate::cli::CustomerCommand;
+
+/// Offset of `WarehouseCustomer::customer`: discriminator, then `warehouse`.
+const WAREHOUSE_CUSTOMER_CUSTOMER_OFFSET: usize = 8 + 32;
+
+pub fn run(ctx: &Context, command: CustomerCommand) -> Result<()> {
+    match command {
+        CustomerCommand::Register { profile_uri } => {
+            ctx.submit(&[instructions::register_customer(&ctx.signer()?, profile_uri)])
+        }
+        CustomerCommand::Request {
+            warehouse,
+            envelope_uri,
+            envelope_hash,
+        } => {
+            let address_envelope = match (envelope_uri, envelope_hash) {
+                (Some(uri), Some(ciphertext_hash)) => Some(AddressEnvelope {
+                    uri,
+                    ciphertext_hash,
+                    encryption_key: encryption_key(ctx, warehouse)?,
+                }),
+                _ => None,
+            };
+            ctx.submit(&[instructions::request_customer_confirmation(
+                &ctx.signer()?,
+                warehouse,
+                address_envelope,
+            )])
+        }
+        CustomerCommand::Seal {
+            warehouse,
+            address,
+            out,
+        } => {
+            let sealed = envelope::seal(&encryption_key(ctx, warehouse)?, address.as_bytes())?;
+            fs::write(&out, &sealed.ciphertext)
+                .with_context(|| format!("writing {}", out.display()))?;
+            ctx.print(&json!({
+                "ciphertext_file": out.display().to_string(),
+                "ciphertext_hash": hex(&sealed.ciphertext_hash),
+                "encryption_key": hex(&sealed.encryption_key),
+            }));
+            Ok(())
+        }
+        CustomerCommand::Withdraw { warehouse } => {
+            ctx.submit(&[instructions::withdraw_from_warehouse(
+                &ctx.signer()?,
+                warehouse,
+            )])
+        }
+        CustomerCommand::Close => {
+            ctx.submit(&[instructions::close_customer_profile(&ctx.signer()?)])
+        }
+        CustomerCommand::Show { customer } => {
+            let customer = customer.map_or_else(|| ctx.signer(), Ok)?;
+            let Some(profile) = accounts::fetch_customer_profile(&ctx.rpc, &customer)? else {
+                bail!("{customer} is not a registered customer");
+            };
+            let mut entries = ctx
+                .rpc
+                .program_accounts(
+                    &farmer_core::ID,
+                    &[
+                        (0, WarehouseCustomer::DISCRIMINATOR),
+                        (WAREHOUSE_CUSTOMER_CUSTOMER_OFFSET, customer.as_ref()),
+                    ],
+                )?
+                .into_iter()
+                .map(|(_, data)| accounts::decode_warehouse_customer(&data))
+                .collect::<anchor_lang::Result<Vec<_>>>()?;
+            entries.sort_by_key(|entry| entry.requested_at);
+
+            let mut output = customer_json(&profile);
+            output["confirmations"] = entries.iter().map(confirmation_json).collect();
+            ctx.print(&output);
+            Ok(())
+        }
+    }
+}
+
+/// The key a warehouse currently accepts address envelopes for.
+fn encryption_key(ctx: &Context, warehouse_id: u64) -> Result<[u8; 32]> {
+    let Some(warehouse) = accounts::fetch_warehouse(&ctx.rpc, warehouse_id)? else {
+        bail!("warehouse {warehouse_id} does not exist");
+    };
+    match warehouse.encryption_key {
+        Some(key) => Ok(key),
+        None => bail!("warehouse {warehouse_id} has not published an encryption key"),
+    }
+}
+
+/// Lowercase hex, the format `parse_hash` reads.
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn customer_json(profile: &CustomerProfile) -> Value {
+    json!({
+        "address": pda::customer(&profile.authority).0.to_string(),
+        "authority": profile.authority.to_string(),
+        "public_profile_uri": profile.public_profile_uri,
+        "order_counter": profile.order_counter,
+        "open_orders": profile.open_orders,
+        "linked_warehouses": profile.linked_warehouses,
+        "created_at": profile.created_at,
+    })
+}
+
+pub fn confirmation_json(entry: &WarehouseCustomer) -> Value {
+    json!({
+        "address": pda::warehouse_customer(&entry.warehouse, &entry.customer).0.to_string(),
+        "warehouse": entry.warehouse.to_string(),
+        "customer": entry.customer.to_string(),
+        "status": match entry.status {
+            WarehouseCustomerStatus::Pending => "pending",
+            WarehouseCustomerStatus::Confirmed => "confirmed",
+            WarehouseCustomerStatus::Revoked => "revoked",
+        },
+        "requested_at": entry.requested_at,
+        "confirmed_at": entry.confirmed_at,
+        "valid_until": entry.valid_until,
+        "open_orders": entry.open_orders,
+        "notes_hash": entry.notes_hash.as_ref().map(|hash| hex(hash)),
+        "address_envelope": entry.address_envelope.as_ref().map(|envelope| json!({
+            "uri": envelope.uri,
+            "ciphertext_hash": hex(&envelope.ciphertext_hash),
+            "encryption_key": hex(&envelope.encryption_key),
+        })),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::hex;
+    use crate::cli::parse_hash;
+
+    #[test]
+    fn hash_parses_from_hex() {
+        let hash = parse_hash(&"0a".repeat(32)).unwrap();
+        assert_eq!(hash, [0x0a; 32]);
+        assert!(parse_hash(&"0A".repeat(32)).is_ok());
+        assert!(parse_hash(&"0a".repeat(31)).is_err());
+        assert!(parse_hash(&"zz".repeat(32)).is_err());
+        assert!(parse_hash(&"é".repeat(32)).is_err());
+
+        let bytes: [u8; 32] = std::array::from_fn(|i| i as u8 * 8);
+        assert_eq!(parse_hash(&hex(&bytes)).unwrap(), bytes);
+    }
+}
@@ -0,0 +1,199 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Discriminator;
+use anyhow::{bail, Result};
+use farmer_core::states::{AffiliationStatus, FarmerAffiliation, FarmerProfile, LotOffer};
+use farmer_core_client::instructions::{FarmerProfileUpdate, NewOffer};
+use farmer_core_client::{accounts, instructions, pda};
+use serde_json::{json, Value};
+
+use super::Context;
+use crate::cli::{FarmerCommand, OfferCommand};
+
+/// Offset of `FarmerAffiliation::farmer`: discriminator, then `warehouse`.
+const AFFILIATION_FARMER_OFFSET: usize = 8 + 32;
+
+pub fn run(ctx: &Context, command: FarmerCommand) -> Result<()> {
+    match command {
+        FarmerCommand::Register { name, profile_uri } => {
+            ctx.submit(&[instructions::register_farmer(
+                &ctx.signer()?,
+                name,
+                profile_uri,
+            )])
+        }
+        FarmerCommand::Update {
+            name,
+            profile_uri,
+            payout_wallet,
+            delegate,
+            clear_delegate,
+        } => ctx.submit(&[instructions::update_farmer_profile(
+            &ctx.signer()?,
+            FarmerProfileUpdate {
+                display_name: name,
+                public_profile_uri: profile_uri,
+                payout_wallet,
+                delegate: if clear_delegate {
+                    Some(Pubkey::default())
+                } else {
+                    delegate
+                },
+            },
+        )]),
+        FarmerCommand::Close => ctx.submit(&[instructions::close_farmer_profile(&ctx.signer()?)]),
+        FarmerCommand::Affiliate { warehouse } => {
+            ctx.submit(&[instructions::request_warehouse_affiliation(
+                &ctx.signer()?,
+                warehouse,
+            )])
+        }
+        FarmerCommand::Leave { warehouse } => ctx.submit(&[instructions::end_affiliation(
+            &ctx.signer()?,
+            warehouse,
+            &ctx.signer()?,
+        )]),
+        FarmerCommand::Offer(command) => run_offer(ctx, command),
+        FarmerCommand::Show { farmer } => {
+            let farmer = farmer.map_or_else(|| ctx.signer(), Ok)?;
+            let Some(profile) = accounts::fetch_farmer_profile(&ctx.rpc, &farmer)? else {
+                bail!("{farmer} is not a registered farmer");
+            };
+            let mut affiliations = ctx
+                .rpc
+                .program_accounts(
+                    &farmer_core::ID,
+                    &[
+                        (0, FarmerAffiliation::DISCRIMINATOR),
+                        (AFFILIATION_FARMER_OFFSET, farmer.as_ref()),
+                    ],
+                )?
+                .into_iter()
+                .map(|(_, data)| accounts::decode_farmer_affiliation(&data))
+                .collect::<anchor_lang::Result<Vec<_>>>()?;
+            affiliations.sort_by_key(|entry| entry.requested_at);
+
+            let mut output = farmer_json(&profile);
+            output["affiliations"] = affiliations.iter().map(affiliation_json).collect();
+            ctx.print(&output);
+            Ok(())
+        }
+    }
+}
+
+fn run_offer(ctx: &Context, command: OfferCommand) -> Result<()> {
+    match command {
+        OfferCommand::Publish {
+            farmer,
+            warehouse,
+            mint,
+            crop,
+            cultivar,
+            unit_code,
+            qty,
+            price,
+            expires_at,
+            notes,
+        } => {
+            // The new offer's address is derived from the current counter
+            let farmer = farmer.map_or_else(|| ctx.signer(), Ok)?;
+            let Some(profile) = accounts::fetch_farmer_profile(&ctx.rpc, &farmer)? else {
+                bail!("{farmer} is not a registered farmer");
+            };
+            ctx.submit(&[instructions::publish_offer(
+                &ctx.signer()?,
+                &farmer,
+                warehouse,
+                &mint,
+                profile.offer_counter,
+                NewOffer {
+                    crop_name: crop,
+                    cultivar_name: cultivar,
+                    unit_code,
+                    qty,
+                    price_minor: price,
+                    expires_at,
+                    notes_public: notes,
+                },
+            )])
+        }
+        OfferCommand::Deactivate { id, farmer } => {
+            let farmer = farmer.map_or_else(|| ctx.signer(), Ok)?;
+            let Some(offer) = accounts::fetch_lot_offer(&ctx.rpc, &farmer, id)? else {
+                bail!("offer {id} of {farmer} does not exist");
+            };
+            ctx.submit(&[instructions::deactivate_offer(
+                &ctx.signer()?,
+                &farmer,
+                id,
+                &offer.warehouse,
+            )])
+        }
+        OfferCommand::Close { id } => {
+            let farmer = ctx.signer()?;
+            let Some(offer) = accounts::fetch_lot_offer(&ctx.rpc, &farmer, id)? else {
+                bail!("offer {id} of {farmer} does not exist");
+            };
+            ctx.submit(&[instructions::close_offer(&farmer, id, &offer.rent_payer)])
+        }
+        OfferCommand::Show { id, farmer } => {
+            let farmer = farmer.map_or_else(|| ctx.signer(), Ok)?;
+            let Some(offer) = accounts::fetch_lot_offer(&ctx.rpc, &farmer, id)? else {
+                bail!("offer {id} of {farmer} does not exist");
+            };
+            ctx.print(&offer_json(&offer));
+            Ok(())
+        }
+    }
+}
+
+fn farmer_json(profile: &FarmerProfile) -> Value {
+    json!({
+        "address": pda::farmer(&profile.authority).0.to_string(),
+        "authority": profile.authority.to_string(),
+        "payout_wallet": profile.payout_wallet.to_string(),
+        "delegate": profile.delegate.map(|key| key.to_string()),
+        "display_name": profile.display_name,
+        "public_profile_uri": profile.public_profile_uri,
+        "offer_counter": profile.offer_counter,
+        "active_offers": profile.active_offers,
+        "offer_accounts": profile.offer_accounts,
+        "open_orders": profile.open_orders,
+        "created_at": profile.created_at,
+    })
+}
+
+pub fn affiliation_json(affiliation: &FarmerAffiliation) -> Value {
+    json!({
+        "address": pda::farmer_affiliation(&affiliation.farmer, &affiliation.warehouse).0.to_string(),
+        "farmer": affiliation.farmer.to_string(),
+        "warehouse": affiliation.warehouse.to_string(),
+        "status": match affiliation.status {
+            AffiliationStatus::Pending => "pending",
+            AffiliationStatus::Approved => "approved",
+            AffiliationStatus::Rejected => "rejected",
+        },
+        "requested_at": affiliation.requested_at,
+        "decided_at": (affiliation.status != AffiliationStatus::Pending).then_some(affiliation.decided_at),
+        "active_offers": affiliation.active_offers,
+    })
+}
+
+fn offer_json(offer: &LotOffer) -> Value {
+    json!({
+        "address": pda::offer(&offer.farmer, offer.offer_id).0.to_string(),
+        "offer_id": offer.offer_id,
+        "farmer": offer.farmer.to_string(),
+        "warehouse": offer.warehouse.to_string(),
+        "crop_name": offer.crop_name,
+        "cultivar_name": offer.cultivar_name,
+        "unit_code": offer.unit_code,
+        "expires_at": offer.expires_at,
+        "notes_public": offer.notes_public,
+        "mint": offer.mint.to_string(),
+        "price_minor": offer.price_minor,
+        "qty_remaining": offer.qty_remaining,
+        "active": offer.active,
+        "created_at": offer.created_at,
+        "rent_payer": offer.rent_payer.to_string(),
+    })
+}
@@ -0,0 +1,50 @@
+use anchor_lang::Discriminator;
+use anyhow::Result;
+use farmer_core::states::AllowedMint;
+use farmer_core_client::{accounts, instructions};
+use serde_json::{json, Value};
+
+use super::Context;
+use crate::cli::MintCommand;
+
+pub fn run(ctx: &Context, command: MintCommand) -> Result<()> {
+    match command {
+        MintCommand::Add {
+            mint,
+            min_order,
+            max_order,
+            fee_floor,
+        } => ctx.submit(&[instructions::add_allowed_mint(
+            &ctx.signer()?,
+            &mint,
+            min_order,
+            max_order,
+            fee_floor,
+        )]),
+        MintCommand::Remove { mint } => {
+            ctx.submit(&[instructions::remove_allowed_mint(&ctx.signer()?, &mint)])
+        }
+        MintCommand::List => {
+            let mut entries = ctx
+                .rpc
+                .program_accounts(&farmer_core::ID, &[(0, AllowedMint::DISCRIMINATOR)])?
+                .into_iter()
+                .map(|(_, data)| accounts::decode_allowed_mint(&data))
+                .collect::<anchor_lang::Result<Vec<_>>>()?;
+            entries.sort_by_key(|entry| entry.mint.to_string());
+
+            ctx.print(&Value::Array(entries.iter().map(mint_json).collect()));
+            Ok(())
+        }
+    }
+}
+
+fn mint_json(entry: &AllowedMint) -> Value {
+    json!({
+        "mint": entry.mint.to_string(),
+        "decimals": entry.decimals,
+        "min_order_subtotal": entry.min_order_subtotal,
+        "max_order_subtotal": entry.max_order_subtotal,
+        "service_fee_floor": entry.service_fee_floor,
+    })
+}
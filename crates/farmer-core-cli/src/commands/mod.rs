@@ -0,0 +1,140 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anyhow::{anyhow, bail, Context as _, Result};
+use serde_json::{json, Value};
+use solana_keypair::{read_keypair_file, Keypair};
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use std::cell::OnceCell;
+use std::path::PathBuf;
+
+use crate::cli::OutputFormat;
+use crate::output;
+use crate::rpc::{encode_transaction, RpcClient};
+
+pub mod config;
+pub mod customer;
+pub mod farmer;
+pub mod mint;
+pub mod warehouse;
+
+/// Everything a subcommand needs: RPC, signer and the global flags.
+///
+/// The keypair is only read when a command needs it, so read-only commands
+/// work on machines without a wallet.
+pub struct Context {
+    pub rpc: RpcClient,
+    pub keypair: PathBuf,
+    /// `--signer`: key to simulate as under `--dry-run` instead of the keypair's
+    pub signer_override: Option<Pubkey>,
+    pub dry_run: bool,
+    pub output: OutputFormat,
+    loaded: OnceCell<Keypair>,
+}
+
+impl Context {
+    pub fn new(
+        rpc: RpcClient,
+        keypair: PathBuf,
+        signer_override: Option<Pubkey>,
+        dry_run: bool,
+        output: OutputFormat,
+    ) -> Self {
+        Self {
+            rpc,
+            keypair,
+            signer_override,
+            dry_run,
+            output,
+            loaded: OnceCell::new(),
+        }
+    }
+
+    pub fn print(&self, value: &Value) {
+        output::print(self.output, value);
+    }
+
+    /// The key transactions are signed and paid by: `--signer` if given,
+    /// otherwise the keypair's public key.
+    pub fn signer(&self) -> Result<Pubkey> {
+        match self.signer_override {
+            Some(signer) => Ok(signer),
+            None => Ok(self.load_keypair()?.pubkey()),
+        }
+    }
+
+    /// Reads the keypair file on first use.
+    fn load_keypair(&self) -> Result<&Keypair> {
+        if let Some(keypair) = self.loaded.get() {
+            return Ok(keypair);
+        }
+        let keypair = read_keypair_file(&self.keypair)
+            .map_err(|e| anyhow!("{e}"))
+            .with_context(|| {
+                format!(
+                    "reading keypair {} (pass --keypair, or --signer with --dry-run)",
+                    self.keypair.display()
+                )
+            })?;
+        Ok(self.loaded.get_or_init(|| keypair))
+    }
+
+    /// Signs `instructions` with the CLI keypair, then sends them or, with
+    /// `--dry-run`, simulates them and prints the encoded transaction. A
+    /// dry run with `--signer` leaves the transaction unsigned.
+    pub fn submit(&self, instructions: &[Instruction]) -> Result<()> {
+        let payer = self.signer()?;
+        let blockhash = self.rpc.latest_blockhash()?;
+        let mut transaction = Transaction::new_with_payer(instructions, Some(&payer));
+        if self.signer_override.is_some() {
+            transaction.message.recent_blockhash = blockhash;
+        } else {
+            transaction.sign(&[self.load_keypair()?], blockhash);
+        }
+
+        if !self.dry_run {
+            let signature = self.rpc.send_and_confirm(&transaction)?;
+            self.print(&json!({ "signature": signature }));
+            return Ok(());
+        }
+
+        let simulation = self.rpc.simulate(&transaction)?;
+        self.print(&json!({
+            "transaction": encode_transaction(&transaction)?,
+            "error": simulation.err,
+            "units_consumed": simulation.units_consumed,
+            "logs": simulation.logs.unwrap_or_default(),
+        }));
+        if let Some(err) = simulation.err {
+            bail!("simulation failed: {err}");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(signer_override: Option<Pubkey>) -> Context {
+        Context::new(
+            RpcClient::new("http://127.0.0.1:8899".into()),
+            PathBuf::from("/nonexistent/id.json"),
+            signer_override,
+            true,
+            OutputFormat::Json,
+        )
+    }
+
+    #[test]
+    fn signer_override_does_not_read_the_keypair() {
+        let signer = Pubkey::new_unique();
+        assert_eq!(context(Some(signer)).signer().unwrap(), signer);
+    }
+
+    #[test]
+    fn missing_keypair_fails_only_when_a_signer_is_needed() {
+        let err = context(None).signer().unwrap_err();
+        assert!(format!("{err:#}").contains("reading keypair /nonexistent/id.json"));
+    }
+}
@@ -0,0 +1,504 @@
+use anchor_lang::prelude::{pubkey, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::Discriminator;
+use anyhow::{bail, Context as _, Result};
+use farmer_core::states::{
+    CustomerConfirmation, FarmerAffiliation, Warehouse, WarehouseCustomer, WarehouseStaff,
+    WarehouseStatus, MAX_CONFIRM_BATCH, ROLE_ALL, ROLE_COMPLETE, ROLE_CONFIRM_CUSTOMERS,
+    ROLE_DISPATCH, ROLE_MANAGE, ROLE_QUOTE, ROLE_REFUND,
+};
+use farmer_core_client::instructions::WarehouseUpdate;
+use farmer_core_client::{accounts, envelope, instructions, pda, zip};
+use serde_json::{json, Value};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use super::customer::{confirmation_json, hex};
+use super::farmer::affiliation_json;
+use super::Context;
+use crate::cli::{
+    parse_hash, AffiliationCommand, StaffCommand, StaffRole, StatusArg, WarehouseCommand,
+    WarehouseCustomerCommand,
+};
+
+const ROLE_NAMES: [(u8, &str); 6] = [
+    (ROLE_CONFIRM_CUSTOMERS, "confirm-customers"),
+    (ROLE_QUOTE, "quote"),
+    (ROLE_DISPATCH, "dispatch"),
+    (ROLE_COMPLETE, "complete"),
+    (ROLE_REFUND, "refund"),
+    (ROLE_MANAGE, "manage"),
+];
+
+pub fn run(ctx: &Context, command: WarehouseCommand) -> Result<()> {
+    match command {
+        WarehouseCommand::Create {
+            id,
+            operator,
+            name,
+            pickup_notes,
+            fee_bps,
+            zip,
+            fee_rules_uri,
+        } => ctx.submit(&[instructions::create_warehouse(
+            &ctx.signer()?,
+            id,
+            operator,
+            name,
+            pickup_notes,
+            fee_bps,
+            zip,
+            fee_rules_uri,
+        )]),
+        WarehouseCommand::Update {
+            id,
+            name,
+            pickup_notes,
+            fee_bps,
+            zip,
+            clear_zip,
+            fee_rules_uri,
+            operator,
+            fee_receiver,
+            confirmation_validity,
+            encryption_key,
+            clear_encryption_key,
+            as_staff,
+        } => ctx.submit(&[instructions::update_warehouse(
+            &ctx.signer()?,
+            id,
+            as_staff,
+            WarehouseUpdate {
+                name,
+                pickup_notes,
+                fee_bps,
+                deliver_zip_prefixes: if clear_zip { Some(Vec::new()) } else { zip },
+                delivery_fee_rules_uri: fee_rules_uri,
+                operator,
+                fee_receiver,
+                confirmation_validity,
+                encryption_key: if clear_encryption_key {
+                    Some([0; 32])
+                } else {
+                    encryption_key
+                },
+            },
+        )]),
+        WarehouseCommand::Status { id, status } => {
+            let status = match status {
+                StatusArg::Active => WarehouseStatus::Active,
+                StatusArg::Suspended => WarehouseStatus::Suspended,
+                StatusArg::Closed => WarehouseStatus::Closed,
+            };
+            ctx.submit(&[instructions::set_warehouse_status(
+                &ctx.signer()?,
+                id,
+                status,
+            )])
+        }
+        WarehouseCommand::Staff(command) => run_staff(ctx, command),
+        WarehouseCommand::Affiliation(command) => run_affiliation(ctx, command),
+        WarehouseCommand::Customer(command) => run_customer(ctx, command),
+        WarehouseCommand::Find { zip } => {
+            let warehouses = ctx
+                .rpc
+                .program_accounts(&farmer_core::ID, &[(0, Warehouse::DISCRIMINATOR)])?
+                .into_iter()
+                .map(|(_, data)| accounts::decode_warehouse(&data))
+                .collect::<anchor_lang::Result<Vec<_>>>()?;
+            let mut serving: Vec<&Warehouse> = zip::serving(&warehouses, &zip).collect();
+            serving.sort_by_key(|warehouse| warehouse.warehouse_id);
+
+            ctx.print(&Value::Array(
+                serving.into_iter().map(warehouse_json).collect(),
+            ));
+            Ok(())
+        }
+        WarehouseCommand::Show { id } => {
+            let Some(warehouse) = accounts::fetch_warehouse(&ctx.rpc, id)? else {
+                bail!("warehouse {id} ({}) does not exist", pda::warehouse(id).0);
+            };
+            ctx.print(&warehouse_json(&warehouse));
+            Ok(())
+        }
+    }
+}
+
+fn run_staff(ctx: &Context, command: StaffCommand) -> Result<()> {
+    match command {
+        StaffCommand::Grant { id, member, roles } => {
+            ctx.submit(&[instructions::grant_staff_roles(
+                &ctx.signer()?,
+                id,
+                member,
+                role_mask(&roles),
+            )])
+        }
+        StaffCommand::Revoke { id, member, roles } => {
+            ctx.submit(&[instructions::revoke_staff_roles(
+                &ctx.signer()?,
+                id,
+                member,
+                role_mask(&roles),
+            )])
+        }
+        StaffCommand::List { id } => {
+            // `warehouse` is the first field, right after the discriminator
+            let warehouse = pda::warehouse(id).0;
+            let prefix = [WarehouseStaff::DISCRIMINATOR, warehouse.as_ref()].concat();
+            let mut entries = ctx
+                .rpc
+                .program_accounts(&farmer_core::ID, &[(0, &prefix)])?
+                .into_iter()
+                .map(|(_, data)| accounts::decode_warehouse_staff(&data))
+                .collect::<anchor_lang::Result<Vec<_>>>()?;
+            entries.sort_by_key(|entry| entry.member.to_string());
+
+            ctx.print(&Value::Array(entries.iter().map(staff_json).collect()));
+            Ok(())
+        }
+    }
+}
+
+fn run_affiliation(ctx: &Context, command: AffiliationCommand) -> Result<()> {
+    match command {
+        AffiliationCommand::Approve { id, farmer } => {
+            ctx.submit(&[instructions::approve_affiliation(
+                &ctx.signer()?,
+                id,
+                &farmer,
+            )])
+        }
+        AffiliationCommand::Reject { id, farmer } => {
+            ctx.submit(&[instructions::reject_affiliation(
+                &ctx.signer()?,
+                id,
+                &farmer,
+            )])
+        }
+        AffiliationCommand::Remove { id, farmer } => {
+            ctx.submit(&[instructions::end_affiliation(&ctx.signer()?, id, &farmer)])
+        }
+        AffiliationCommand::List { id } => {
+            // `warehouse` is the first field, right after the discriminator
+            let warehouse = pda::warehouse(id).0;
+            let prefix = [FarmerAffiliation::DISCRIMINATOR, warehouse.as_ref()].concat();
+            let mut entries = ctx
+                .rpc
+                .program_accounts(&farmer_core::ID, &[(0, &prefix)])?
+                .into_iter()
+                .map(|(_, data)| accounts::decode_farmer_affiliation(&data))
+                .collect::<anchor_lang::Result<Vec<_>>>()?;
+            entries.sort_by_key(|entry| entry.requested_at);
+
+            ctx.print(&Value::Array(
+                entries.iter().map(affiliation_json).collect(),
+            ));
+            Ok(())
+        }
+    }
+}
+
+fn run_customer(ctx: &Context, command: WarehouseCustomerCommand) -> Result<()> {
+    match command {
+        WarehouseCustomerCommand::Confirm {
+            id,
+            customer,
+            notes_hash,
+            valid_until,
+            envelope_hash,
+            as_staff,
+        } => ctx.submit(&[instructions::confirm_customer(
+            &ctx.signer()?,
+            id,
+            &customer,
+            as_staff,
+            notes_hash,
+            valid_until,
+            envelope_hash,
+        )]),
+        WarehouseCustomerCommand::ConfirmBatch {
+            id,
+            customers,
+            valid_until,
+            compute_units,
+            as_staff,
+        } => {
+            if customers.len() > MAX_CONFIRM_BATCH {
+                bail!(
+                    "{} customers given; a batch takes at most {MAX_CONFIRM_BATCH}",
+                    customers.len()
+                );
+            }
+            let entries: Vec<_> = customers
+                .into_iter()
+                .map(|(customer, envelope_hash)| {
+                    let confirmation = CustomerConfirmation {
+                        notes_hash: None,
+                        envelope_hash,
+                    };
+                    (customer, confirmation)
+                })
+                .collect();
+            ctx.submit(&confirm_batch(
+                &ctx.signer()?,
+                id,
+                as_staff,
+                valid_until,
+                compute_units,
+                &entries,
+            ))
+        }
+        WarehouseCustomerCommand::Renew {
+            id,
+            customer,
+            notes_hash,
+            valid_until,
+            as_staff,
+        } => ctx.submit(&[instructions::renew_confirmation(
+            &ctx.signer()?,
+            id,
+            &customer,
+            as_staff,
+            notes_hash,
+            valid_until,
+        )]),
+        WarehouseCustomerCommand::Revoke {
+            id,
+            customer,
+            as_staff,
+        } => ctx.submit(&[instructions::revoke_customer(
+            &ctx.signer()?,
+            id,
+            &customer,
+            as_staff,
+        )]),
+        WarehouseCustomerCommand::List { id } => {
+            // `warehouse` is the first field, right after the discriminator
+            let warehouse = pda::warehouse(id).0;
+            let prefix = [WarehouseCustomer::DISCRIMINATOR, warehouse.as_ref()].concat();
+            let mut entries = ctx
+                .rpc
+                .program_accounts(&farmer_core::ID, &[(0, &prefix)])?
+                .into_iter()
+                .map(|(_, data)| accounts::decode_warehouse_customer(&data))
+                .collect::<anchor_lang::Result<Vec<_>>>()?;
+            entries.sort_by_key(|entry| entry.requested_at);
+
+            ctx.print(&Value::Array(
+                entries.iter().map(confirmation_json).collect(),
+            ));
+            Ok(())
+        }
+        WarehouseCustomerCommand::Keygen { out } => {
+            let (secret_key, public_key) = envelope::generate_keypair();
+            write_secret(&out, &hex(&secret_key))?;
+            ctx.print(&json!({
+                "secret_key_file": out.display().to_string(),
+                "public_key": hex(&public_key),
+            }));
+            Ok(())
+        }
+        WarehouseCustomerCommand::Open {
+            id,
+            customer,
+            secret_key,
+            ciphertext,
+        } => {
+            let warehouse = pda::warehouse(id).0;
+            let Some(entry) = accounts::fetch_warehouse_customer(&ctx.rpc, &warehouse, &customer)?
+            else {
+                bail!("{customer} has not requested a confirmation from warehouse {id}");
+            };
+            let Some(address_envelope) = entry.address_envelope else {
+                bail!("{customer} did not send an address envelope");
+            };
+            let secret = fs::read_to_string(&secret_key)
+                .with_context(|| format!("reading {}", secret_key.display()))?;
+            let secret = parse_hash(secret.trim()).map_err(anyhow::Error::msg)?;
+            let ciphertext = fs::read(&ciphertext)
+                .with_context(|| format!("reading {}", ciphertext.display()))?;
+            let address = envelope::open(&secret, &address_envelope, &ciphertext)?;
+
+            ctx.print(&json!({
+                "address": String::from_utf8_lossy(&address),
+                "envelope_hash": hex(&address_envelope.ciphertext_hash),
+            }));
+            Ok(())
+        }
+    }
+}
+
+/// Writes a secret key readable only by the current user.
+fn write_secret(path: &Path, contents: &str) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("creating {}", path.display()))?;
+    file.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+/// The compute budget program; `SetComputeUnitLimit` is its instruction 2.
+const COMPUTE_BUDGET_PROGRAM_ID: Pubkey = pubkey!("ComputeBudget111111111111111111111111111111");
+
+/// `confirm_customers` behind a `SetComputeUnitLimit`, since a full batch can
+/// outgrow the default 200k units.
+fn confirm_batch(
+    signer: &Pubkey,
+    warehouse_id: u64,
+    as_staff: bool,
+    valid_until: Option<i64>,
+    compute_units: u32,
+    entries: &[(Pubkey, CustomerConfirmation)],
+) -> [Instruction; 2] {
+    let mut data = vec![2];
+    data.extend_from_slice(&compute_units.to_le_bytes());
+    [
+        Instruction {
+            program_id: COMPUTE_BUDGET_PROGRAM_ID,
+            accounts: vec![],
+            data,
+        },
+        instructions::confirm_customers(signer, warehouse_id, as_staff, valid_until, entries),
+    ]
+}
+
+pub fn role_mask(roles: &[StaffRole]) -> u8 {
+    roles.iter().fold(0, |mask, role| {
+        mask | match role {
+            StaffRole::ConfirmCustomers => ROLE_CONFIRM_CUSTOMERS,
+            StaffRole::Quote => ROLE_QUOTE,
+            StaffRole::Dispatch => ROLE_DISPATCH,
+            StaffRole::Complete => ROLE_COMPLETE,
+            StaffRole::Refund => ROLE_REFUND,
+            StaffRole::Manage => ROLE_MANAGE,
+            StaffRole::All => ROLE_ALL,
+        }
+    })
+}
+
+fn staff_json(staff: &WarehouseStaff) -> Value {
+    let roles: Vec<&str> = ROLE_NAMES
+        .iter()
+        .filter(|(bit, _)| staff.roles & bit != 0)
+        .map(|(_, name)| *name)
+        .collect();
+
+    json!({
+        "address": pda::warehouse_staff(&staff.warehouse, &staff.member).0.to_string(),
+        "member": staff.member.to_string(),
+        "roles": roles,
+    })
+}
+
+fn warehouse_json(warehouse: &Warehouse) -> Value {
+    json!({
+        "address": pda::warehouse(warehouse.warehouse_id).0.to_string(),
+        "warehouse_id": warehouse.warehouse_id,
+        "operator": warehouse.operator.to_string(),
+        "fee_receiver": warehouse.fee_receiver.to_string(),
+        "status": status_name(warehouse.status),
+        "name": warehouse.name,
+        "pickup_notes": warehouse.pickup_notes,
+        "fee_bps": warehouse.fee_bps,
+        "pending_fee_bps": warehouse.pending_fee_bps,
+        "fee_effective_at": (warehouse.pending_fee_bps.is_some()).then_some(warehouse.fee_effective_at),
+        "confirmation_validity": warehouse.confirmation_validity,
+        "encryption_key": warehouse.encryption_key.as_ref().map(|key| hex(key)),
+        "delivery_fee_rules_uri": warehouse.delivery_fee_rules_uri,
+        "deliver_zip_prefixes": warehouse
+            .deliver_zip_prefixes
+            .iter()
+            .map(zip::format)
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn status_name(status: WarehouseStatus) -> &'static str {
+    match status {
+        WarehouseStatus::Active => "active",
+        WarehouseStatus::Suspended => "suspended",
+        WarehouseStatus::Closed => "closed",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{parse_batch_entry, parse_zip_prefix};
+    use farmer_core::states::ZipPrefix;
+    use solana_hash::Hash;
+    use solana_keypair::Keypair;
+    use solana_signer::Signer;
+    use solana_transaction::Transaction;
+    #[test]
+    fn zip_prefix_keeps_leading_zeros() {
+        let zip = parse_zip_prefix("0123").unwrap();
+        assert_eq!(
+            zip,
+            ZipPrefix {
+                prefix: 123,
+                len: 4
+            }
+        );
+        assert_eq!(zip::format(&zip), "0123");
+        assert!(parse_zip_prefix("12a").is_err());
+        assert!(parse_zip_prefix("").is_err());
+        assert!(parse_zip_prefix("12").is_err());
+        assert!(parse_zip_prefix("123456").is_err());
+    }
+
+    #[test]
+    fn role_mask_combines_roles() {
+        assert_eq!(
+            role_mask(&[StaffRole::Quote, StaffRole::Dispatch]),
+            ROLE_QUOTE | ROLE_DISPATCH
+        );
+        assert_eq!(role_mask(&[StaffRole::All]), ROLE_ALL);
+    }
+
+    #[test]
+    fn batch_entry_takes_an_optional_envelope_hash() {
+        let customer = Pubkey::new_unique();
+        assert_eq!(
+            parse_batch_entry(&customer.to_string()).unwrap(),
+            (customer, None)
+        );
+        let entry = format!("{customer}:{}", "ab".repeat(32));
+        assert_eq!(
+            parse_batch_entry(&entry).unwrap(),
+            (customer, Some([0xab; 32]))
+        );
+        assert!(parse_batch_entry(&format!("{customer}:ab")).is_err());
+        assert!(parse_batch_entry("not-a-key").is_err());
+    }
+
+    #[test]
+    fn full_confirm_batch_fits_in_a_transaction() {
+        let signer = Keypair::new();
+        let entries: Vec<_> = (0..MAX_CONFIRM_BATCH)
+            .map(|_| (Pubkey::new_unique(), CustomerConfirmation::default()))
+            .collect();
+        let instructions = confirm_batch(
+            &signer.pubkey(),
+            u64::MAX,
+            true,
+            Some(i64::MAX),
+            400_000,
+            &entries,
+        );
+        let transaction = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&signer.pubkey()),
+            &[&signer],
+            Hash::default(),
+        );
+        // PACKET_DATA_SIZE
+        assert!(bincode::serialize(&transaction).unwrap().len() <= 1232);
+    }
+}
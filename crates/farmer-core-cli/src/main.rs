@@ -0,0 +1,40 @@
+//! `farmer-core-cli`: admin operations on the farmer-core program.
+//!
+//! Every transaction subcommand accepts `--dry-run` (simulate and print the
+//! transaction) and `--output json` (machine-readable output for scripts).
+//! The keypair is only read by commands that sign; `show`/`list` and
+//! `--dry-run --signer <PUBKEY>` work without one.
+
+use anyhow::Result;
+use clap::Parser;
+
+mod cli;
+mod commands;
+mod output;
+mod rpc;
+mod settings;
+
+use cli::{Cli, Command};
+use commands::Context;
+use rpc::RpcClient;
+use settings::Settings;
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let settings = Settings::resolve(&cli.global)?;
+    let ctx = Context::new(
+        RpcClient::new(settings.url),
+        settings.keypair,
+        cli.global.signer,
+        cli.global.dry_run,
+        cli.global.output,
+    );
+
+    match cli.command {
+        Command::Config(command) => commands::config::run(&ctx, command),
+        Command::Mint(command) => commands::mint::run(&ctx, command),
+        Command::Warehouse(command) => commands::warehouse::run(&ctx, command),
+        Command::Farmer(command) => commands::farmer::run(&ctx, command),
+        Command::Customer(command) => commands::customer::run(&ctx, command),
+    }
+}
@@ -0,0 +1,54 @@
+use serde_json::Value;
+
+use crate::cli::OutputFormat;
+
+/// Prints a command result as pretty JSON or as `key: value` lines.
+pub fn print(format: OutputFormat, value: &Value) {
+    match format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(value).expect("JSON value")
+            )
+        }
+        OutputFormat::Text => print_text(value, 0),
+    }
+}
+
+fn print_text(value: &Value, indent: usize) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                match field {
+                    Value::Object(_) | Value::Array(_) => {
+                        println!("{pad}{key}:");
+                        print_text(field, indent + 2);
+                    }
+                    _ => println!("{pad}{key}: {}", scalar(field)),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                if item.is_object() {
+                    if i > 0 {
+                        println!();
+                    }
+                    print_text(item, indent);
+                } else {
+                    println!("{pad}{}", scalar(item));
+                }
+            }
+        }
+        _ => println!("{pad}{}", scalar(value)),
+    }
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "-".to_string(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
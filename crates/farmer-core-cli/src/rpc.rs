@@ -0,0 +1,221 @@
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use farmer_core_client::accounts::AccountFetcher;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use solana_hash::Hash;
+use solana_transaction::Transaction;
+use std::str::FromStr;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+const CONFIRM_POLL: Duration = Duration::from_millis(500);
+
+/// Minimal blocking JSON-RPC client for the calls the CLI needs.
+pub struct RpcClient {
+    url: String,
+    agent: ureq::Agent,
+}
+
+#[derive(Deserialize)]
+struct Response<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    message: String,
+    data: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct WithContext<T> {
+    value: T,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockhashInfo {
+    blockhash: String,
+}
+
+#[derive(Deserialize)]
+struct AccountInfo {
+    data: (String, String),
+}
+
+#[derive(Deserialize)]
+struct KeyedAccount {
+    pubkey: String,
+    account: AccountInfo,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignatureStatus {
+    err: Option<Value>,
+    confirmation_status: Option<String>,
+}
+
+/// Result of `simulateTransaction`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Simulation {
+    pub err: Option<Value>,
+    pub logs: Option<Vec<String>>,
+    pub units_consumed: Option<u64>,
+}
+
+impl RpcClient {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .build(),
+        }
+    }
+
+    fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Response<T> = self
+            .agent
+            .post(&self.url)
+            .send_json(body)
+            .with_context(|| format!("{method} request to {}", self.url))?
+            .into_json()
+            .with_context(|| format!("decoding {method} response"))?;
+
+        if let Some(error) = response.error {
+            let logs = error
+                .data
+                .as_ref()
+                .and_then(|data| data.get("logs"))
+                .and_then(Value::as_array)
+                .map(|logs| {
+                    logs.iter()
+                        .filter_map(Value::as_str)
+                        .collect::<Vec<_>>()
+                        .join("\n  ")
+                })
+                .unwrap_or_default();
+            if logs.is_empty() {
+                bail!("{method} failed: {}", error.message);
+            }
+            bail!("{method} failed: {}\n  {logs}", error.message);
+        }
+        response
+            .result
+            .ok_or_else(|| anyhow!("{method} returned no result"))
+    }
+
+    pub fn latest_blockhash(&self) -> Result<Hash> {
+        let info: WithContext<BlockhashInfo> =
+            self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+        Hash::from_str(&info.value.blockhash).map_err(|e| anyhow!("bad blockhash: {e}"))
+    }
+
+    pub fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        let info: WithContext<Option<AccountInfo>> = self.call(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+        info.value.map(|account| decode_data(&account)).transpose()
+    }
+
+    /// Accounts owned by `program` whose data holds each `(offset, bytes)` of `filters`.
+    pub fn program_accounts(
+        &self,
+        program: &Pubkey,
+        filters: &[(usize, &[u8])],
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        let filters: Vec<Value> = filters
+            .iter()
+            .map(|(offset, bytes)| {
+                json!({ "memcmp": { "offset": offset, "bytes": BASE64.encode(bytes), "encoding": "base64" } })
+            })
+            .collect();
+        let accounts: Vec<KeyedAccount> = self.call(
+            "getProgramAccounts",
+            json!([program.to_string(), {
+                "encoding": "base64",
+                "commitment": "confirmed",
+                "filters": filters,
+            }]),
+        )?;
+        accounts
+            .into_iter()
+            .map(|keyed| {
+                let pubkey = Pubkey::from_str(&keyed.pubkey)?;
+                Ok((pubkey, decode_data(&keyed.account)?))
+            })
+            .collect()
+    }
+
+    pub fn simulate(&self, transaction: &Transaction) -> Result<Simulation> {
+        let simulation: WithContext<Simulation> = self.call(
+            "simulateTransaction",
+            json!([encode_transaction(transaction)?, {
+                "encoding": "base64",
+                "commitment": "confirmed",
+                "sigVerify": false,
+            }]),
+        )?;
+        Ok(simulation.value)
+    }
+
+    /// Sends the transaction and waits until it is confirmed.
+    pub fn send_and_confirm(&self, transaction: &Transaction) -> Result<String> {
+        let signature: String = self.call(
+            "sendTransaction",
+            json!([encode_transaction(transaction)?, {
+                "encoding": "base64",
+                "preflightCommitment": "confirmed",
+            }]),
+        )?;
+
+        let started = Instant::now();
+        while started.elapsed() < CONFIRM_TIMEOUT {
+            let statuses: WithContext<Vec<Option<SignatureStatus>>> =
+                self.call("getSignatureStatuses", json!([[signature]]))?;
+            if let Some(Some(status)) = statuses.value.first() {
+                if let Some(err) = &status.err {
+                    bail!("transaction {signature} failed: {err}");
+                }
+                if matches!(
+                    status.confirmation_status.as_deref(),
+                    Some("confirmed" | "finalized")
+                ) {
+                    return Ok(signature);
+                }
+            }
+            sleep(CONFIRM_POLL);
+        }
+        bail!("transaction {signature} was not confirmed in time")
+    }
+}
+
+impl AccountFetcher for RpcClient {
+    type Error = anyhow::Error;
+
+    fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        RpcClient::account_data(self, address)
+    }
+}
+
+pub fn encode_transaction(transaction: &Transaction) -> Result<String> {
+    Ok(BASE64.encode(bincode::serialize(transaction)?))
+}
+
+fn decode_data(account: &AccountInfo) -> Result<Vec<u8>> {
+    let (data, encoding) = &account.data;
+    if encoding != "base64" {
+        bail!("unexpected account encoding {encoding}");
+    }
+    Ok(BASE64.decode(data)?)
+}
@@ -0,0 +1,94 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use crate::cli::GlobalArgs;
+
+const DEFAULT_KEYPAIR: &str = "~/.config/solana/id.json";
+
+/// RPC URL and keypair path after applying flags over Anchor.toml.
+pub struct Settings {
+    pub url: String,
+    pub keypair: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct AnchorToml {
+    provider: Option<Provider>,
+}
+
+#[derive(Deserialize)]
+struct Provider {
+    cluster: Option<String>,
+    wallet: Option<String>,
+}
+
+impl Settings {
+    pub fn resolve(args: &GlobalArgs) -> Result<Self> {
+        let anchor = find_anchor_toml(&std::env::current_dir()?)
+            .map(|path| load_provider(&path).map(|provider| (path, provider)))
+            .transpose()?;
+
+        let cluster = args
+            .url
+            .clone()
+            .or_else(|| anchor.as_ref().and_then(|(_, p)| p.cluster.clone()))
+            .unwrap_or_else(|| "localnet".to_string());
+
+        let keypair = match &args.keypair {
+            Some(path) => path.clone(),
+            None => match &anchor {
+                Some((
+                    path,
+                    Provider {
+                        wallet: Some(wallet),
+                        ..
+                    },
+                )) => {
+                    // Relative wallet paths are relative to Anchor.toml
+                    path.parent().unwrap_or(Path::new(".")).join(expand(wallet))
+                }
+                _ => expand(DEFAULT_KEYPAIR),
+            },
+        };
+
+        Ok(Self {
+            url: cluster_url(&cluster),
+            keypair,
+        })
+    }
+}
+
+fn find_anchor_toml(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join("Anchor.toml"))
+        .find(|path| path.is_file())
+}
+
+fn load_provider(path: &Path) -> Result<Provider> {
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let parsed: AnchorToml =
+        toml::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+    Ok(parsed.provider.unwrap_or(Provider {
+        cluster: None,
+        wallet: None,
+    }))
+}
+
+fn expand(path: &str) -> PathBuf {
+    PathBuf::from(shellexpand::tilde(path).into_owned())
+}
+
+/// Maps Anchor cluster names to RPC URLs; anything else is used as a URL.
+pub fn cluster_url(cluster: &str) -> String {
+    match cluster {
+        "localnet" | "localhost" => "http://127.0.0.1:8899",
+        "devnet" => "https://api.devnet.solana.com",
+        "testnet" => "https://api.testnet.solana.com",
+        "mainnet" | "mainnet-beta" => "https://api.mainnet-beta.solana.com",
+        url => url,
+    }
+    .to_string()
+}
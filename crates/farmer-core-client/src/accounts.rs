@@ -0,0 +1,226 @@
+use anchor_lang::prelude::*;
+use anchor_lang::AccountDeserialize;
+use farmer_core::states::{
+    AllowedMint, CustomerProfile, FarmerAffiliation, FarmerProfile, LotOffer, ProgramConfig,
+    Warehouse, WarehouseCustomer, WarehouseStaff,
+};
+
+use crate::pda;
+
+// ============================================================================
+// ACCOUNT DECODING
+// ============================================================================
+
+/// Decodes raw account data, checking the Anchor discriminator.
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_program_config(data: &[u8]) -> Result<ProgramConfig> {
+    decode(data)
+}
+
+pub fn decode_allowed_mint(data: &[u8]) -> Result<AllowedMint> {
+    decode(data)
+}
+
+pub fn decode_warehouse(data: &[u8]) -> Result<Warehouse> {
+    decode(data)
+}
+
+pub fn decode_warehouse_staff(data: &[u8]) -> Result<WarehouseStaff> {
+    decode(data)
+}
+
+pub fn decode_farmer_profile(data: &[u8]) -> Result<FarmerProfile> {
+    decode(data)
+}
+
+pub fn decode_farmer_affiliation(data: &[u8]) -> Result<FarmerAffiliation> {
+    decode(data)
+}
+
+pub fn decode_lot_offer(data: &[u8]) -> Result<LotOffer> {
+    decode(data)
+}
+
+pub fn decode_customer_profile(data: &[u8]) -> Result<CustomerProfile> {
+    decode(data)
+}
+
+pub fn decode_warehouse_customer(data: &[u8]) -> Result<WarehouseCustomer> {
+    decode(data)
+}
+
+// ============================================================================
+// ACCOUNT FETCHING
+// ============================================================================
+// The client does not pick an RPC library. Implement `AccountFetcher` for the
+// one you use (or pass a closure) and the typed helpers below work with it.
+
+/// Source of raw account data, e.g. a wrapper around an RPC client.
+pub trait AccountFetcher {
+    type Error;
+
+    /// Returns the account's data, or `None` if the account does not exist.
+    fn account_data(&self, address: &Pubkey) -> std::result::Result<Option<Vec<u8>>, Self::Error>;
+}
+
+impl<F, E> AccountFetcher for F
+where
+    F: Fn(&Pubkey) -> std::result::Result<Option<Vec<u8>>, E>,
+{
+    type Error = E;
+
+    fn account_data(&self, address: &Pubkey) -> std::result::Result<Option<Vec<u8>>, E> {
+        self(address)
+    }
+}
+
+/// Why a fetch helper failed.
+#[derive(Debug)]
+pub enum FetchError<E> {
+    /// The fetcher itself failed
+    Fetch(E),
+    /// The account exists but is not the expected type
+    Decode(anchor_lang::error::Error),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for FetchError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FetchError::Fetch(err) => write!(f, "fetching account failed: {err}"),
+            FetchError::Decode(err) => write!(f, "decoding account failed: {err}"),
+        }
+    }
+}
+
+impl<E: std::fmt::Debug + std::fmt::Display> std::error::Error for FetchError<E> {}
+
+/// Fetches and decodes the account at `address`; `Ok(None)` if it does not exist.
+pub fn fetch<T, F>(
+    fetcher: &F,
+    address: &Pubkey,
+) -> std::result::Result<Option<T>, FetchError<F::Error>>
+where
+    T: AccountDeserialize,
+    F: AccountFetcher,
+{
+    match fetcher.account_data(address).map_err(FetchError::Fetch)? {
+        Some(data) => decode(&data).map(Some).map_err(FetchError::Decode),
+        None => Ok(None),
+    }
+}
+
+pub fn fetch_program_config<F: AccountFetcher>(
+    fetcher: &F,
+) -> std::result::Result<Option<ProgramConfig>, FetchError<F::Error>> {
+    fetch(fetcher, &pda::config().0)
+}
+
+/// Fetches the allowlist entry for `mint`; `Ok(None)` means the mint is not allowed.
+pub fn fetch_allowed_mint<F: AccountFetcher>(
+    fetcher: &F,
+    mint: &Pubkey,
+) -> std::result::Result<Option<AllowedMint>, FetchError<F::Error>> {
+    fetch(fetcher, &pda::allowed_mint(mint).0)
+}
+
+pub fn fetch_warehouse<F: AccountFetcher>(
+    fetcher: &F,
+    warehouse_id: u64,
+) -> std::result::Result<Option<Warehouse>, FetchError<F::Error>> {
+    fetch(fetcher, &pda::warehouse(warehouse_id).0)
+}
+
+/// Fetches `member`'s grant at `warehouse`; `Ok(None)` means no roles.
+pub fn fetch_warehouse_staff<F: AccountFetcher>(
+    fetcher: &F,
+    warehouse: &Pubkey,
+    member: &Pubkey,
+) -> std::result::Result<Option<WarehouseStaff>, FetchError<F::Error>> {
+    fetch(fetcher, &pda::warehouse_staff(warehouse, member).0)
+}
+
+/// Fetches the profile of the farmer key `farmer`; `Ok(None)` if not registered.
+pub fn fetch_farmer_profile<F: AccountFetcher>(
+    fetcher: &F,
+    farmer: &Pubkey,
+) -> std::result::Result<Option<FarmerProfile>, FetchError<F::Error>> {
+    fetch(fetcher, &pda::farmer(farmer).0)
+}
+
+/// Fetches `farmer`'s affiliation with `warehouse`; `Ok(None)` if never requested or ended.
+pub fn fetch_farmer_affiliation<F: AccountFetcher>(
+    fetcher: &F,
+    farmer: &Pubkey,
+    warehouse: &Pubkey,
+) -> std::result::Result<Option<FarmerAffiliation>, FetchError<F::Error>> {
+    fetch(fetcher, &pda::farmer_affiliation(farmer, warehouse).0)
+}
+
+pub fn fetch_lot_offer<F: AccountFetcher>(
+    fetcher: &F,
+    farmer: &Pubkey,
+    offer_id: u64,
+) -> std::result::Result<Option<LotOffer>, FetchError<F::Error>> {
+    fetch(fetcher, &pda::offer(farmer, offer_id).0)
+}
+
+/// Fetches the profile of the customer key `customer`; `Ok(None)` if not registered.
+pub fn fetch_customer_profile<F: AccountFetcher>(
+    fetcher: &F,
+    customer: &Pubkey,
+) -> std::result::Result<Option<CustomerProfile>, FetchError<F::Error>> {
+    fetch(fetcher, &pda::customer(customer).0)
+}
+
+/// Fetches `customer`'s confirmation at `warehouse`; `Ok(None)` if never requested.
+pub fn fetch_warehouse_customer<F: AccountFetcher>(
+    fetcher: &F,
+    warehouse: &Pubkey,
+    customer: &Pubkey,
+) -> std::result::Result<Option<WarehouseCustomer>, FetchError<F::Error>> {
+    fetch(fetcher, &pda::warehouse_customer(warehouse, customer).0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn allowed_mint_data(mint: Pubkey) -> Vec<u8> {
+        let entry = AllowedMint {
+            mint,
+            decimals: 6,
+            min_order_subtotal: 100,
+            max_order_subtotal: 1_000_000,
+            service_fee_floor: Some(5),
+            bump: pda::allowed_mint(&mint).1,
+        };
+        let mut data = Vec::new();
+        entry.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn fetches_through_a_closure() {
+        let mint = Pubkey::new_unique();
+        let store = HashMap::from([(pda::allowed_mint(&mint).0, allowed_mint_data(mint))]);
+        let fetcher = |address: &Pubkey| Ok::<_, ()>(store.get(address).cloned());
+
+        let entry = fetch_allowed_mint(&fetcher, &mint).unwrap().unwrap();
+        assert_eq!(entry.mint, mint);
+        assert_eq!(entry.service_fee_floor, Some(5));
+
+        assert!(fetch_allowed_mint(&fetcher, &Pubkey::new_unique())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn rejects_the_wrong_account_type() {
+        let data = allowed_mint_data(Pubkey::new_unique());
+        assert!(decode_program_config(&data).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+use std::fmt;
+
+use crypto_box::aead::OsRng;
+use crypto_box::{PublicKey, SecretKey};
+use farmer_core::states::AddressEnvelope;
+use sha2::{Digest, Sha256};
+
+// ============================================================================
+// ADDRESS ENVELOPES
+// ============================================================================
+// A customer's delivery address, sealed to the warehouse's X25519
+// `Warehouse.encryption_key` as a libsodium sealed box (X25519 +
+// XSalsa20-Poly1305 with an ephemeral sender key). The ciphertext is stored
+// off-chain at `AddressEnvelope.uri`; the program only keeps its SHA-256, and
+// `confirm_customer` must repeat that hash.
+
+/// Why an envelope could not be sealed or opened.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnvelopeError {
+    /// The ciphertext's SHA-256 is not `AddressEnvelope.ciphertext_hash`
+    HashMismatch,
+    /// The envelope was sealed to another warehouse key
+    KeyMismatch,
+    /// Encryption failed, or the ciphertext is corrupt or not for this key
+    Crypto,
+}
+
+impl fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::HashMismatch => "ciphertext does not match the envelope hash",
+            Self::KeyMismatch => "envelope is sealed to another encryption key",
+            Self::Crypto => "address could not be sealed or opened",
+        })
+    }
+}
+
+impl std::error::Error for EnvelopeError {}
+
+/// An address sealed to a warehouse key. Store `ciphertext` somewhere the
+/// warehouse can fetch it, then pass [`SealedAddress::envelope`] to
+/// `request_customer_confirmation`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SealedAddress {
+    pub ciphertext: Vec<u8>,
+    pub ciphertext_hash: [u8; 32],
+    pub encryption_key: [u8; 32],
+}
+
+impl SealedAddress {
+    /// The on-chain pointer once `ciphertext` is stored at `uri`.
+    pub fn envelope(&self, uri: String) -> AddressEnvelope {
+        AddressEnvelope {
+            uri,
+            ciphertext_hash: self.ciphertext_hash,
+            encryption_key: self.encryption_key,
+        }
+    }
+}
+
+/// A new warehouse key pair as `(secret, public)`. Publish the public key with
+/// `update_warehouse`; the secret never leaves the warehouse.
+pub fn generate_keypair() -> ([u8; 32], [u8; 32]) {
+    let secret = SecretKey::generate(&mut OsRng);
+    (secret.to_bytes(), secret.public_key().to_bytes())
+}
+
+/// The public key for a warehouse secret key.
+pub fn public_key(secret_key: &[u8; 32]) -> [u8; 32] {
+    SecretKey::from_bytes(*secret_key).public_key().to_bytes()
+}
+
+/// SHA-256 of a ciphertext, as stored in `AddressEnvelope.ciphertext_hash`.
+pub fn ciphertext_hash(ciphertext: &[u8]) -> [u8; 32] {
+    Sha256::digest(ciphertext).into()
+}
+
+/// Seals `address` to `encryption_key` (the warehouse's published key). Only
+/// the warehouse's secret key opens the result, not even the sender's.
+pub fn seal(encryption_key: &[u8; 32], address: &[u8]) -> Result<SealedAddress, EnvelopeError> {
+    let ciphertext = PublicKey::from_bytes(*encryption_key)
+        .seal(&mut OsRng, address)
+        .map_err(|_| EnvelopeError::Crypto)?;
+    Ok(SealedAddress {
+        ciphertext_hash: ciphertext_hash(&ciphertext),
+        ciphertext,
+        encryption_key: *encryption_key,
+    })
+}
+
+/// Opens the ciphertext fetched from `envelope.uri` with the warehouse's
+/// secret key, after checking it is the one the envelope commits to.
+pub fn open(
+    secret_key: &[u8; 32],
+    envelope: &AddressEnvelope,
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, EnvelopeError> {
+    if ciphertext_hash(ciphertext) != envelope.ciphertext_hash {
+        return Err(EnvelopeError::HashMismatch);
+    }
+    let secret = SecretKey::from_bytes(*secret_key);
+    if secret.public_key().to_bytes() != envelope.encryption_key {
+        return Err(EnvelopeError::KeyMismatch);
+    }
+    secret.unseal(ciphertext).map_err(|_| EnvelopeError::Crypto)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &[u8] = b"Rua das Flores 12, 01234-000";
+
+    #[test]
+    fn sealed_address_opens_with_warehouse_key_only() {
+        let (secret, public) = generate_keypair();
+        assert_eq!(public_key(&secret), public);
+
+        let sealed = seal(&public, ADDRESS).unwrap();
+        assert_ne!(sealed.ciphertext, ADDRESS);
+        let envelope = sealed.envelope("ipfs://bafyaddress".to_string());
+        assert_eq!(
+            envelope.ciphertext_hash,
+            ciphertext_hash(&sealed.ciphertext)
+        );
+        assert_eq!(envelope.encryption_key, public);
+        assert_eq!(
+            open(&secret, &envelope, &sealed.ciphertext).unwrap(),
+            ADDRESS
+        );
+
+        let (other_secret, _) = generate_keypair();
+        assert_eq!(
+            open(&other_secret, &envelope, &sealed.ciphertext),
+            Err(EnvelopeError::KeyMismatch)
+        );
+    }
+
+    #[test]
+    fn open_rejects_ciphertext_not_committed_to() {
+        let (secret, public) = generate_keypair();
+        let sealed = seal(&public, ADDRESS).unwrap();
+        let envelope = sealed.envelope("https://example.com/a".to_string());
+
+        let mut tampered = sealed.ciphertext.clone();
+        tampered[40] ^= 1;
+        assert_eq!(
+            open(&secret, &envelope, &tampered),
+            Err(EnvelopeError::HashMismatch)
+        );
+
+        // Matching hash but corrupt contents still fail authentication
+        let forged = AddressEnvelope {
+            ciphertext_hash: ciphertext_hash(&tampered),
+            ..envelope
+        };
+        assert_eq!(
+            open(&secret, &forged, &tampered),
+            Err(EnvelopeError::Crypto)
+        );
+    }
+}
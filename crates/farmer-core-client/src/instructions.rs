@@ -0,0 +1,848 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{system_program, InstructionData};
+use farmer_core::states::{AddressEnvelope, CustomerConfirmation, WarehouseStatus, ZipPrefix};
+use farmer_core::{accounts, instruction};
+
+use crate::pda;
+
+// ============================================================================
+// INSTRUCTION BUILDERS
+// ============================================================================
+// One builder per program instruction. PDAs are derived here; callers only pass
+// the signers and wallets the instruction is about.
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: farmer_core::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// `init_config`, signed by the program's upgrade authority (the new admin).
+pub fn init_config(admin: &Pubkey, logistics_wallet: Pubkey, pause_flags: u8) -> Instruction {
+    build(
+        accounts::InitConfig {
+            config: pda::config().0,
+            admin: *admin,
+            program: farmer_core::ID,
+            program_data: pda::program_data().0,
+            system_program: system_program::ID,
+        },
+        instruction::InitConfig {
+            logistics_wallet,
+            pause_flags,
+        },
+    )
+}
+
+/// `update_config`; `None` keeps the stored value.
+pub fn update_config(
+    admin: &Pubkey,
+    logistics_wallet: Option<Pubkey>,
+    fee_notice_period: Option<i64>,
+    protocol_fee_bps: Option<u16>,
+) -> Instruction {
+    build(
+        accounts::UpdateConfig {
+            config: pda::config().0,
+            admin: *admin,
+        },
+        instruction::UpdateConfig {
+            logistics_wallet,
+            fee_notice_period,
+            protocol_fee_bps,
+        },
+    )
+}
+
+/// `propose_admin`, signed by the current admin.
+pub fn propose_admin(admin: &Pubkey, new_admin: Pubkey) -> Instruction {
+    build(
+        accounts::ProposeAdmin {
+            config: pda::config().0,
+            admin: *admin,
+        },
+        instruction::ProposeAdmin { new_admin },
+    )
+}
+
+/// `accept_admin`, signed by the proposed admin.
+pub fn accept_admin(new_admin: &Pubkey) -> Instruction {
+    build(
+        accounts::AcceptAdmin {
+            config: pda::config().0,
+            new_admin: *new_admin,
+        },
+        instruction::AcceptAdmin {},
+    )
+}
+
+/// `cancel_admin_transfer`, signed by the current admin.
+pub fn cancel_admin_transfer(admin: &Pubkey) -> Instruction {
+    build(
+        accounts::CancelAdminTransfer {
+            config: pda::config().0,
+            admin: *admin,
+        },
+        instruction::CancelAdminTransfer {},
+    )
+}
+
+/// `set_pause_flags` with a mask of `PAUSE_*` bits.
+pub fn set_pause_flags(admin: &Pubkey, pause_flags: u8) -> Instruction {
+    build(
+        accounts::SetPauseFlags {
+            config: pda::config().0,
+            admin: *admin,
+        },
+        instruction::SetPauseFlags { pause_flags },
+    )
+}
+
+/// `add_allowed_mint`; the admin pays for the `AllowedMint` PDA.
+pub fn add_allowed_mint(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    min_order_subtotal: u64,
+    max_order_subtotal: u64,
+    service_fee_floor: Option<u64>,
+) -> Instruction {
+    build(
+        accounts::AddAllowedMint {
+            config: pda::config().0,
+            allowed_mint: pda::allowed_mint(mint).0,
+            mint: *mint,
+            admin: *admin,
+            system_program: system_program::ID,
+        },
+        instruction::AddAllowedMint {
+            min_order_subtotal,
+            max_order_subtotal,
+            service_fee_floor,
+        },
+    )
+}
+
+/// `remove_allowed_mint`; the rent goes back to the admin.
+pub fn remove_allowed_mint(admin: &Pubkey, mint: &Pubkey) -> Instruction {
+    build(
+        accounts::RemoveAllowedMint {
+            config: pda::config().0,
+            allowed_mint: pda::allowed_mint(mint).0,
+            admin: *admin,
+        },
+        instruction::RemoveAllowedMint {},
+    )
+}
+
+/// `migrate_allowed_mints` for the first `mints.len()` legacy entries, all
+/// with the same limits.
+///
+/// `mints` must list those entries' mints in the order they are stored; each
+/// mint account is passed ahead of its `AllowedMint` PDA.
+pub fn migrate_allowed_mints(
+    admin: &Pubkey,
+    mints: &[Pubkey],
+    min_order_subtotal: u64,
+    max_order_subtotal: u64,
+    service_fee_floor: Option<u64>,
+) -> Instruction {
+    let mut ix = build(
+        accounts::MigrateAllowedMints {
+            config: pda::config().0,
+            admin: *admin,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateAllowedMints {
+            min_order_subtotal,
+            max_order_subtotal,
+            service_fee_floor,
+        },
+    );
+    ix.accounts.extend(mints.iter().flat_map(|mint| {
+        [
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(pda::allowed_mint(mint).0, false),
+        ]
+    }));
+    ix
+}
+
+/// `migrate_config`; the admin pays rent for any extra space.
+pub fn migrate_config(admin: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateConfig {
+            config: pda::config().0,
+            admin: *admin,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateConfig {},
+    )
+}
+
+/// `create_warehouse`; the admin pays for the `Warehouse` PDA.
+#[allow(clippy::too_many_arguments)]
+pub fn create_warehouse(
+    admin: &Pubkey,
+    warehouse_id: u64,
+    operator: Pubkey,
+    name: String,
+    pickup_notes: String,
+    fee_bps: u16,
+    deliver_zip_prefixes: Vec<ZipPrefix>,
+    delivery_fee_rules_uri: Option<String>,
+) -> Instruction {
+    build(
+        accounts::CreateWarehouse {
+            config: pda::config().0,
+            warehouse: pda::warehouse(warehouse_id).0,
+            admin: *admin,
+            system_program: system_program::ID,
+        },
+        instruction::CreateWarehouse {
+            warehouse_id,
+            operator,
+            name,
+            pickup_notes,
+            fee_bps,
+            deliver_zip_prefixes,
+            delivery_fee_rules_uri,
+        },
+    )
+}
+
+/// Fields to change with `update_warehouse`; `None` keeps the stored value.
+#[derive(Clone, Debug, Default)]
+pub struct WarehouseUpdate {
+    pub name: Option<String>,
+    pub pickup_notes: Option<String>,
+    pub fee_bps: Option<u16>,
+    pub deliver_zip_prefixes: Option<Vec<ZipPrefix>>,
+    /// `Some("")` clears the URI
+    pub delivery_fee_rules_uri: Option<String>,
+    pub operator: Option<Pubkey>,
+    pub fee_receiver: Option<Pubkey>,
+    /// Seconds; `Some(0)` removes the default so confirmations do not expire
+    pub confirmation_validity: Option<i64>,
+    /// X25519 public key for address envelopes; `Some([0; 32])` removes it
+    pub encryption_key: Option<[u8; 32]>,
+}
+
+/// `update_warehouse`, signed by the warehouse operator or the admin, or by
+/// staff holding `ROLE_MANAGE` with `as_staff` set (passes their grant PDA).
+pub fn update_warehouse(
+    authority: &Pubkey,
+    warehouse_id: u64,
+    as_staff: bool,
+    update: WarehouseUpdate,
+) -> Instruction {
+    let warehouse = pda::warehouse(warehouse_id).0;
+    build(
+        accounts::UpdateWarehouse {
+            config: pda::config().0,
+            warehouse,
+            staff: as_staff.then(|| pda::warehouse_staff(&warehouse, authority).0),
+            authority: *authority,
+        },
+        instruction::UpdateWarehouse {
+            name: update.name,
+            pickup_notes: update.pickup_notes,
+            fee_bps: update.fee_bps,
+            deliver_zip_prefixes: update.deliver_zip_prefixes,
+            delivery_fee_rules_uri: update.delivery_fee_rules_uri,
+            operator: update.operator,
+            fee_receiver: update.fee_receiver,
+            confirmation_validity: update.confirmation_validity,
+            encryption_key: update.encryption_key,
+        },
+    )
+}
+
+/// `set_warehouse_status`, signed by the admin.
+pub fn set_warehouse_status(
+    admin: &Pubkey,
+    warehouse_id: u64,
+    status: WarehouseStatus,
+) -> Instruction {
+    build(
+        accounts::SetWarehouseStatus {
+            config: pda::config().0,
+            warehouse: pda::warehouse(warehouse_id).0,
+            admin: *admin,
+        },
+        instruction::SetWarehouseStatus { status },
+    )
+}
+
+/// `grant_staff_roles`, signed by the warehouse operator or the admin.
+pub fn grant_staff_roles(
+    authority: &Pubkey,
+    warehouse_id: u64,
+    member: Pubkey,
+    roles: u8,
+) -> Instruction {
+    let warehouse = pda::warehouse(warehouse_id).0;
+    build(
+        accounts::GrantStaffRoles {
+            config: pda::config().0,
+            warehouse,
+            staff: pda::warehouse_staff(&warehouse, &member).0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::GrantStaffRoles { member, roles },
+    )
+}
+
+/// `revoke_staff_roles`, signed by the warehouse operator or the admin.
+pub fn revoke_staff_roles(
+    authority: &Pubkey,
+    warehouse_id: u64,
+    member: Pubkey,
+    roles: u8,
+) -> Instruction {
+    let warehouse = pda::warehouse(warehouse_id).0;
+    build(
+        accounts::RevokeStaffRoles {
+            config: pda::config().0,
+            warehouse,
+            staff: pda::warehouse_staff(&warehouse, &member).0,
+            authority: *authority,
+        },
+        instruction::RevokeStaffRoles { member, roles },
+    )
+}
+
+/// `register_farmer`, signed by the farmer (pays for the profile).
+pub fn register_farmer(
+    farmer: &Pubkey,
+    display_name: String,
+    public_profile_uri: String,
+) -> Instruction {
+    build(
+        accounts::RegisterFarmer {
+            config: pda::config().0,
+            farmer_profile: pda::farmer(farmer).0,
+            authority: *farmer,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterFarmer {
+            display_name,
+            public_profile_uri,
+        },
+    )
+}
+
+/// Fields to change with `update_farmer_profile`; `None` keeps the stored value.
+#[derive(Clone, Debug, Default)]
+pub struct FarmerProfileUpdate {
+    pub display_name: Option<String>,
+    /// `Some("")` clears the URI
+    pub public_profile_uri: Option<String>,
+    pub payout_wallet: Option<Pubkey>,
+    /// `Some(Pubkey::default())` removes the delegate
+    pub delegate: Option<Pubkey>,
+}
+
+/// `update_farmer_profile`, signed by the farmer (not the delegate).
+pub fn update_farmer_profile(farmer: &Pubkey, update: FarmerProfileUpdate) -> Instruction {
+    build(
+        accounts::UpdateFarmerProfile {
+            farmer_profile: pda::farmer(farmer).0,
+            authority: *farmer,
+        },
+        instruction::UpdateFarmerProfile {
+            display_name: update.display_name,
+            public_profile_uri: update.public_profile_uri,
+            payout_wallet: update.payout_wallet,
+            delegate: update.delegate,
+        },
+    )
+}
+
+/// `close_farmer_profile`, signed by the farmer (receives the rent).
+pub fn close_farmer_profile(farmer: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseFarmerProfile {
+            farmer_profile: pda::farmer(farmer).0,
+            authority: *farmer,
+        },
+        instruction::CloseFarmerProfile {},
+    )
+}
+
+/// `request_warehouse_affiliation`, signed by the farmer (pays for the affiliation).
+pub fn request_warehouse_affiliation(farmer: &Pubkey, warehouse_id: u64) -> Instruction {
+    let warehouse = pda::warehouse(warehouse_id).0;
+    build(
+        accounts::RequestWarehouseAffiliation {
+            config: pda::config().0,
+            farmer_profile: pda::farmer(farmer).0,
+            warehouse,
+            affiliation: pda::farmer_affiliation(farmer, &warehouse).0,
+            authority: *farmer,
+            system_program: system_program::ID,
+        },
+        instruction::RequestWarehouseAffiliation {},
+    )
+}
+
+/// `approve_affiliation`, signed by the warehouse operator.
+pub fn approve_affiliation(operator: &Pubkey, warehouse_id: u64, farmer: &Pubkey) -> Instruction {
+    let warehouse = pda::warehouse(warehouse_id).0;
+    build(
+        accounts::ApproveAffiliation {
+            config: pda::config().0,
+            affiliation: pda::farmer_affiliation(farmer, &warehouse).0,
+            warehouse,
+            operator: *operator,
+        },
+        instruction::ApproveAffiliation {},
+    )
+}
+
+/// `reject_affiliation`, signed by the warehouse operator.
+pub fn reject_affiliation(operator: &Pubkey, warehouse_id: u64, farmer: &Pubkey) -> Instruction {
+    let warehouse = pda::warehouse(warehouse_id).0;
+    build(
+        accounts::RejectAffiliation {
+            affiliation: pda::farmer_affiliation(farmer, &warehouse).0,
+            warehouse,
+            operator: *operator,
+        },
+        instruction::RejectAffiliation {},
+    )
+}
+
+/// `end_affiliation`, signed by the farmer or the warehouse operator; the rent goes to the farmer.
+pub fn end_affiliation(authority: &Pubkey, warehouse_id: u64, farmer: &Pubkey) -> Instruction {
+    let warehouse = pda::warehouse(warehouse_id).0;
+    build(
+        accounts::EndAffiliation {
+            affiliation: pda::farmer_affiliation(farmer, &warehouse).0,
+            warehouse,
+            farmer: *farmer,
+            authority: *authority,
+        },
+        instruction::EndAffiliation {},
+    )
+}
+
+/// Public fields of a new offer, for `publish_offer`.
+#[derive(Clone, Debug, Default)]
+pub struct NewOffer {
+    pub crop_name: String,
+    pub cultivar_name: Option<String>,
+    pub unit_code: u16,
+    /// Quantity in base units
+    pub qty: u64,
+    /// Price per base unit, in the mint's minor units
+    pub price_minor: u64,
+    pub expires_at: Option<i64>,
+    pub notes_public: Option<String>,
+}
+
+/// `publish_offer`, signed by the farmer or its delegate (pays for the offer).
+///
+/// `offer_id` must be the profile's current `offer_counter`; it is the seed of
+/// the new offer's address.
+pub fn publish_offer(
+    authority: &Pubkey,
+    farmer: &Pubkey,
+    warehouse_id: u64,
+    mint: &Pubkey,
+    offer_id: u64,
+    offer: NewOffer,
+) -> Instruction {
+    let warehouse = pda::warehouse(warehouse_id).0;
+    build(
+        accounts::PublishOffer {
+            config: pda::config().0,
+            farmer_profile: pda::farmer(farmer).0,
+            affiliation: pda::farmer_affiliation(farmer, &warehouse).0,
+            warehouse,
+            allowed_mint: pda::allowed_mint(mint).0,
+            offer: pda::offer(farmer, offer_id).0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::PublishOffer {
+            crop_name: offer.crop_name,
+            cultivar_name: offer.cultivar_name,
+            unit_code: offer.unit_code,
+            qty: offer.qty,
+            price_minor: offer.price_minor,
+            expires_at: offer.expires_at,
+            notes_public: offer.notes_public,
+        },
+    )
+}
+
+/// `deactivate_offer`, signed by the farmer or its delegate; `warehouse` is the
+/// offer's `LotOffer::warehouse`.
+pub fn deactivate_offer(
+    authority: &Pubkey,
+    farmer: &Pubkey,
+    offer_id: u64,
+    warehouse: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::DeactivateOffer {
+            farmer_profile: pda::farmer(farmer).0,
+            affiliation: pda::farmer_affiliation(farmer, warehouse).0,
+            offer: pda::offer(farmer, offer_id).0,
+            authority: *authority,
+        },
+        instruction::DeactivateOffer {},
+    )
+}
+
+/// `close_offer`, signed by the farmer; `rent_payer` is the offer's
+/// `LotOffer::rent_payer` and receives the rent.
+pub fn close_offer(farmer: &Pubkey, offer_id: u64, rent_payer: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseOffer {
+            farmer_profile: pda::farmer(farmer).0,
+            offer: pda::offer(farmer, offer_id).0,
+            rent_payer: *rent_payer,
+            authority: *farmer,
+        },
+        instruction::CloseOffer {},
+    )
+}
+
+/// `register_customer`, signed by the customer (pays for the profile).
+pub fn register_customer(customer: &Pubkey, public_profile_uri: String) -> Instruction {
+    build(
+        accounts::RegisterCustomer {
+            config: pda::config().0,
+            customer_profile: pda::customer(customer).0,
+            authority: *customer,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterCustomer { public_profile_uri },
+    )
+}
+
+/// `request_customer_confirmation`, signed by the customer (pays for the request).
+/// See [`crate::envelope`] for sealing the address the envelope points to.
+pub fn request_customer_confirmation(
+    customer: &Pubkey,
+    warehouse_id: u64,
+    address_envelope: Option<AddressEnvelope>,
+) -> Instruction {
+    let warehouse = pda::warehouse(warehouse_id).0;
+    build(
+        accounts::RequestCustomerConfirmation {
+            config: pda::config().0,
+            customer_profile: pda::customer(customer).0,
+            warehouse,
+            warehouse_customer: pda::warehouse_customer(&warehouse, customer).0,
+            authority: *customer,
+            system_program: system_program::ID,
+        },
+        instruction::RequestCustomerConfirmation { address_envelope },
+    )
+}
+
+/// `confirm_customer`, signed by the warehouse operator, or by staff holding
+/// `ROLE_CONFIRM_CUSTOMERS` with `as_staff` set (passes their grant PDA).
+/// `valid_until: None` applies the warehouse's default validity. `envelope_hash`
+/// must be the request's `address_envelope.ciphertext_hash`, if it has one.
+pub fn confirm_customer(
+    authority: &Pubkey,
+    warehouse_id: u64,
+    customer: &Pubkey,
+    as_staff: bool,
+    notes_hash: Option<[u8; 32]>,
+    valid_until: Option<i64>,
+    envelope_hash: Option<[u8; 32]>,
+) -> Instruction {
+    let warehouse = pda::warehouse(warehouse_id).0;
+    build(
+        accounts::ConfirmCustomer {
+            config: pda::config().0,
+            warehouse,
+            staff: as_staff.then(|| pda::warehouse_staff(&warehouse, authority).0),
+            warehouse_customer: pda::warehouse_customer(&warehouse, customer).0,
+            authority: *authority,
+        },
+        instruction::ConfirmCustomer {
+            notes_hash,
+            valid_until,
+            envelope_hash,
+        },
+    )
+}
+
+/// `confirm_customers`, signed like [`confirm_customer`], for up to
+/// `MAX_CONFIRM_BATCH` pending `(customer, confirmation)` pairs. Each
+/// customer's `WarehouseCustomer` PDA is appended in the same order.
+pub fn confirm_customers(
+    authority: &Pubkey,
+    warehouse_id: u64,
+    as_staff: bool,
+    valid_until: Option<i64>,
+    entries: &[(Pubkey, CustomerConfirmation)],
+) -> Instruction {
+    let warehouse = pda::warehouse(warehouse_id).0;
+    let mut ix = build(
+        accounts::ConfirmCustomers {
+            config: pda::config().0,
+            warehouse,
+            staff: as_staff.then(|| pda::warehouse_staff(&warehouse, authority).0),
+            authority: *authority,
+        },
+        instruction::ConfirmCustomers {
+            valid_until,
+            confirmations: entries
+                .iter()
+                .map(|(_, confirmation)| *confirmation)
+                .collect(),
+        },
+    );
+    ix.accounts.extend(entries.iter().map(|(customer, _)| {
+        AccountMeta::new(pda::warehouse_customer(&warehouse, customer).0, false)
+    }));
+    ix
+}
+
+/// `renew_confirmation`, signed like [`confirm_customer`]. `notes_hash: None`
+/// keeps the stored hash.
+pub fn renew_confirmation(
+    authority: &Pubkey,
+    warehouse_id: u64,
+    customer: &Pubkey,
+    as_staff: bool,
+    notes_hash: Option<[u8; 32]>,
+    valid_until: Option<i64>,
+) -> Instruction {
+    let warehouse = pda::warehouse(warehouse_id).0;
+    build(
+        accounts::RenewConfirmation {
+            config: pda::config().0,
+            warehouse,
+            staff: as_staff.then(|| pda::warehouse_staff(&warehouse, authority).0),
+            warehouse_customer: pda::warehouse_customer(&warehouse, customer).0,
+            authority: *authority,
+        },
+        instruction::RenewConfirmation {
+            notes_hash,
+            valid_until,
+        },
+    )
+}
+
+/// `revoke_customer`, signed like [`confirm_customer`].
+pub fn revoke_customer(
+    authority: &Pubkey,
+    warehouse_id: u64,
+    customer: &Pubkey,
+    as_staff: bool,
+) -> Instruction {
+    let warehouse = pda::warehouse(warehouse_id).0;
+    build(
+        accounts::RevokeCustomer {
+            warehouse,
+            staff: as_staff.then(|| pda::warehouse_staff(&warehouse, authority).0),
+            warehouse_customer: pda::warehouse_customer(&warehouse, customer).0,
+            authority: *authority,
+        },
+        instruction::RevokeCustomer {},
+    )
+}
+
+/// `withdraw_from_warehouse`, signed by the customer (receives the rent).
+pub fn withdraw_from_warehouse(customer: &Pubkey, warehouse_id: u64) -> Instruction {
+    let warehouse = pda::warehouse(warehouse_id).0;
+    build(
+        accounts::WithdrawFromWarehouse {
+            customer_profile: pda::customer(customer).0,
+            warehouse,
+            warehouse_customer: pda::warehouse_customer(&warehouse, customer).0,
+            authority: *customer,
+        },
+        instruction::WithdrawFromWarehouse {},
+    )
+}
+
+/// `close_customer_profile`, signed by the customer (receives the rent).
+pub fn close_customer_profile(customer: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseCustomerProfile {
+            customer_profile: pda::customer(customer).0,
+            authority: *customer,
+        },
+        instruction::CloseCustomerProfile {},
+    )
+}
+
+/// `check_coverage`; simulate it and read the `bool` from the return data.
+pub fn check_coverage(warehouse_id: u64, zip_prefix: ZipPrefix) -> Instruction {
+    build(
+        accounts::CheckCoverage {
+            warehouse: pda::warehouse(warehouse_id).0,
+        },
+        instruction::CheckCoverage { zip_prefix },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    #[test]
+    fn init_config_matches_program_layout() {
+        let admin = Pubkey::new_unique();
+        let wallet = Pubkey::new_unique();
+        let ix = init_config(&admin, wallet, 0x02);
+
+        assert_eq!(ix.program_id, farmer_core::ID);
+        assert!(ix.data.starts_with(instruction::InitConfig::DISCRIMINATOR));
+        let args = instruction::InitConfig::try_from_slice(
+            &ix.data[instruction::InitConfig::DISCRIMINATOR.len()..],
+        )
+        .unwrap();
+        assert_eq!(args.logistics_wallet, wallet);
+        assert_eq!(args.pause_flags, 0x02);
+
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(
+            keys,
+            [
+                pda::config().0,
+                admin,
+                farmer_core::ID,
+                pda::program_data().0,
+                system_program::ID,
+            ]
+        );
+        assert!(ix.accounts[1].is_signer && ix.accounts[1].is_writable);
+    }
+
+    #[test]
+    fn migrate_allowed_mints_appends_mint_and_entry_pairs() {
+        let admin = Pubkey::new_unique();
+        let mints = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let ix = migrate_allowed_mints(&admin, &mints, 100, 1_000, Some(5));
+
+        assert_eq!(ix.accounts.len(), 3 + 2 * mints.len());
+        for (pair, mint) in ix.accounts[3..].chunks(2).zip(&mints) {
+            assert_eq!(pair[0].pubkey, *mint);
+            assert!(!pair[0].is_writable && !pair[0].is_signer);
+            assert_eq!(pair[1].pubkey, pda::allowed_mint(mint).0);
+            assert!(pair[1].is_writable && !pair[1].is_signer);
+        }
+        let args = instruction::MigrateAllowedMints::try_from_slice(
+            &ix.data[instruction::MigrateAllowedMints::DISCRIMINATOR.len()..],
+        )
+        .unwrap();
+        assert_eq!(args.max_order_subtotal, 1_000);
+        assert_eq!(args.service_fee_floor, Some(5));
+    }
+
+    #[test]
+    fn update_warehouse_passes_staff_pda_only_as_staff() {
+        let signer = Pubkey::new_unique();
+        let warehouse = pda::warehouse(7).0;
+
+        let owner_ix = update_warehouse(&signer, 7, false, WarehouseUpdate::default());
+        // Anchor encodes a missing optional account as the program id
+        assert_eq!(owner_ix.accounts[2].pubkey, farmer_core::ID);
+
+        let staff_ix = update_warehouse(&signer, 7, true, WarehouseUpdate::default());
+        assert_eq!(
+            staff_ix.accounts[2].pubkey,
+            pda::warehouse_staff(&warehouse, &signer).0
+        );
+        assert!(staff_ix.accounts[3].is_signer);
+    }
+
+    #[test]
+    fn publish_offer_derives_offer_and_affiliation() {
+        let farmer = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let warehouse = pda::warehouse(3).0;
+        let delegate = Pubkey::new_unique();
+        let ix = publish_offer(&delegate, &farmer, 3, &mint, 42, NewOffer::default());
+
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(keys[2], pda::farmer_affiliation(&farmer, &warehouse).0);
+        assert_eq!(keys[3], warehouse);
+        assert_eq!(keys[4], pda::allowed_mint(&mint).0);
+        assert_eq!(keys[5], pda::offer(&farmer, 42).0);
+        assert_eq!(keys[6], delegate);
+        assert!(ix.accounts[5].is_writable && ix.accounts[6].is_signer);
+    }
+
+    #[test]
+    fn close_offer_refunds_the_rent_payer() {
+        let farmer = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let ix = close_offer(&farmer, 4, &delegate);
+
+        assert_eq!(ix.accounts[1].pubkey, pda::offer(&farmer, 4).0);
+        assert_eq!(ix.accounts[2].pubkey, delegate);
+        assert!(ix.accounts[2].is_writable && !ix.accounts[2].is_signer);
+        assert_eq!(ix.accounts[3].pubkey, farmer);
+        assert!(ix.accounts[3].is_signer);
+    }
+
+    #[test]
+    fn confirm_customer_derives_warehouse_customer() {
+        let staff = Pubkey::new_unique();
+        let customer = Pubkey::new_unique();
+        let warehouse = pda::warehouse(5).0;
+        let ix = confirm_customer(&staff, 5, &customer, true, Some([7; 32]), Some(1_000), None);
+
+        assert_eq!(
+            ix.accounts[2].pubkey,
+            pda::warehouse_staff(&warehouse, &staff).0
+        );
+        assert_eq!(
+            ix.accounts[3].pubkey,
+            pda::warehouse_customer(&warehouse, &customer).0
+        );
+        assert!(ix.accounts[3].is_writable && ix.accounts[4].is_signer);
+        let args = instruction::ConfirmCustomer::try_from_slice(
+            &ix.data[instruction::ConfirmCustomer::DISCRIMINATOR.len()..],
+        )
+        .unwrap();
+        assert_eq!(args.notes_hash, Some([7; 32]));
+        assert_eq!(args.valid_until, Some(1_000));
+    }
+
+    #[test]
+    fn confirm_customers_appends_entry_pdas_in_order() {
+        let authority = Pubkey::new_unique();
+        let warehouse = pda::warehouse(5).0;
+        let entries = [
+            (Pubkey::new_unique(), CustomerConfirmation::default()),
+            (
+                Pubkey::new_unique(),
+                CustomerConfirmation {
+                    notes_hash: None,
+                    envelope_hash: Some([9; 32]),
+                },
+            ),
+        ];
+        let ix = confirm_customers(&authority, 5, false, Some(1_000), &entries);
+
+        assert_eq!(ix.accounts[2].pubkey, farmer_core::ID);
+        assert!(ix.accounts[3].is_signer);
+        assert_eq!(ix.accounts.len(), 4 + entries.len());
+        for (meta, (customer, _)) in ix.accounts[4..].iter().zip(&entries) {
+            assert_eq!(meta.pubkey, pda::warehouse_customer(&warehouse, customer).0);
+            assert!(meta.is_writable && !meta.is_signer);
+        }
+        let args = instruction::ConfirmCustomers::try_from_slice(
+            &ix.data[instruction::ConfirmCustomers::DISCRIMINATOR.len()..],
+        )
+        .unwrap();
+        assert_eq!(args.valid_until, Some(1_000));
+        assert_eq!(args.confirmations[1].envelope_hash, Some([9; 32]));
+    }
+}
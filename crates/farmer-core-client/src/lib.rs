@@ -0,0 +1,24 @@
+//! Rust client for the `farmer-core` program.
+//!
+//! - [`pda`]: address derivation for every seed in `farmer_core::states`
+//! - [`instructions`]: one builder per program instruction
+//! - [`accounts`]: decode account data and fetch it through any RPC client
+//! - [`zip`]: ZIP prefix parsing and offline warehouse coverage filtering
+//! - [`envelope`]: sealing and opening customer address envelopes
+//!
+//! Account and instruction types are the program's own (`farmer_core::states`,
+//! `farmer_core::accounts`, `farmer_core::instruction`), built with the
+//! `no-entrypoint` feature, so the client cannot drift from the program.
+
+pub mod accounts;
+pub mod envelope;
+pub mod instructions;
+pub mod pda;
+pub mod zip;
+
+pub use farmer_core::states::{
+    AddressEnvelope, AffiliationStatus, AllowedMint, CustomerConfirmation, CustomerProfile,
+    FarmerAffiliation, FarmerProfile, LotOffer, ProgramConfig, Warehouse, WarehouseCustomer,
+    WarehouseCustomerStatus, WarehouseStaff, WarehouseStatus, ZipPrefix,
+};
+pub use farmer_core::ID as PROGRAM_ID;
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use farmer_core::states::{
+    SEED_AFFILIATION, SEED_CONFIG, SEED_CUSTOMER, SEED_ESCROW, SEED_FARMER, SEED_MINT, SEED_OFFER,
+    SEED_ORDER, SEED_PACK, SEED_STAFF, SEED_WAREHOUSE, SEED_WCUSTOMER,
+};
+
+// ============================================================================
+// PDA DERIVATION
+// ============================================================================
+// One helper per seed in `farmer_core::states`. Each returns `(address, bump)`.
+
+fn find(seeds: &[&[u8]]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(seeds, &farmer_core::ID)
+}
+
+/// `["config"]`
+pub fn config() -> (Pubkey, u8) {
+    find(&[SEED_CONFIG])
+}
+
+/// `["mint", mint]`
+pub fn allowed_mint(mint: &Pubkey) -> (Pubkey, u8) {
+    find(&[SEED_MINT, mint.as_ref()])
+}
+
+/// `["warehouse", warehouse_id_u64_le]`
+pub fn warehouse(warehouse_id: u64) -> (Pubkey, u8) {
+    find(&[SEED_WAREHOUSE, &warehouse_id.to_le_bytes()])
+}
+
+/// `["staff", warehouse, member]`
+pub fn warehouse_staff(warehouse: &Pubkey, member: &Pubkey) -> (Pubkey, u8) {
+    find(&[SEED_STAFF, warehouse.as_ref(), member.as_ref()])
+}
+
+/// `["farmer", farmer]`
+pub fn farmer(farmer: &Pubkey) -> (Pubkey, u8) {
+    find(&[SEED_FARMER, farmer.as_ref()])
+}
+
+/// `["affiliation", farmer, warehouse]`
+pub fn farmer_affiliation(farmer: &Pubkey, warehouse: &Pubkey) -> (Pubkey, u8) {
+    find(&[SEED_AFFILIATION, farmer.as_ref(), warehouse.as_ref()])
+}
+
+/// `["customer", customer]`
+pub fn customer(customer: &Pubkey) -> (Pubkey, u8) {
+    find(&[SEED_CUSTOMER, customer.as_ref()])
+}
+
+/// `["wcustomer", warehouse, customer]`
+pub fn warehouse_customer(warehouse: &Pubkey, customer: &Pubkey) -> (Pubkey, u8) {
+    find(&[SEED_WCUSTOMER, warehouse.as_ref(), customer.as_ref()])
+}
+
+/// `["pack", farmer, pack_id_u64_le]`
+pub fn pack(farmer: &Pubkey, pack_id: u64) -> (Pubkey, u8) {
+    find(&[SEED_PACK, farmer.as_ref(), &pack_id.to_le_bytes()])
+}
+
+/// `["offer", farmer, offer_id_u64_le]`
+pub fn offer(farmer: &Pubkey, offer_id: u64) -> (Pubkey, u8) {
+    find(&[SEED_OFFER, farmer.as_ref(), &offer_id.to_le_bytes()])
+}
+
+/// `["order", offer, customer, order_id_u64_le]`
+pub fn order(offer: &Pubkey, customer: &Pubkey, order_id: u64) -> (Pubkey, u8) {
+    find(&[
+        SEED_ORDER,
+        offer.as_ref(),
+        customer.as_ref(),
+        &order_id.to_le_bytes(),
+    ])
+}
+
+/// `["escrow", order]`
+pub fn escrow(order: &Pubkey) -> (Pubkey, u8) {
+    find(&[SEED_ESCROW, order.as_ref()])
+}
+
+/// The program's `ProgramData` account (owned by the upgradeable loader).
+pub fn program_data() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[farmer_core::ID.as_ref()], &bpf_loader_upgradeable::ID)
+}
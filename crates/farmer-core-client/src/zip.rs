@@ -0,0 +1,104 @@
+use farmer_core::states::{Warehouse, ZipPrefix};
+
+// ============================================================================
+// ZIP COVERAGE
+// ============================================================================
+// String form of `ZipPrefix` and offline coverage filtering. Validation and
+// matching are the program's own (`ZipPrefix::validate`, `ZipPrefix::covers`,
+// `Warehouse::serves`), so a UI filtering fetched warehouses gets the same
+// answer as the `check_coverage` instruction.
+
+/// Parses `"0123"` as `ZipPrefix { prefix: 123, len: 4 }`; `None` unless it is
+/// 3-5 digits.
+pub fn parse(s: &str) -> Option<ZipPrefix> {
+    if !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let zip = ZipPrefix {
+        prefix: s.parse().ok()?,
+        len: u8::try_from(s.len()).ok()?,
+    };
+    zip.validate().ok()?;
+    Some(zip)
+}
+
+/// Formats a prefix with its leading zeros, e.g. `{ prefix: 123, len: 4 }` as `0123`.
+pub fn format(zip: &ZipPrefix) -> String {
+    format!("{:0width$}", zip.prefix, width = zip.len as usize)
+}
+
+/// The warehouses whose coverage includes `zip`, in input order.
+pub fn serving<'a>(
+    warehouses: impl IntoIterator<Item = &'a Warehouse>,
+    zip: &'a ZipPrefix,
+) -> impl Iterator<Item = &'a Warehouse> {
+    warehouses
+        .into_iter()
+        .filter(move |warehouse| warehouse.serves(zip))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use farmer_core::states::WarehouseStatus;
+
+    fn warehouse(warehouse_id: u64, prefixes: &[&str]) -> Warehouse {
+        Warehouse {
+            warehouse_id,
+            operator: Pubkey::new_unique(),
+            status: WarehouseStatus::Active,
+            fee_receiver: Pubkey::new_unique(),
+            name: String::new(),
+            pickup_notes: String::new(),
+            fee_bps: 0,
+            pending_fee_bps: None,
+            fee_effective_at: 0,
+            confirmation_validity: None,
+            encryption_key: None,
+            delivery_fee_rules_uri: None,
+            deliver_zip_prefixes: prefixes.iter().map(|s| parse(s).unwrap()).collect(),
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn parse_keeps_leading_zeros_and_bounds_len() {
+        let zip = parse("0123").unwrap();
+        assert_eq!(
+            zip,
+            ZipPrefix {
+                prefix: 123,
+                len: 4
+            }
+        );
+        assert_eq!(format(&zip), "0123");
+
+        assert_eq!(parse("12"), None);
+        assert_eq!(parse("123456"), None);
+        assert_eq!(parse("12a"), None);
+        assert_eq!(parse("+123"), None);
+        assert_eq!(parse(""), None);
+    }
+
+    #[test]
+    fn serving_filters_by_coverage() {
+        let warehouses = [
+            warehouse(1, &["123", "04567"]),
+            warehouse(2, &["1234"]),
+            warehouse(3, &["999"]),
+        ];
+
+        let zip = parse("12345").unwrap();
+        let ids: Vec<u64> = serving(&warehouses, &zip)
+            .map(|warehouse| warehouse.warehouse_id)
+            .collect();
+        assert_eq!(ids, [1, 2]);
+
+        let zip = parse("04567").unwrap();
+        let ids: Vec<u64> = serving(&warehouses, &zip)
+            .map(|warehouse| warehouse.warehouse_id)
+            .collect();
+        assert_eq!(ids, [1]);
+    }
+}
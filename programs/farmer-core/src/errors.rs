@@ -4,21 +4,228 @@ use anchor_lang::prelude::*;
 // ERROR ENUMS
 // ============================================================================
 // Errors are organized by entity (Customer, Farmer, Warehouse, Config, etc.)
+// Anchor numbers variants by position, so new ones are only ever appended and
+// retired ones stay as placeholders; deployed clients keep decoding codes right.
 
 #[error_code]
 pub enum CustomerError {
     #[msg("Customer not found")]
     CustomerNotFound,
+    #[msg("Public profile URI is too long")]
+    ProfileUriTooLong,
+    #[msg("Public profile URI must start with https://, ipfs:// or ar://")]
+    InvalidProfileUriScheme,
+    #[msg("Unauthorized: signer is not the customer")]
+    UnauthorizedCustomer,
+    #[msg("Confirmation request is already pending")]
+    ConfirmationAlreadyPending,
+    #[msg("Customer is already confirmed by this warehouse")]
+    AlreadyConfirmed,
+    #[msg("Confirmation request is not pending")]
+    ConfirmationNotPending,
+    #[msg("Customer confirmation is already revoked")]
+    AlreadyRevoked,
+    #[msg("Customer is not confirmed by this warehouse")]
+    CustomerNotConfirmed,
+    #[msg("Confirmation expiry must be in the future")]
+    InvalidConfirmationExpiry,
+    #[msg("Customer still has open orders")]
+    OpenOrdersRemaining,
+    #[msg("Customer must withdraw from every warehouse before closing the profile")]
+    LinkedWarehousesRemaining,
+    #[msg("Envelope URI is too long")]
+    EnvelopeUriTooLong,
+    #[msg("Envelope URI must start with https://, ipfs:// or ar://")]
+    InvalidEnvelopeUri,
+    #[msg("Warehouse has not published an encryption key")]
+    EncryptionKeyNotSet,
+    #[msg("Envelope is not sealed to the warehouse's current encryption key")]
+    EnvelopeKeyMismatch,
+    #[msg("Envelope hash does not match the customer's request")]
+    EnvelopeHashMismatch,
+    #[msg("Batch must confirm between 1 and MAX_CONFIRM_BATCH customers")]
+    InvalidBatchSize,
+    #[msg("Batch needs one confirmation per WarehouseCustomer account")]
+    BatchLengthMismatch,
+    #[msg("Account is not a writable WarehouseCustomer PDA of this warehouse")]
+    InvalidWarehouseCustomerAccount,
+    #[msg("Customer confirmation has expired; the warehouse must renew it")]
+    ConfirmationExpired,
 }
 
 #[error_code]
 pub enum ConfigError {
+    /// Deprecated: the allowlist moved to `AllowedMint` PDAs and has no cap
     #[msg("Too many allowed mints provided")]
     TooManyAllowedMints,
+    /// Deprecated: replaced by the per-action `*Paused` errors
     #[msg("Program is currently paused")]
     ProgramPaused,
     #[msg("Unauthorized: caller is not the admin")]
     UnauthorizedAdmin,
     #[msg("Mint not in allowed list")]
     MintNotAllowed,
+    #[msg("Invalid pause flags: unknown bits set")]
+    InvalidPauseFlags,
+    #[msg("New orders are currently paused")]
+    NewOrdersPaused,
+    #[msg("New offers are currently paused")]
+    NewOffersPaused,
+    #[msg("Onboarding is currently paused")]
+    OnboardingPaused,
+    #[msg("Mint is already in the allowed list")]
+    MintAlreadyAllowed,
+    #[msg("No admin transfer is pending")]
+    NoPendingAdmin,
+    #[msg("Unauthorized: caller is not the pending admin")]
+    UnauthorizedPendingAdmin,
+    #[msg("Proposed admin is already the admin")]
+    AdminUnchanged,
+    #[msg("Invalid mint limits: min order must not exceed max order, and max must be positive")]
+    InvalidMintLimits,
+    #[msg("Config is already in the current layout")]
+    ConfigAlreadyMigrated,
+    #[msg("Account does not match the allowed mint PDA for this entry")]
+    InvalidAllowedMintAccount,
+    #[msg("Config still holds a legacy allowlist; run migrate_allowed_mints first")]
+    LegacyAllowlistPending,
+    #[msg("Config account layout is not recognized")]
+    UnknownConfigLayout,
+    #[msg("Program data account does not belong to this program")]
+    InvalidProgramData,
+    #[msg("Unauthorized: only the upgrade authority can initialize the config")]
+    UnauthorizedInitializer,
+    #[msg("Fee notice period must not be negative")]
+    InvalidFeeNoticePeriod,
+    #[msg("Invalid protocol fee: protocol_fee_bps must not exceed 10_000")]
+    InvalidProtocolFeeBps,
+    #[msg("Settlements are currently paused")]
+    SettlementsPaused,
+    #[msg("Refunds are currently paused")]
+    RefundsPaused,
+    #[msg("Config is not in the current layout; migrate it first")]
+    ConfigNotMigrated,
+}
+
+#[error_code]
+pub enum WarehouseError {
+    #[msg("Warehouse name is too long")]
+    NameTooLong,
+    #[msg("Pickup notes are too long")]
+    PickupNotesTooLong,
+    #[msg("Delivery fee rules URI is too long")]
+    UriTooLong,
+    #[msg("Too many ZIP prefixes")]
+    TooManyZipPrefixes,
+    #[msg("Invalid fee: fee_bps must not exceed 10_000")]
+    InvalidFeeBps,
+    #[msg("Unauthorized: caller is neither the warehouse operator nor the admin")]
+    UnauthorizedWarehouseAuthority,
+    #[msg("Unauthorized: signer lacks the required warehouse staff role")]
+    MissingStaffRole,
+    #[msg("Invalid roles: unknown bits set or no role given")]
+    InvalidRoles,
+    #[msg("Warehouse is suspended: no new orders or offers")]
+    WarehouseSuspended,
+    #[msg("Warehouse is closed: only refunds are allowed")]
+    WarehouseClosed,
+    #[msg("Invalid warehouse status transition")]
+    InvalidStatusTransition,
+    #[msg("Invalid ZIP prefix: len must be 3-5 and prefix must fit in len digits")]
+    InvalidZipPrefix,
+    #[msg("ZIP prefix listed more than once")]
+    DuplicateZipPrefix,
+    #[msg("Unauthorized: caller is not the warehouse operator")]
+    UnauthorizedOperator,
+    #[msg("Confirmation validity must be positive")]
+    InvalidConfirmationValidity,
+    #[msg("Operator must be set")]
+    InvalidOperator,
+    #[msg("Staff member must be a key other than the operator")]
+    InvalidStaffMember,
+}
+
+#[error_code]
+pub enum OrderError {
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Order subtotal is below the minimum for this mint")]
+    SubtotalBelowMinimum,
+    #[msg("Order subtotal is above the maximum for this mint")]
+    SubtotalAboveMaximum,
+}
+
+#[error_code]
+pub enum FarmerError {
+    #[msg("Display name must not be empty")]
+    EmptyDisplayName,
+    #[msg("Display name is too long")]
+    DisplayNameTooLong,
+    #[msg("Public profile URI is too long")]
+    ProfileUriTooLong,
+    #[msg("Public profile URI must start with https://, ipfs:// or ar://")]
+    InvalidProfileUriScheme,
+    #[msg("Unauthorized: signer is not the farmer")]
+    UnauthorizedFarmer,
+    #[msg("Farmer still has active offers")]
+    ActiveOffersRemaining,
+    #[msg("Farmer still has open orders")]
+    OpenOrdersRemaining,
+    #[msg("Farmer is already affiliated with this warehouse")]
+    AlreadyAffiliated,
+    #[msg("Deactivate the farmer's offers at this warehouse first")]
+    ActiveOffersAtWarehouse,
+    #[msg("Affiliation request is not pending")]
+    AffiliationNotPending,
+    #[msg("Farmer is not affiliated with this warehouse")]
+    AffiliationNotActive,
+    #[msg("Unauthorized: caller is neither the farmer nor the warehouse operator")]
+    UnauthorizedAffiliationAuthority,
+    #[msg("Payout wallet must be set")]
+    InvalidPayoutWallet,
+    #[msg("Delegate must be a key other than the farmer")]
+    InvalidDelegate,
+    #[msg("Unauthorized: signer is neither the farmer nor its delegate")]
+    UnauthorizedOfferSigner,
+    #[msg("Close the farmer's offers first")]
+    OfferAccountsRemaining,
+}
+
+#[error_code]
+pub enum OfferError {
+    #[msg("Crop name must not be empty")]
+    EmptyCropName,
+    #[msg("Crop name is too long")]
+    CropNameTooLong,
+    #[msg("Cultivar name is too long")]
+    CultivarNameTooLong,
+    #[msg("Public notes are too long")]
+    NotesTooLong,
+    #[msg("Offer quantity must be positive")]
+    InvalidQuantity,
+    #[msg("Offer price must be positive")]
+    InvalidPrice,
+    #[msg("Offer expiry must be in the future")]
+    InvalidExpiry,
+    #[msg("Offer is not active")]
+    OfferNotActive,
+    #[msg("Offer is still active; deactivate it first")]
+    OfferStillActive,
+    #[msg("Rent must be refunded to the account that paid for the offer")]
+    RentPayerMismatch,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deployed_config_error_codes_are_stable() {
+        // Codes of the original program; clients in the wild decode these
+        assert_eq!(u32::from(ConfigError::TooManyAllowedMints), 6000);
+        assert_eq!(u32::from(ConfigError::ProgramPaused), 6001);
+        assert_eq!(u32::from(ConfigError::UnauthorizedAdmin), 6002);
+        assert_eq!(u32::from(ConfigError::MintNotAllowed), 6003);
+        assert_eq!(u32::from(CustomerError::CustomerNotFound), 6000);
+    }
 }
@@ -0,0 +1,306 @@
+use anchor_lang::prelude::*;
+use crate::states::{AddressEnvelope, WarehouseStatus};
+
+// ============================================================================
+// EVENTS
+// ============================================================================
+// Events are the integration backbone for indexers and katubaya sync.
+
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub old_logistics_wallet: Pubkey,
+    pub new_logistics_wallet: Pubkey,
+    pub old_fee_notice_period: i64,
+    pub new_fee_notice_period: i64,
+    pub old_protocol_fee_bps: u16,
+    pub new_protocol_fee_bps: u16,
+}
+
+#[event]
+pub struct AdminTransferProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferAccepted {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferCanceled {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct PauseFlagsUpdated {
+    pub admin: Pubkey,
+    pub old_pause_flags: u8,
+    pub new_pause_flags: u8,
+}
+
+#[event]
+pub struct AllowedMintAdded {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub min_order_subtotal: u64,
+    pub max_order_subtotal: u64,
+    pub service_fee_floor: Option<u64>,
+}
+
+#[event]
+pub struct AllowedMintRemoved {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct AllowedMintsMigrated {
+    pub admin: Pubkey,
+    pub migrated: u32,
+    pub remaining: u32,
+}
+
+#[event]
+pub struct ConfigMigrated {
+    pub admin: Pubkey,
+    pub old_version: u8,
+    pub new_version: u8,
+}
+
+#[event]
+pub struct WarehouseCreated {
+    pub admin: Pubkey,
+    pub warehouse: Pubkey,
+    pub warehouse_id: u64,
+    pub operator: Pubkey,
+    pub fee_bps: u16,
+}
+
+#[event]
+pub struct WarehouseUpdated {
+    pub warehouse: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct WarehouseOperatorChanged {
+    pub warehouse: Pubkey,
+    pub authority: Pubkey,
+    pub old_operator: Pubkey,
+    pub new_operator: Pubkey,
+}
+
+#[event]
+pub struct WarehouseFeeReceiverChanged {
+    pub warehouse: Pubkey,
+    pub authority: Pubkey,
+    pub old_fee_receiver: Pubkey,
+    pub new_fee_receiver: Pubkey,
+}
+
+/// `effective_at` equals the transaction time when the change applies at once.
+#[event]
+pub struct WarehouseFeeChanged {
+    pub warehouse: Pubkey,
+    pub old_fee_bps: u16,
+    pub new_fee_bps: u16,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct StaffRolesGranted {
+    pub warehouse: Pubkey,
+    pub member: Pubkey,
+    pub authority: Pubkey,
+    pub roles: u8,
+    pub new_roles: u8,
+}
+
+/// `new_roles == 0` means the `WarehouseStaff` account was closed.
+#[event]
+pub struct StaffRolesRevoked {
+    pub warehouse: Pubkey,
+    pub member: Pubkey,
+    pub authority: Pubkey,
+    pub roles: u8,
+    pub new_roles: u8,
+}
+
+#[event]
+pub struct WarehouseStatusChanged {
+    pub admin: Pubkey,
+    pub warehouse: Pubkey,
+    pub old_status: WarehouseStatus,
+    pub new_status: WarehouseStatus,
+}
+
+#[event]
+pub struct FarmerRegistered {
+    pub farmer: Pubkey,
+    pub authority: Pubkey,
+    pub display_name: String,
+    pub public_profile_uri: String,
+}
+
+#[event]
+pub struct FarmerProfileUpdated {
+    pub farmer: Pubkey,
+    pub authority: Pubkey,
+    pub display_name: String,
+    pub public_profile_uri: String,
+}
+
+#[event]
+pub struct FarmerPayoutWalletChanged {
+    pub farmer: Pubkey,
+    pub authority: Pubkey,
+    pub old_payout_wallet: Pubkey,
+    pub new_payout_wallet: Pubkey,
+}
+
+#[event]
+pub struct FarmerDelegateChanged {
+    pub farmer: Pubkey,
+    pub authority: Pubkey,
+    pub old_delegate: Option<Pubkey>,
+    pub new_delegate: Option<Pubkey>,
+}
+
+#[event]
+pub struct FarmerProfileClosed {
+    pub farmer: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct AffiliationRequested {
+    pub affiliation: Pubkey,
+    pub farmer: Pubkey,
+    pub warehouse: Pubkey,
+}
+
+#[event]
+pub struct AffiliationApproved {
+    pub affiliation: Pubkey,
+    pub farmer: Pubkey,
+    pub operator: Pubkey,
+    pub warehouse: Pubkey,
+}
+
+#[event]
+pub struct AffiliationRejected {
+    pub affiliation: Pubkey,
+    pub farmer: Pubkey,
+    pub operator: Pubkey,
+    pub warehouse: Pubkey,
+}
+
+#[event]
+pub struct AffiliationEnded {
+    pub affiliation: Pubkey,
+    pub farmer: Pubkey,
+    pub warehouse: Pubkey,
+    /// The farmer or the warehouse operator
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct OfferPublished {
+    pub offer: Pubkey,
+    pub farmer: Pubkey,
+    pub warehouse: Pubkey,
+    pub crop_name: String,
+    pub cultivar_name: Option<String>,
+    pub unit_code: u16,
+    pub qty: u64,
+    pub price_minor: u64,
+    pub mint: Pubkey,
+    pub expires_at: Option<i64>,
+}
+
+#[event]
+pub struct OfferDeactivated {
+    pub offer: Pubkey,
+    pub farmer: Pubkey,
+    pub warehouse: Pubkey,
+}
+
+#[event]
+pub struct CustomerRegistered {
+    pub customer: Pubkey,
+    pub authority: Pubkey,
+    pub public_profile_uri: String,
+}
+
+#[event]
+pub struct CustomerConfirmationRequested {
+    pub warehouse_customer: Pubkey,
+    pub warehouse: Pubkey,
+    pub customer: Pubkey,
+    pub address_envelope: Option<AddressEnvelope>,
+}
+
+#[event]
+pub struct CustomerConfirmed {
+    pub warehouse_customer: Pubkey,
+    pub warehouse: Pubkey,
+    pub customer: Pubkey,
+    pub authority: Pubkey,
+    pub notes_hash: Option<[u8; 32]>,
+    pub valid_until: Option<i64>,
+    /// Ciphertext hash of the address envelope the warehouse opened, if any
+    pub envelope_hash: Option<[u8; 32]>,
+}
+
+#[event]
+pub struct CustomerRevoked {
+    pub warehouse_customer: Pubkey,
+    pub warehouse: Pubkey,
+    pub customer: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct CustomerConfirmationRenewed {
+    pub warehouse_customer: Pubkey,
+    pub warehouse: Pubkey,
+    pub customer: Pubkey,
+    pub authority: Pubkey,
+    pub notes_hash: Option<[u8; 32]>,
+    pub old_valid_until: Option<i64>,
+    pub new_valid_until: Option<i64>,
+}
+
+#[event]
+pub struct CustomerWithdrawn {
+    pub warehouse_customer: Pubkey,
+    pub warehouse: Pubkey,
+    pub customer: Pubkey,
+}
+
+#[event]
+pub struct CustomerProfileClosed {
+    pub customer: Pubkey,
+    pub authority: Pubkey,
+}
+
+/// `new_key` is `None` when the key was removed.
+#[event]
+pub struct WarehouseEncryptionKeyChanged {
+    pub warehouse: Pubkey,
+    pub authority: Pubkey,
+    pub old_key: Option<[u8; 32]>,
+    pub new_key: Option<[u8; 32]>,
+}
+
+#[event]
+pub struct OfferClosed {
+    pub offer: Pubkey,
+    pub farmer: Pubkey,
+    pub warehouse: Pubkey,
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use crate::errors::ConfigError;
+use crate::events::AdminTransferAccepted;
+use crate::states::{ProgramConfig, SEED_CONFIG};
+
+/// Accepts a pending admin transfer.
+///
+/// Second step of the two-step admin handover. Must be signed by the key
+/// stored in `ProgramConfig.pending_admin`; on success it becomes the admin
+/// and the pending slot is cleared.
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// The proposed admin authority
+    pub new_admin: Signer<'info>,
+}
+
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    let pending_admin = config.pending_admin.ok_or(ConfigError::NoPendingAdmin)?;
+    require_keys_eq!(
+        pending_admin,
+        ctx.accounts.new_admin.key(),
+        ConfigError::UnauthorizedPendingAdmin
+    );
+
+    let old_admin = config.admin;
+    config.admin = pending_admin;
+    config.pending_admin = None;
+
+    emit!(AdminTransferAccepted {
+        old_admin,
+        new_admin: config.admin,
+    });
+
+    msg!("Admin transfer accepted");
+    msg!("Admin: {}", config.admin);
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::Mint;
+use crate::errors::ConfigError;
+use crate::events::AllowedMintAdded;
+use crate::states::{AllowedMint, ProgramConfig, SEED_CONFIG, SEED_MINT};
+
+/// Adds a single mint to the allowlist by creating its `AllowedMint` PDA.
+///
+/// The `mint` account must be owned by the SPL Token or Token-2022 program
+/// and deserialize as a mint, so typos and non-mint accounts are rejected.
+/// Its decimals are snapshotted into the entry. Adding a mint twice fails with
+/// `MintAlreadyAllowed`, since the PDA already exists.
+///
+/// # Arguments
+/// - `min_order_subtotal`: Smallest accepted order subtotal (minor units)
+/// - `max_order_subtotal`: Largest accepted single-order subtotal (minor units)
+/// - `service_fee_floor`: Optional minimum service fee per order (minor units)
+#[derive(Accounts)]
+pub struct AddAllowedMint<'info> {
+    #[account(
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated,
+        has_one = admin @ ConfigError::UnauthorizedAdmin
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// CHECK: Created here once it is known to be empty; seeds are checked
+    #[account(
+        mut,
+        seeds = [SEED_MINT, mint.key().as_ref()],
+        bump
+    )]
+    pub allowed_mint: UncheckedAccount<'info>,
+
+    /// The SPL Token / Token-2022 mint to allow
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The current admin authority (pays for the entry)
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_allowed_mint(
+    ctx: Context<AddAllowedMint>,
+    min_order_subtotal: u64,
+    max_order_subtotal: u64,
+    service_fee_floor: Option<u64>,
+) -> Result<()> {
+    let target = ctx.accounts.allowed_mint.to_account_info();
+    require!(target.data_is_empty(), ConfigError::MintAlreadyAllowed);
+
+    let allowed_mint = AllowedMint {
+        mint: ctx.accounts.mint.key(),
+        decimals: ctx.accounts.mint.decimals,
+        min_order_subtotal,
+        max_order_subtotal,
+        service_fee_floor,
+        bump: ctx.bumps.allowed_mint,
+    };
+    allowed_mint.validate_limits()?;
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        SEED_MINT,
+        allowed_mint.mint.as_ref(),
+        &[allowed_mint.bump],
+    ]];
+    create_allowed_mint_account(
+        ctx.accounts.admin.to_account_info(),
+        target.clone(),
+        ctx.accounts.system_program.to_account_info(),
+        &Rent::get()?,
+        signer_seeds,
+    )?;
+    allowed_mint.try_serialize(&mut &mut target.try_borrow_mut_data()?[..])?;
+
+    emit!(AllowedMintAdded {
+        admin: ctx.accounts.admin.key(),
+        mint: allowed_mint.mint,
+        decimals: allowed_mint.decimals,
+        min_order_subtotal,
+        max_order_subtotal,
+        service_fee_floor,
+    });
+
+    msg!("Allowed mint added: {}", allowed_mint.mint);
+    msg!("Decimals: {}", allowed_mint.decimals);
+
+    Ok(())
+}
+
+/// Creates a program-owned `AllowedMint` PDA, even if someone pre-funded it.
+pub(crate) fn create_allowed_mint_account<'info>(
+    payer: AccountInfo<'info>,
+    target: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    rent: &Rent,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let required = rent.minimum_balance(AllowedMint::SIZE);
+    let current = target.lamports();
+
+    if current == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program,
+                system_program::CreateAccount {
+                    from: payer,
+                    to: target,
+                },
+                signer_seeds,
+            ),
+            required,
+            AllowedMint::SIZE as u64,
+            &crate::ID,
+        );
+    }
+
+    if current < required {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer,
+                    to: target.clone(),
+                },
+            ),
+            required - current,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Allocate {
+                account_to_allocate: target.clone(),
+            },
+            signer_seeds,
+        ),
+        AllowedMint::SIZE as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program,
+            system_program::Assign {
+                account_to_assign: target,
+            },
+            signer_seeds,
+        ),
+        &crate::ID,
+    )
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use crate::errors::{ConfigError, WarehouseError};
+use crate::events::AffiliationApproved;
+use crate::states::{
+    AffiliationStatus, FarmerAffiliation, ProgramConfig, Warehouse, PAUSE_ONBOARDING,
+    SEED_AFFILIATION, SEED_CONFIG, SEED_WAREHOUSE,
+};
+
+/// Accepts a farmer's pending request; the farmer can then publish offers
+/// fulfilled by this warehouse.
+///
+/// Signed by the warehouse operator. Blocked while onboarding is paused or the
+/// warehouse is closed.
+#[derive(Accounts)]
+pub struct ApproveAffiliation<'info> {
+    #[account(
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        seeds = [SEED_AFFILIATION, affiliation.farmer.as_ref(), warehouse.key().as_ref()],
+        bump = affiliation.bump
+    )]
+    pub affiliation: Account<'info, FarmerAffiliation>,
+
+    #[account(
+        seeds = [SEED_WAREHOUSE, warehouse.warehouse_id.to_le_bytes().as_ref()],
+        bump = warehouse.bump,
+        has_one = operator @ WarehouseError::UnauthorizedOperator
+    )]
+    pub warehouse: Account<'info, Warehouse>,
+
+    /// The warehouse operator
+    pub operator: Signer<'info>,
+}
+
+pub fn approve_affiliation(ctx: Context<ApproveAffiliation>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_ONBOARDING)?;
+    ctx.accounts.warehouse.require_open_for(PAUSE_ONBOARDING)?;
+
+    let affiliation = &mut ctx.accounts.affiliation;
+    affiliation.require_pending()?;
+
+    affiliation.status = AffiliationStatus::Approved;
+    affiliation.decided_at = Clock::get()?.unix_timestamp;
+
+    emit!(AffiliationApproved {
+        affiliation: affiliation.key(),
+        farmer: affiliation.farmer,
+        operator: ctx.accounts.operator.key(),
+        warehouse: affiliation.warehouse,
+    });
+
+    msg!("Affiliation approved: {}", affiliation.farmer);
+    msg!("Warehouse: {}", affiliation.warehouse);
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::errors::ConfigError;
+use crate::events::AdminTransferCanceled;
+use crate::states::{ProgramConfig, SEED_CONFIG};
+
+/// Cancels a pending admin transfer.
+///
+/// Signed by the current admin. Fails if no transfer is pending.
+#[derive(Accounts)]
+pub struct CancelAdminTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated,
+        has_one = admin @ ConfigError::UnauthorizedAdmin
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// The current admin authority
+    pub admin: Signer<'info>,
+}
+
+pub fn cancel_admin_transfer(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    let pending_admin = config.pending_admin.take().ok_or(ConfigError::NoPendingAdmin)?;
+
+    emit!(AdminTransferCanceled {
+        admin: config.admin,
+        pending_admin,
+    });
+
+    msg!("Admin transfer canceled");
+    msg!("Canceled pending admin: {}", pending_admin);
+
+    Ok(())
+}
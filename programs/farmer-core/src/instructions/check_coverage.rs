@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::states::{Warehouse, ZipPrefix, SEED_WAREHOUSE};
+
+/// Read-only view: does the warehouse deliver to `zip_prefix`?
+///
+/// Returns `true` through return data when one of the warehouse's
+/// `deliver_zip_prefixes` covers `zip_prefix` (see `ZipPrefix::covers`). Nothing
+/// is written and no signer is needed, so callers simulate it (`.view()` in the
+/// TS client). Coverage is discovery only: the warehouse status is not checked.
+///
+/// # Arguments
+/// - `zip_prefix`: Area to look up; a full ZIP code is a 5-digit prefix
+#[derive(Accounts)]
+pub struct CheckCoverage<'info> {
+    #[account(
+        seeds = [SEED_WAREHOUSE, warehouse.warehouse_id.to_le_bytes().as_ref()],
+        bump = warehouse.bump
+    )]
+    pub warehouse: Account<'info, Warehouse>,
+}
+
+pub fn check_coverage(ctx: Context<CheckCoverage>, zip_prefix: ZipPrefix) -> Result<bool> {
+    zip_prefix.validate()?;
+
+    let covered = ctx.accounts.warehouse.serves(&zip_prefix);
+
+    msg!("Warehouse: {}", ctx.accounts.warehouse.warehouse_id);
+    msg!("Covers {:?}: {}", zip_prefix, covered);
+
+    Ok(covered)
+}
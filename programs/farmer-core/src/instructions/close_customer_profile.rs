@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::errors::CustomerError;
+use crate::events::CustomerProfileClosed;
+use crate::states::{CustomerProfile, SEED_CUSTOMER};
+
+/// Closes the signer's `CustomerProfile` and refunds its rent to the customer.
+///
+/// Refused while the customer has open orders or still holds a
+/// `WarehouseCustomer` entry, so no entry outlives the profile. Closed accounts
+/// leave current state, but their past contents stay in transaction history.
+/// Registering again later starts a fresh profile with `order_counter = 0`.
+#[derive(Accounts)]
+pub struct CloseCustomerProfile<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_CUSTOMER, authority.key().as_ref()],
+        bump = customer_profile.bump,
+        has_one = authority @ CustomerError::UnauthorizedCustomer,
+        close = authority
+    )]
+    pub customer_profile: Account<'info, CustomerProfile>,
+
+    /// The customer (receives the rent)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+pub fn close_customer_profile(ctx: Context<CloseCustomerProfile>) -> Result<()> {
+    let profile = &ctx.accounts.customer_profile;
+    profile.require_closable()?;
+
+    emit!(CustomerProfileClosed {
+        customer: profile.key(),
+        authority: profile.authority,
+    });
+
+    msg!("Customer profile closed: {}", profile.authority);
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::errors::FarmerError;
+use crate::events::FarmerProfileClosed;
+use crate::states::{FarmerProfile, SEED_FARMER};
+
+/// Closes the signer's `FarmerProfile` and refunds its rent to the farmer.
+///
+/// Refused while the farmer has active offers, offer accounts or open orders, so
+/// no offer or order is left pointing at a missing profile. Every offer must be
+/// closed with `close_offer` first: registering again starts a fresh profile
+/// with `offer_counter = 0`, and its offer ids are then free to reuse.
+#[derive(Accounts)]
+pub struct CloseFarmerProfile<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_FARMER, authority.key().as_ref()],
+        bump = farmer_profile.bump,
+        has_one = authority @ FarmerError::UnauthorizedFarmer,
+        close = authority
+    )]
+    pub farmer_profile: Account<'info, FarmerProfile>,
+
+    /// The farmer (receives the rent)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+pub fn close_farmer_profile(ctx: Context<CloseFarmerProfile>) -> Result<()> {
+    let profile = &ctx.accounts.farmer_profile;
+    profile.require_closable()?;
+
+    emit!(FarmerProfileClosed {
+        farmer: profile.key(),
+        authority: profile.authority,
+    });
+
+    msg!("Farmer profile closed: {}", profile.authority);
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use crate::errors::{FarmerError, OfferError, OrderError};
+use crate::events::OfferClosed;
+use crate::states::{FarmerProfile, LotOffer, SEED_FARMER, SEED_OFFER};
+
+/// Closes one of the signer's deactivated offers and refunds its rent.
+///
+/// Signed by the farmer only, like the other profile changes. The rent goes
+/// back to the offer's `rent_payer`, the farmer or the delegate that published
+/// it. Refused while the offer is active or the farmer has open orders, so no
+/// order is left pointing at a missing offer. Allowed whatever the pause flags
+/// or the warehouse's status. Once every offer is closed the profile itself can
+/// be closed.
+#[derive(Accounts)]
+pub struct CloseOffer<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_FARMER, authority.key().as_ref()],
+        bump = farmer_profile.bump,
+        has_one = authority @ FarmerError::UnauthorizedFarmer
+    )]
+    pub farmer_profile: Account<'info, FarmerProfile>,
+
+    #[account(
+        mut,
+        seeds = [
+            SEED_OFFER,
+            authority.key().as_ref(),
+            offer.offer_id.to_le_bytes().as_ref()
+        ],
+        bump = offer.bump,
+        close = rent_payer
+    )]
+    pub offer: Account<'info, LotOffer>,
+
+    /// CHECK: Only receives lamports; must be the offer's recorded rent payer
+    #[account(mut, address = offer.rent_payer @ OfferError::RentPayerMismatch)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// The farmer
+    pub authority: Signer<'info>,
+}
+
+pub fn close_offer(ctx: Context<CloseOffer>) -> Result<()> {
+    let offer = &ctx.accounts.offer;
+    require!(!offer.active, OfferError::OfferStillActive);
+
+    let profile = &mut ctx.accounts.farmer_profile;
+    require!(profile.open_orders == 0, FarmerError::OpenOrdersRemaining);
+    profile.offer_accounts = profile
+        .offer_accounts
+        .checked_sub(1)
+        .ok_or(OrderError::MathOverflow)?;
+
+    emit!(OfferClosed {
+        offer: offer.key(),
+        farmer: offer.farmer,
+        warehouse: offer.warehouse,
+    });
+
+    msg!("Offer closed: {} #{}", offer.farmer, offer.offer_id);
+
+    Ok(())
+}
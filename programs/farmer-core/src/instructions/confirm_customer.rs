@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use crate::errors::ConfigError;
+use crate::events::CustomerConfirmed;
+use crate::states::{
+    ProgramConfig, Warehouse, WarehouseCustomer, WarehouseCustomerStatus, WarehouseStaff,
+    PAUSE_ONBOARDING, ROLE_CONFIRM_CUSTOMERS, SEED_CONFIG, SEED_STAFF, SEED_WAREHOUSE,
+    SEED_WCUSTOMER,
+};
+
+/// Confirms a pending customer once the warehouse has verified the address off-chain.
+///
+/// Signed by the warehouse operator or staff holding `ROLE_CONFIRM_CUSTOMERS`
+/// (passing their `WarehouseStaff` PDA). Blocked while onboarding is paused or
+/// the warehouse is closed.
+///
+/// The confirmation lasts until `valid_until`, or `Warehouse.confirmation_validity`
+/// seconds when not given; with neither it does not expire.
+///
+/// When the request carries an address envelope, the warehouse must pass its
+/// ciphertext hash, committing the confirmation to the address it opened.
+///
+/// # Arguments
+/// - `notes_hash`: Optional hash of the warehouse's off-chain record (never the record itself)
+/// - `valid_until`: Optional expiry overriding the warehouse default; must be in the future
+/// - `envelope_hash`: `address_envelope.ciphertext_hash` of the request; `None` without an envelope
+#[derive(Accounts)]
+pub struct ConfirmCustomer<'info> {
+    #[account(
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        seeds = [SEED_WAREHOUSE, warehouse.warehouse_id.to_le_bytes().as_ref()],
+        bump = warehouse.bump
+    )]
+    pub warehouse: Account<'info, Warehouse>,
+
+    /// The signer's staff grant; only needed when signing as staff
+    #[account(
+        seeds = [SEED_STAFF, warehouse.key().as_ref(), authority.key().as_ref()],
+        bump = staff.bump
+    )]
+    pub staff: Option<Account<'info, WarehouseStaff>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SEED_WCUSTOMER,
+            warehouse.key().as_ref(),
+            warehouse_customer.customer.as_ref()
+        ],
+        bump = warehouse_customer.bump
+    )]
+    pub warehouse_customer: Account<'info, WarehouseCustomer>,
+
+    /// The warehouse operator or staff with `ROLE_CONFIRM_CUSTOMERS`
+    pub authority: Signer<'info>,
+}
+
+pub fn confirm_customer(
+    ctx: Context<ConfirmCustomer>,
+    notes_hash: Option<[u8; 32]>,
+    valid_until: Option<i64>,
+    envelope_hash: Option<[u8; 32]>,
+) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_ONBOARDING)?;
+    ctx.accounts.warehouse.require_open_for(PAUSE_ONBOARDING)?;
+    let authority = ctx.accounts.authority.key();
+    ctx.accounts.warehouse.require_role(
+        &authority,
+        ctx.accounts.staff.as_deref(),
+        ROLE_CONFIRM_CUSTOMERS,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let valid_until = ctx.accounts.warehouse.confirmation_expiry(valid_until, now)?;
+
+    let entry = &mut ctx.accounts.warehouse_customer;
+    entry.require_pending()?;
+    entry.require_envelope_hash(envelope_hash)?;
+
+    entry.status = WarehouseCustomerStatus::Confirmed;
+    entry.confirmed_at = Some(now);
+    entry.valid_until = valid_until;
+    entry.notes_hash = notes_hash;
+
+    emit!(CustomerConfirmed {
+        warehouse_customer: entry.key(),
+        warehouse: entry.warehouse,
+        customer: entry.customer,
+        authority,
+        notes_hash,
+        valid_until,
+        envelope_hash,
+    });
+
+    msg!("Customer confirmed: {}", entry.customer);
+    msg!("Warehouse: {}", entry.warehouse);
+
+    Ok(())
+}
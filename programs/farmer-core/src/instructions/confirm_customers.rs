@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use crate::errors::{ConfigError, CustomerError};
+use crate::events::CustomerConfirmed;
+use crate::states::{
+    CustomerConfirmation, ProgramConfig, Warehouse, WarehouseCustomer, WarehouseCustomerStatus,
+    WarehouseStaff, MAX_CONFIRM_BATCH, PAUSE_ONBOARDING, ROLE_CONFIRM_CUSTOMERS, SEED_CONFIG,
+    SEED_STAFF, SEED_WAREHOUSE, SEED_WCUSTOMER,
+};
+
+/// Confirms several pending customers of one warehouse in a single transaction.
+///
+/// Pass the `WarehouseCustomer` PDAs (writable) as remaining accounts, with one
+/// `CustomerConfirmation` per account in the same order. Each is checked like
+/// `confirm_customer`: derived from this warehouse and its customer, pending,
+/// and committing to its envelope hash. Any failure reverts the whole batch;
+/// an account listed twice fails as no longer pending. Signed, paused and
+/// limited exactly like `confirm_customer`.
+///
+/// # Arguments
+/// - `valid_until`: Optional expiry for every confirmation; must be in the future
+/// - `confirmations`: 1 to `MAX_CONFIRM_BATCH` entries, one per remaining account
+#[derive(Accounts)]
+pub struct ConfirmCustomers<'info> {
+    #[account(
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        seeds = [SEED_WAREHOUSE, warehouse.warehouse_id.to_le_bytes().as_ref()],
+        bump = warehouse.bump
+    )]
+    pub warehouse: Account<'info, Warehouse>,
+
+    /// The signer's staff grant; only needed when signing as staff
+    #[account(
+        seeds = [SEED_STAFF, warehouse.key().as_ref(), authority.key().as_ref()],
+        bump = staff.bump
+    )]
+    pub staff: Option<Account<'info, WarehouseStaff>>,
+
+    /// The warehouse operator or staff with `ROLE_CONFIRM_CUSTOMERS`
+    pub authority: Signer<'info>,
+}
+
+pub fn confirm_customers<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ConfirmCustomers<'info>>,
+    valid_until: Option<i64>,
+    confirmations: Vec<CustomerConfirmation>,
+) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_ONBOARDING)?;
+    ctx.accounts.warehouse.require_open_for(PAUSE_ONBOARDING)?;
+    let authority = ctx.accounts.authority.key();
+    ctx.accounts.warehouse.require_role(
+        &authority,
+        ctx.accounts.staff.as_deref(),
+        ROLE_CONFIRM_CUSTOMERS,
+    )?;
+
+    require!(
+        (1..=MAX_CONFIRM_BATCH).contains(&confirmations.len()),
+        CustomerError::InvalidBatchSize
+    );
+    require!(
+        ctx.remaining_accounts.len() == confirmations.len(),
+        CustomerError::BatchLengthMismatch
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let valid_until = ctx.accounts.warehouse.confirmation_expiry(valid_until, now)?;
+    let warehouse = ctx.accounts.warehouse.key();
+
+    for (info, confirmation) in ctx.remaining_accounts.iter().zip(confirmations) {
+        require!(info.is_writable, CustomerError::InvalidWarehouseCustomerAccount);
+        // Checks the owner and discriminator
+        let mut entry = Account::<WarehouseCustomer>::try_from(info)?;
+        let expected = Pubkey::create_program_address(
+            &[
+                SEED_WCUSTOMER,
+                warehouse.as_ref(),
+                entry.customer.as_ref(),
+                &[entry.bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| CustomerError::InvalidWarehouseCustomerAccount)?;
+        require_keys_eq!(
+            info.key(),
+            expected,
+            CustomerError::InvalidWarehouseCustomerAccount
+        );
+
+        entry.require_pending()?;
+        entry.require_envelope_hash(confirmation.envelope_hash)?;
+
+        entry.status = WarehouseCustomerStatus::Confirmed;
+        entry.confirmed_at = Some(now);
+        entry.valid_until = valid_until;
+        entry.notes_hash = confirmation.notes_hash;
+
+        emit!(CustomerConfirmed {
+            warehouse_customer: info.key(),
+            warehouse,
+            customer: entry.customer,
+            authority,
+            notes_hash: confirmation.notes_hash,
+            valid_until,
+            envelope_hash: confirmation.envelope_hash,
+        });
+
+        // Written now so a duplicate later in the batch is no longer pending
+        entry.exit(&crate::ID)?;
+    }
+
+    msg!("Customers confirmed: {}", ctx.remaining_accounts.len());
+    msg!("Warehouse: {}", warehouse);
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use crate::errors::ConfigError;
+use crate::events::WarehouseCreated;
+use crate::states::{
+    ProgramConfig, Warehouse, WarehouseStatus, ZipPrefix, PAUSE_ONBOARDING, SEED_CONFIG,
+    SEED_WAREHOUSE,
+};
+
+/// Onboards a fulfillment partner by creating its `Warehouse` PDA.
+///
+/// Only the admin can create warehouses; `operator` is the key that runs the
+/// warehouse afterwards and, until changed via `update_warehouse`, also its
+/// `fee_receiver`. Blocked while onboarding is paused.
+///
+/// # Arguments
+/// - `warehouse_id`: Unique id, also the PDA seed (u64 little-endian)
+/// - `operator`: Warehouse operator key; must not be `Pubkey::default()`
+/// - `name`: Display name (max `MAX_NAME_LEN` bytes)
+/// - `pickup_notes`: Public pickup instructions (max `MAX_NOTES_LEN` bytes)
+/// - `fee_bps`: Service fee on order subtotals (max 10_000)
+/// - `deliver_zip_prefixes`: Delivery coverage (max `MAX_ZIP_PREFIXES`)
+/// - `delivery_fee_rules_uri`: Optional public delivery fee rules (max `MAX_URI_LEN` bytes)
+#[derive(Accounts)]
+#[instruction(warehouse_id: u64)]
+pub struct CreateWarehouse<'info> {
+    #[account(
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated,
+        has_one = admin @ ConfigError::UnauthorizedAdmin
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Warehouse::SIZE,
+        seeds = [SEED_WAREHOUSE, warehouse_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub warehouse: Account<'info, Warehouse>,
+
+    /// The current admin authority (pays for the warehouse)
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_warehouse(
+    ctx: Context<CreateWarehouse>,
+    warehouse_id: u64,
+    operator: Pubkey,
+    name: String,
+    pickup_notes: String,
+    fee_bps: u16,
+    deliver_zip_prefixes: Vec<ZipPrefix>,
+    delivery_fee_rules_uri: Option<String>,
+) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_ONBOARDING)?;
+
+    let warehouse = &mut ctx.accounts.warehouse;
+
+    warehouse.warehouse_id = warehouse_id;
+    warehouse.operator = operator;
+    warehouse.status = WarehouseStatus::Active;
+    warehouse.fee_receiver = operator;
+    warehouse.name = name;
+    warehouse.pickup_notes = pickup_notes;
+    warehouse.fee_bps = fee_bps;
+    warehouse.pending_fee_bps = None;
+    warehouse.fee_effective_at = 0;
+    warehouse.confirmation_validity = None;
+    warehouse.encryption_key = None;
+    warehouse.delivery_fee_rules_uri = delivery_fee_rules_uri;
+    warehouse.deliver_zip_prefixes = deliver_zip_prefixes;
+    warehouse.bump = ctx.bumps.warehouse;
+    warehouse.validate()?;
+
+    emit!(WarehouseCreated {
+        admin: ctx.accounts.admin.key(),
+        warehouse: warehouse.key(),
+        warehouse_id,
+        operator,
+        fee_bps,
+    });
+
+    msg!("Warehouse created: {}", warehouse_id);
+    msg!("Operator: {}", warehouse.operator);
+    msg!("Fee bps: {}", warehouse.fee_bps);
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use crate::errors::{OfferError, OrderError};
+use crate::events::OfferDeactivated;
+use crate::states::{
+    FarmerAffiliation, FarmerProfile, LotOffer, SEED_AFFILIATION, SEED_FARMER, SEED_OFFER,
+};
+
+/// Stops purchases on one of a farmer's offers.
+///
+/// Signed by the farmer or its delegate. Always allowed, whatever the pause
+/// flags or the affiliation's status, so a farmer can wind down before leaving
+/// a warehouse. The offer account stays until the farmer closes it with
+/// `close_offer`.
+#[derive(Accounts)]
+pub struct DeactivateOffer<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_FARMER, farmer_profile.authority.as_ref()],
+        bump = farmer_profile.bump
+    )]
+    pub farmer_profile: Account<'info, FarmerProfile>,
+
+    #[account(
+        mut,
+        seeds = [
+            SEED_AFFILIATION,
+            farmer_profile.authority.as_ref(),
+            offer.warehouse.as_ref()
+        ],
+        bump = affiliation.bump
+    )]
+    pub affiliation: Account<'info, FarmerAffiliation>,
+
+    #[account(
+        mut,
+        seeds = [
+            SEED_OFFER,
+            farmer_profile.authority.as_ref(),
+            offer.offer_id.to_le_bytes().as_ref()
+        ],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, LotOffer>,
+
+    /// The farmer or its delegate
+    pub authority: Signer<'info>,
+}
+
+pub fn deactivate_offer(ctx: Context<DeactivateOffer>) -> Result<()> {
+    ctx.accounts
+        .farmer_profile
+        .require_offer_signer(ctx.accounts.authority.key)?;
+
+    let offer = &mut ctx.accounts.offer;
+    require!(offer.active, OfferError::OfferNotActive);
+    offer.active = false;
+
+    let profile = &mut ctx.accounts.farmer_profile;
+    let affiliation = &mut ctx.accounts.affiliation;
+    profile.active_offers = profile
+        .active_offers
+        .checked_sub(1)
+        .ok_or(OrderError::MathOverflow)?;
+    affiliation.active_offers = affiliation
+        .active_offers
+        .checked_sub(1)
+        .ok_or(OrderError::MathOverflow)?;
+
+    emit!(OfferDeactivated {
+        offer: offer.key(),
+        farmer: offer.farmer,
+        warehouse: offer.warehouse,
+    });
+
+    msg!("Offer deactivated: {} #{}", offer.farmer, offer.offer_id);
+
+    Ok(())
+}
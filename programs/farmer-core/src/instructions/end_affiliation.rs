@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::errors::FarmerError;
+use crate::events::AffiliationEnded;
+use crate::states::{FarmerAffiliation, Warehouse, SEED_AFFILIATION, SEED_WAREHOUSE};
+
+/// Ends a farmer's relationship with a warehouse and closes the affiliation.
+///
+/// Signed by the farmer or the warehouse operator, in any status: the farmer
+/// can withdraw a request or leave, the operator can drop a farmer. Refused
+/// while offers fulfilled by this warehouse are active, so no offer points at a
+/// warehouse that will not fulfil it. The rent goes back to the farmer.
+#[derive(Accounts)]
+pub struct EndAffiliation<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_AFFILIATION, farmer.key().as_ref(), warehouse.key().as_ref()],
+        bump = affiliation.bump,
+        has_one = farmer,
+        close = farmer
+    )]
+    pub affiliation: Account<'info, FarmerAffiliation>,
+
+    #[account(
+        seeds = [SEED_WAREHOUSE, warehouse.warehouse_id.to_le_bytes().as_ref()],
+        bump = warehouse.bump,
+        constraint = authority.key() == farmer.key()
+            || authority.key() == warehouse.operator
+            @ FarmerError::UnauthorizedAffiliationAuthority
+    )]
+    pub warehouse: Account<'info, Warehouse>,
+
+    /// CHECK: The farmer key stored in `affiliation` (`has_one`); receives the rent
+    #[account(mut)]
+    pub farmer: UncheckedAccount<'info>,
+
+    /// The farmer or the warehouse operator
+    pub authority: Signer<'info>,
+}
+
+pub fn end_affiliation(ctx: Context<EndAffiliation>) -> Result<()> {
+    let affiliation = &ctx.accounts.affiliation;
+    affiliation.require_closable()?;
+
+    emit!(AffiliationEnded {
+        affiliation: affiliation.key(),
+        farmer: affiliation.farmer,
+        warehouse: affiliation.warehouse,
+        authority: ctx.accounts.authority.key(),
+    });
+
+    msg!("Affiliation ended: {}", affiliation.farmer);
+    msg!("Warehouse: {}", affiliation.warehouse);
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use crate::errors::{ConfigError, WarehouseError};
+use crate::events::StaffRolesGranted;
+use crate::states::{
+    ProgramConfig, Warehouse, WarehouseStaff, PAUSE_ONBOARDING, SEED_CONFIG, SEED_STAFF,
+    SEED_WAREHOUSE,
+};
+
+/// Grants warehouse roles to a staff member.
+///
+/// Signed by the warehouse operator or the admin, who pays for the
+/// `WarehouseStaff` PDA the first time `member` is granted a role. Roles are
+/// added to the ones `member` already holds. Not allowed while onboarding is
+/// paused or once the warehouse is closed.
+///
+/// # Arguments
+/// - `member`: Staff key receiving the roles; neither `Pubkey::default()` nor the operator
+/// - `roles`: `ROLE_*` bits to add (non-empty, known bits only)
+#[derive(Accounts)]
+#[instruction(member: Pubkey)]
+pub struct GrantStaffRoles<'info> {
+    #[account(
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        seeds = [SEED_WAREHOUSE, warehouse.warehouse_id.to_le_bytes().as_ref()],
+        bump = warehouse.bump,
+        constraint = authority.key() == warehouse.operator
+            || authority.key() == config.admin
+            @ WarehouseError::UnauthorizedWarehouseAuthority
+    )]
+    pub warehouse: Account<'info, Warehouse>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = WarehouseStaff::SIZE,
+        seeds = [SEED_STAFF, warehouse.key().as_ref(), member.as_ref()],
+        bump
+    )]
+    pub staff: Account<'info, WarehouseStaff>,
+
+    /// The warehouse operator or the admin (pays for a new grant)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn grant_staff_roles(ctx: Context<GrantStaffRoles>, member: Pubkey, roles: u8) -> Result<()> {
+    WarehouseStaff::validate_roles(roles)?;
+    WarehouseStaff::validate_member(&member, &ctx.accounts.warehouse.operator)?;
+    ctx.accounts.config.require_not_paused(PAUSE_ONBOARDING)?;
+    ctx.accounts.warehouse.require_open_for(PAUSE_ONBOARDING)?;
+
+    let warehouse = ctx.accounts.warehouse.key();
+    let staff = &mut ctx.accounts.staff;
+
+    staff.warehouse = warehouse;
+    staff.member = member;
+    staff.roles |= roles;
+    staff.bump = ctx.bumps.staff;
+
+    emit!(StaffRolesGranted {
+        warehouse,
+        member,
+        authority: ctx.accounts.authority.key(),
+        roles,
+        new_roles: staff.roles,
+    });
+
+    msg!("Staff roles granted: {}", member);
+    msg!("Roles: {:#04x}", staff.roles);
+
+    Ok(())
+}
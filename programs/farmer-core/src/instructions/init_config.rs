@@ -1,18 +1,27 @@
 use anchor_lang::prelude::*;
-use crate::states::{ProgramConfig, SEED_CONFIG, MAX_ALLOWED_MINTS};
+use crate::errors::ConfigError;
+use crate::program::FarmerCore;
+use crate::states::{
+    ProgramConfig, SEED_CONFIG, PAUSE_ALL, CONFIG_VERSION, DEFAULT_FEE_NOTICE_PERIOD,
+};
 
 /// Initializes the program configuration account.
 /// 
 /// This instruction creates the ProgramConfig PDA that stores:
 /// - Admin authority
 /// - Logistics wallet (fee receiver)
-/// - Paused state
-/// - Allowed token mints (optional allowlist)
+/// - Pause flags
+/// 
+/// The mint allowlist lives in `AllowedMint` PDAs managed by
+/// `add_allowed_mint` / `remove_allowed_mint`.
+/// 
+/// Only the program's upgrade authority may call it, so nobody can front-run
+/// the deployer and take over `admin`. Builds with the `fixed-admin` feature
+/// accept the compile-time `ADMIN` key instead.
 /// 
 /// # Arguments
 /// - `logistics_wallet`: The wallet that receives logistics/service fees
-/// - `paused`: Initial paused state (true = program paused)
-/// - `allowed_mints`: Optional vector of allowed SPL token mints (empty = no allowlist)
+/// - `pause_flags`: Initial pause mask (`PAUSE_*` bits, 0 = nothing paused)
 #[derive(Accounts)]
 pub struct InitConfig<'info> {
     #[account(
@@ -28,34 +37,62 @@ pub struct InitConfig<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
     
+    /// This program, used to locate its program data account
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+            @ ConfigError::InvalidProgramData
+    )]
+    pub program: Program<'info, FarmerCore>,
+    
+    /// Program data account holding the upgrade authority
+    pub program_data: Account<'info, ProgramData>,
+    
     pub system_program: Program<'info, System>,
 }
 
 pub fn init_config(
     ctx: Context<InitConfig>,
     logistics_wallet: Pubkey,
-    paused: bool,
-    allowed_mints: Vec<Pubkey>,
+    pause_flags: u8,
 ) -> Result<()> {
+    require!(
+        is_initializer(&ctx.accounts.program_data, &ctx.accounts.admin.key()),
+        ConfigError::UnauthorizedInitializer
+    );
+    
     let config = &mut ctx.accounts.config;
     
-    // Validate allowed_mints length
+    // Validate pause_flags only uses known bits
     require!(
-        allowed_mints.len() <= MAX_ALLOWED_MINTS,
-        crate::errors::ConfigError::TooManyAllowedMints
+        pause_flags & !PAUSE_ALL == 0,
+        ConfigError::InvalidPauseFlags
     );
     
     // Set config fields
     config.admin = ctx.accounts.admin.key();
     config.logistics_wallet = logistics_wallet;
-    config.paused = paused;
-    config.allowed_mints = allowed_mints;
+    config.pause_flags = pause_flags;
+    config.pending_admin = None;
+    config.version = CONFIG_VERSION;
+    config.fee_notice_period = DEFAULT_FEE_NOTICE_PERIOD;
+    config.protocol_fee_bps = 0;
+    config.reserved = [0; 54];
     
     msg!("Program config initialized");
     msg!("Admin: {}", config.admin);
     msg!("Logistics wallet: {}", config.logistics_wallet);
-    msg!("Paused: {}", config.paused);
-    msg!("Allowed mints: {}", config.allowed_mints.len());
+    msg!("Pause flags: {:#04x}", config.pause_flags);
+    msg!("Layout version: {}", config.version);
     
     Ok(())
 }
+
+#[cfg(not(feature = "fixed-admin"))]
+fn is_initializer(program_data: &ProgramData, signer: &Pubkey) -> bool {
+    program_data.upgrade_authority_address == Some(*signer)
+}
+
+#[cfg(feature = "fixed-admin")]
+fn is_initializer(_program_data: &ProgramData, signer: &Pubkey) -> bool {
+    *signer == crate::states::ADMIN
+}
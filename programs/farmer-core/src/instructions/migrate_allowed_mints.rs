@@ -0,0 +1,436 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_spl::token_interface::Mint;
+use crate::errors::ConfigError;
+use crate::events::{AllowedMintAdded, AllowedMintsMigrated};
+use crate::instructions::add_allowed_mint::create_allowed_mint_account;
+use crate::states::{
+    AllowedMint, LegacyProgramConfig, ProgramConfig, CONFIG_VERSION, DEFAULT_FEE_NOTICE_PERIOD,
+    PAUSE_LEGACY_ALL, SEED_CONFIG, SEED_MINT,
+};
+
+/// Moves the original `ProgramConfig.allowed_mints` vector into `AllowedMint` PDAs.
+///
+/// For each of the first N legacy entries, pass the mint account and then its
+/// `AllowedMint` PDA as remaining accounts, in order. The decimals are read
+/// from the mint; the original program stored none, so the limits come from
+/// the arguments and apply to every entry of the call (migrate mints one per
+/// call to give them different limits). Migrated entries are dropped from the
+/// vector, so a long allowlist can be moved over several transactions. The
+/// original program did not deduplicate, so a mint listed twice keeps the
+/// entry created for its first listing and the repeat is only dropped. Once
+/// the vector is empty the config is rewritten in the current layout, shrunk to
+/// `ProgramConfig::SIZE` and the freed rent is returned to the admin.
+///
+/// The legacy and current layouts share a discriminator; a config larger
+/// than `ProgramConfig::SIZE` is treated as legacy.
+///
+/// # Arguments
+/// - `min_order_subtotal`: Smallest accepted order subtotal (minor units)
+/// - `max_order_subtotal`: Largest accepted single-order subtotal (minor units)
+/// - `service_fee_floor`: Optional minimum service fee per order (minor units)
+#[derive(Accounts)]
+pub struct MigrateAllowedMints<'info> {
+    /// CHECK: Decoded manually as `LegacyProgramConfig`; seeds and owner are checked here
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG],
+        bump,
+        owner = crate::ID
+    )]
+    pub config: UncheckedAccount<'info>,
+
+    /// The current admin authority (pays for the new entries)
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_allowed_mints<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MigrateAllowedMints<'info>>,
+    min_order_subtotal: u64,
+    max_order_subtotal: u64,
+    service_fee_floor: Option<u64>,
+) -> Result<()> {
+    let config_info = ctx.accounts.config.to_account_info();
+    let admin = &ctx.accounts.admin;
+
+    let mut legacy = decode_legacy_config(&config_info.try_borrow_data()?)?;
+
+    require_keys_eq!(legacy.admin, admin.key(), ConfigError::UnauthorizedAdmin);
+    require!(
+        ctx.remaining_accounts.len() % 2 == 0
+            && ctx.remaining_accounts.len() / 2 <= legacy.allowed_mints.len(),
+        ConfigError::InvalidAllowedMintAccount
+    );
+
+    let rent = Rent::get()?;
+    let migrated = ctx.remaining_accounts.len() / 2;
+
+    for (mint, accounts) in legacy
+        .allowed_mints
+        .iter()
+        .zip(ctx.remaining_accounts.chunks(2))
+    {
+        let (mint_info, target) = (&accounts[0], &accounts[1]);
+        require_keys_eq!(mint_info.key(), *mint, ConfigError::InvalidAllowedMintAccount);
+        // Checks the token program owner and the mint layout
+        let decimals = InterfaceAccount::<Mint>::try_from(mint_info)?.decimals;
+
+        let (expected, bump) =
+            Pubkey::find_program_address(&[SEED_MINT, mint.as_ref()], &crate::ID);
+        require_keys_eq!(target.key(), expected, ConfigError::InvalidAllowedMintAccount);
+        if is_migrated(target, mint)? {
+            continue;
+        }
+
+        let allowed_mint = AllowedMint {
+            mint: *mint,
+            decimals,
+            min_order_subtotal,
+            max_order_subtotal,
+            service_fee_floor,
+            bump,
+        };
+        allowed_mint.validate_limits()?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[SEED_MINT, mint.as_ref(), &[bump]]];
+        create_allowed_mint_account(
+            admin.to_account_info(),
+            target.clone(),
+            ctx.accounts.system_program.to_account_info(),
+            &rent,
+            signer_seeds,
+        )?;
+        allowed_mint.try_serialize(&mut &mut target.try_borrow_mut_data()?[..])?;
+
+        emit!(AllowedMintAdded {
+            admin: admin.key(),
+            mint: *mint,
+            decimals,
+            min_order_subtotal,
+            max_order_subtotal,
+            service_fee_floor,
+        });
+    }
+
+    legacy.allowed_mints.drain(..migrated);
+    let remaining = legacy.allowed_mints.len();
+
+    if remaining > 0 {
+        // Write back the shortened legacy vector and wait for the next batch
+        write_legacy_config(&mut config_info.try_borrow_mut_data()?, &legacy)?;
+    } else {
+        let config = current_config(&legacy);
+
+        config_info.resize(ProgramConfig::SIZE)?;
+        {
+            let mut data = config_info.try_borrow_mut_data()?;
+            data.fill(0);
+            config.try_serialize(&mut &mut data[..])?;
+        }
+
+        // Return the rent freed by the shrink
+        let excess = config_info
+            .lamports()
+            .saturating_sub(rent.minimum_balance(ProgramConfig::SIZE));
+        **config_info.try_borrow_mut_lamports()? -= excess;
+        **admin.to_account_info().try_borrow_mut_lamports()? += excess;
+    }
+
+    emit!(AllowedMintsMigrated {
+        admin: admin.key(),
+        migrated: migrated as u32,
+        remaining: remaining as u32,
+    });
+
+    msg!("Allowed mints migrated: {}", migrated);
+    msg!("Remaining legacy entries: {}", remaining);
+
+    Ok(())
+}
+
+/// Decodes a config still in the original layout. Fails with
+/// `ConfigAlreadyMigrated` if `data` is no larger than `ProgramConfig::SIZE`.
+pub fn decode_legacy_config(data: &[u8]) -> Result<LegacyProgramConfig> {
+    require!(
+        data.len() > ProgramConfig::SIZE,
+        ConfigError::ConfigAlreadyMigrated
+    );
+    require!(
+        data.starts_with(ProgramConfig::DISCRIMINATOR),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    let mut body: &[u8] = &data[ProgramConfig::DISCRIMINATOR.len()..];
+    Ok(LegacyProgramConfig::deserialize(&mut body)?)
+}
+
+/// True if `target` already holds the `AllowedMint` entry for `mint`, as when
+/// the legacy vector lists the mint twice. Any other existing data fails with
+/// `MintAlreadyAllowed`.
+fn is_migrated(target: &AccountInfo, mint: &Pubkey) -> Result<bool> {
+    if target.data_is_empty() {
+        return Ok(false);
+    }
+    require_keys_eq!(*target.owner, crate::ID, ConfigError::MintAlreadyAllowed);
+    let entry = AllowedMint::try_deserialize(&mut &target.try_borrow_data()?[..])?;
+    require_keys_eq!(entry.mint, *mint, ConfigError::MintAlreadyAllowed);
+    Ok(true)
+}
+
+/// Rewrites a partly migrated legacy config in place, zeroing the freed tail.
+fn write_legacy_config(data: &mut [u8], legacy: &LegacyProgramConfig) -> Result<()> {
+    let body = &mut data[ProgramConfig::DISCRIMINATOR.len()..];
+    body.fill(0);
+    legacy.serialize(&mut &mut body[..])?;
+    Ok(())
+}
+
+/// The config in the current layout once every legacy entry has moved out.
+/// A paused legacy config stays fully paused (`PAUSE_LEGACY_ALL`).
+fn current_config(legacy: &LegacyProgramConfig) -> ProgramConfig {
+    ProgramConfig {
+        admin: legacy.admin,
+        logistics_wallet: legacy.logistics_wallet,
+        pause_flags: if legacy.paused { PAUSE_LEGACY_ALL } else { 0 },
+        pending_admin: None,
+        version: CONFIG_VERSION,
+        fee_notice_period: DEFAULT_FEE_NOTICE_PERIOD,
+        protocol_fee_bps: 0,
+        reserved: [0; 54],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::{ProposeAdmin, SetPauseFlags, UpdateConfig};
+    use crate::states::PAUSE_ALL;
+    use anchor_lang::Bumps;
+    use std::collections::BTreeSet;
+
+    const ADMIN: Pubkey = Pubkey::new_from_array([1; 32]);
+    const LOGISTICS_WALLET: Pubkey = Pubkey::new_from_array([2; 32]);
+
+    /// A config account as written by the original program, byte by byte:
+    /// `paused: bool` and `Vec<Pubkey>` in a `LegacyProgramConfig::SIZE` account.
+    fn baseline_fixture(paused: bool, mints: &[Pubkey]) -> Vec<u8> {
+        let mut data = Vec::with_capacity(LegacyProgramConfig::SIZE);
+        data.extend_from_slice(ProgramConfig::DISCRIMINATOR);
+        data.extend_from_slice(ADMIN.as_ref());
+        data.extend_from_slice(LOGISTICS_WALLET.as_ref());
+        data.push(paused as u8);
+        data.extend_from_slice(&(mints.len() as u32).to_le_bytes());
+        for mint in mints {
+            data.extend_from_slice(mint.as_ref());
+        }
+        data.resize(LegacyProgramConfig::SIZE, 0);
+        data
+    }
+
+    fn mints(count: u8) -> Vec<Pubkey> {
+        (0..count)
+            .map(|i| Pubkey::new_from_array([10 + i; 32]))
+            .collect()
+    }
+
+    #[test]
+    fn decodes_baseline_layout() {
+        let mints = mints(3);
+        let legacy = decode_legacy_config(&baseline_fixture(false, &mints)).unwrap();
+
+        assert_eq!(legacy.admin, ADMIN);
+        assert_eq!(legacy.logistics_wallet, LOGISTICS_WALLET);
+        assert!(!legacy.paused);
+        assert_eq!(legacy.allowed_mints, mints);
+    }
+
+    #[test]
+    fn decodes_full_baseline_allowlist() {
+        let mints = mints(LegacyProgramConfig::MAX_ALLOWED_MINTS as u8);
+        let data = baseline_fixture(true, &mints);
+        assert_eq!(data.len(), LegacyProgramConfig::SIZE);
+
+        let legacy = decode_legacy_config(&data).unwrap();
+        assert!(legacy.paused);
+        assert_eq!(legacy.allowed_mints, mints);
+    }
+
+    #[test]
+    fn partial_migration_rewrites_a_decodable_baseline_config() {
+        let mut data = baseline_fixture(false, &mints(3));
+        let mut legacy = decode_legacy_config(&data).unwrap();
+        legacy.allowed_mints.drain(..2);
+
+        write_legacy_config(&mut data, &legacy).unwrap();
+
+        assert_eq!(data.len(), LegacyProgramConfig::SIZE);
+        assert_eq!(data, baseline_fixture(false, &mints(3)[2..]));
+        let reread = decode_legacy_config(&data).unwrap();
+        assert_eq!(reread.allowed_mints, [Pubkey::new_from_array([12; 32])]);
+    }
+
+    #[test]
+    fn repeated_mint_reuses_its_entry() {
+        let mint = Pubkey::new_from_array([10; 32]);
+        let key = Pubkey::find_program_address(&[SEED_MINT, mint.as_ref()], &crate::ID).0;
+        let mut lamports = 0;
+        let mut data = Vec::new();
+        AllowedMint {
+            mint,
+            decimals: 6,
+            min_order_subtotal: 0,
+            max_order_subtotal: 1,
+            service_fee_floor: None,
+            bump: 255,
+        }
+        .try_serialize(&mut data)
+        .unwrap();
+        let target = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &crate::ID,
+            false,
+            0,
+        );
+        assert!(is_migrated(&target, &mint).unwrap());
+        assert_eq!(
+            is_migrated(&target, &Pubkey::new_from_array([11; 32])).unwrap_err(),
+            ConfigError::MintAlreadyAllowed.into()
+        );
+
+        // Baseline `init_config` kept duplicates; both listings leave the vector
+        let mut legacy = decode_legacy_config(&baseline_fixture(false, &[mint, mint])).unwrap();
+        legacy.allowed_mints.drain(..2);
+        assert!(legacy.allowed_mints.is_empty());
+    }
+
+    #[test]
+    fn empty_target_is_not_migrated() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = [];
+        let target = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &system_program::ID,
+            false,
+            0,
+        );
+        assert!(!is_migrated(&target, &Pubkey::new_unique()).unwrap());
+    }
+
+    /// Runs the account checks of `T` against a config holding `data` and an
+    /// admin signer, as the runtime would before the handler.
+    fn check_accounts<T>(data: Vec<u8>) -> Result<T>
+    where
+        T: Accounts<'static, T::Bumps> + Bumps,
+        T::Bumps: Default,
+    {
+        let config = Box::leak(Box::new(
+            Pubkey::find_program_address(&[SEED_CONFIG], &crate::ID).0,
+        ));
+        let accounts: &'static [AccountInfo<'static>] = Box::leak(Box::new([
+            AccountInfo::new(
+                config,
+                false,
+                true,
+                Box::leak(Box::new(1_000_000_000)),
+                Box::leak(data.into_boxed_slice()),
+                &crate::ID,
+                false,
+                0,
+            ),
+            AccountInfo::new(
+                &ADMIN,
+                true,
+                false,
+                Box::leak(Box::new(0)),
+                Box::leak(Box::new([])),
+                &system_program::ID,
+                false,
+                0,
+            ),
+        ]));
+        T::try_accounts(
+            &crate::ID,
+            &mut &accounts[..],
+            &[],
+            &mut T::Bumps::default(),
+            &mut BTreeSet::new(),
+        )
+    }
+
+    #[test]
+    fn admin_instructions_reject_an_unmigrated_config() {
+        // The mint's last byte is read as `version`, so only the size gives it away
+        let data = baseline_fixture(false, &[Pubkey::new_from_array([CONFIG_VERSION; 32])]);
+        for result in [
+            check_accounts::<SetPauseFlags>(data.clone()).map(|_| ()),
+            check_accounts::<UpdateConfig>(data.clone()).map(|_| ()),
+            check_accounts::<ProposeAdmin>(data).map(|_| ()),
+        ] {
+            assert_eq!(result.unwrap_err(), ConfigError::ConfigNotMigrated.into());
+        }
+
+        let legacy = decode_legacy_config(&baseline_fixture(false, &[])).unwrap();
+        let serialized = |version| {
+            let mut config = current_config(&legacy);
+            config.version = version;
+            let mut data = Vec::new();
+            config.try_serialize(&mut data).unwrap();
+            data.resize(ProgramConfig::SIZE, 0);
+            data
+        };
+        assert!(check_accounts::<SetPauseFlags>(serialized(CONFIG_VERSION)).is_ok());
+        // A current-size config still on an older version is refused too
+        assert_eq!(
+            check_accounts::<SetPauseFlags>(serialized(2)).map(|_| ()).unwrap_err(),
+            ConfigError::ConfigNotMigrated.into()
+        );
+    }
+
+    #[test]
+    fn migrated_config_keeps_admin_and_pause_state() {
+        let legacy = decode_legacy_config(&baseline_fixture(true, &[])).unwrap();
+        let config = current_config(&legacy);
+
+        assert_eq!(config.admin, ADMIN);
+        assert_eq!(config.logistics_wallet, LOGISTICS_WALLET);
+        assert_eq!(config.pause_flags, PAUSE_LEGACY_ALL);
+        assert!(config.require_not_paused(PAUSE_ALL).is_err());
+        assert_eq!(config.pending_admin, None);
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.fee_notice_period, DEFAULT_FEE_NOTICE_PERIOD);
+
+        let unpaused = decode_legacy_config(&baseline_fixture(false, &[])).unwrap();
+        assert_eq!(current_config(&unpaused).pause_flags, 0);
+    }
+
+    #[test]
+    fn rejects_current_layout() {
+        let mut data = vec![0; ProgramConfig::SIZE];
+        data[..8].copy_from_slice(ProgramConfig::DISCRIMINATOR);
+        assert_eq!(
+            decode_legacy_config(&data).err().unwrap(),
+            ConfigError::ConfigAlreadyMigrated.into()
+        );
+    }
+
+    #[test]
+    fn rejects_other_discriminator() {
+        let mut data = baseline_fixture(false, &mints(1));
+        data[0] ^= 0xff;
+        assert_eq!(
+            decode_legacy_config(&data).err().unwrap(),
+            ErrorCode::AccountDiscriminatorMismatch.into()
+        );
+    }
+}
@@ -0,0 +1,305 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use crate::errors::ConfigError;
+use crate::events::ConfigMigrated;
+use crate::states::{
+    ProgramConfig, ProgramConfigV0, CONFIG_VERSION, DEFAULT_FEE_NOTICE_PERIOD, SEED_CONFIG,
+};
+
+/// Upgrades the config account to the current `ProgramConfig` layout.
+///
+/// Older layouts are decoded, the PDA is reallocated to `ProgramConfig::SIZE`
+/// (the admin pays any extra rent) and the config is rewritten with
+/// `version = CONFIG_VERSION`. Every other instruction expects the current
+/// layout, so run this once after each upgrade that changes it.
+///
+/// Configs that still carry the legacy allowlist vector must go through
+/// `migrate_allowed_mints` instead; it writes the current layout directly.
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// CHECK: Decoded manually by `upgrade_config_data`; seeds and owner are checked here
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG],
+        bump,
+        owner = crate::ID
+    )]
+    pub config: UncheckedAccount<'info>,
+
+    /// The current admin authority (pays rent for the extra space)
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+    let config_info = ctx.accounts.config.to_account_info();
+    let admin = &ctx.accounts.admin;
+
+    let (old_version, config) = upgrade_config_data(&config_info.try_borrow_data()?)?;
+    require_keys_eq!(config.admin, admin.key(), ConfigError::UnauthorizedAdmin);
+
+    // Top up rent before growing the account
+    let required = Rent::get()?.minimum_balance(ProgramConfig::SIZE);
+    let current = config_info.lamports();
+    if current < required {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: admin.to_account_info(),
+                    to: config_info.clone(),
+                },
+            ),
+            required - current,
+        )?;
+    }
+
+    config_info.resize(ProgramConfig::SIZE)?;
+    {
+        let mut data = config_info.try_borrow_mut_data()?;
+        data.fill(0);
+        config.try_serialize(&mut &mut data[..])?;
+    }
+
+    emit!(ConfigMigrated {
+        admin: admin.key(),
+        old_version,
+        new_version: config.version,
+    });
+
+    msg!("Program config migrated");
+    msg!("Layout version: {} -> {}", old_version, config.version);
+
+    Ok(())
+}
+
+/// Decodes a config stored in an older layout.
+///
+/// Returns the stored layout version and the config converted to the current
+/// layout. Fails with `ConfigAlreadyMigrated` if `data` is already current.
+pub fn upgrade_config_data(data: &[u8]) -> Result<(u8, ProgramConfig)> {
+    require!(
+        data.starts_with(ProgramConfig::DISCRIMINATOR),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    let mut body: &[u8] = &data[ProgramConfig::DISCRIMINATOR.len()..];
+
+    match data.len() {
+        ProgramConfig::SIZE => {
+            let stored = ProgramConfig::deserialize(&mut body)?;
+            match stored.version {
+                CONFIG_VERSION => err!(ConfigError::ConfigAlreadyMigrated),
+                // v1 -> v3: `fee_notice_period` and `protocol_fee_bps` were carved out
+                // of the zeroed `reserved`
+                1 => Ok((
+                    1,
+                    ProgramConfig {
+                        version: CONFIG_VERSION,
+                        fee_notice_period: DEFAULT_FEE_NOTICE_PERIOD,
+                        protocol_fee_bps: 0,
+                        reserved: [0; 54],
+                        ..stored
+                    },
+                )),
+                // v2 -> v3: `protocol_fee_bps` was carved out of `reserved`; 0 = no cut
+                2 => Ok((
+                    2,
+                    ProgramConfig {
+                        version: CONFIG_VERSION,
+                        protocol_fee_bps: 0,
+                        reserved: [0; 54],
+                        ..stored
+                    },
+                )),
+                _ => err!(ConfigError::UnknownConfigLayout),
+            }
+        }
+        ProgramConfigV0::SIZE => {
+            let old = ProgramConfigV0::deserialize(&mut body)?;
+            Ok((
+                0,
+                ProgramConfig {
+                    admin: old.admin,
+                    logistics_wallet: old.logistics_wallet,
+                    pause_flags: old.pause_flags,
+                    pending_admin: old.pending_admin,
+                    version: CONFIG_VERSION,
+                    fee_notice_period: DEFAULT_FEE_NOTICE_PERIOD,
+                    protocol_fee_bps: 0,
+                    reserved: [0; 54],
+                },
+            ))
+        }
+        len if len > ProgramConfig::SIZE => err!(ConfigError::LegacyAllowlistPending),
+        _ => err!(ConfigError::UnknownConfigLayout),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::states::{PAUSE_NEW_ORDERS, PAUSE_REFUNDS};
+
+    const ADMIN: Pubkey = Pubkey::new_from_array([1; 32]);
+    const LOGISTICS_WALLET: Pubkey = Pubkey::new_from_array([2; 32]);
+    const PENDING_ADMIN: Pubkey = Pubkey::new_from_array([3; 32]);
+
+    /// A config account as written by the unversioned (v0) program, byte by byte.
+    fn v0_fixture(pending_admin: Option<Pubkey>) -> Vec<u8> {
+        let mut data = Vec::with_capacity(ProgramConfigV0::SIZE);
+        data.extend_from_slice(ProgramConfig::DISCRIMINATOR);
+        data.extend_from_slice(ADMIN.as_ref());
+        data.extend_from_slice(LOGISTICS_WALLET.as_ref());
+        data.push(PAUSE_NEW_ORDERS | PAUSE_REFUNDS);
+        match pending_admin {
+            Some(key) => {
+                data.push(1);
+                data.extend_from_slice(key.as_ref());
+            }
+            // Anchor allocates the full `Option` even when it is `None`
+            None => data.extend_from_slice(&[0; 33]),
+        }
+        assert_eq!(data.len(), ProgramConfigV0::SIZE);
+        data
+    }
+
+    /// A config account as written by the v1 program (`version` + 64 reserved bytes).
+    fn v1_fixture() -> Vec<u8> {
+        let mut data = v0_fixture(Some(PENDING_ADMIN));
+        data.push(1);
+        data.extend_from_slice(&[0; 64]);
+        assert_eq!(data.len(), ProgramConfig::SIZE);
+        data
+    }
+
+    /// A config account as written by the v2 program (`fee_notice_period` + 56 reserved bytes).
+    fn v2_fixture(fee_notice_period: i64) -> Vec<u8> {
+        let mut data = v0_fixture(Some(PENDING_ADMIN));
+        data.push(2);
+        data.extend_from_slice(&fee_notice_period.to_le_bytes());
+        data.extend_from_slice(&[0; 56]);
+        assert_eq!(data.len(), ProgramConfig::SIZE);
+        data
+    }
+
+    /// Runs the upgrade and writes the result the way `migrate_config` does.
+    fn migrate(data: &[u8]) -> (u8, Vec<u8>) {
+        let (old_version, config) = upgrade_config_data(data).unwrap();
+        let mut migrated = vec![0; ProgramConfig::SIZE];
+        config.try_serialize(&mut &mut migrated[..]).unwrap();
+        (old_version, migrated)
+    }
+
+    #[test]
+    fn upgrades_v0_layout_in_place() {
+        let (old_version, migrated) = migrate(&v0_fixture(Some(PENDING_ADMIN)));
+        let config = ProgramConfig::try_deserialize(&mut &migrated[..]).unwrap();
+
+        assert_eq!(old_version, 0);
+        assert_eq!(config.admin, ADMIN);
+        assert_eq!(config.logistics_wallet, LOGISTICS_WALLET);
+        assert_eq!(config.pause_flags, PAUSE_NEW_ORDERS | PAUSE_REFUNDS);
+        assert_eq!(config.pending_admin, Some(PENDING_ADMIN));
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.fee_notice_period, DEFAULT_FEE_NOTICE_PERIOD);
+        assert_eq!(config.protocol_fee_bps, 0);
+        assert_eq!(config.reserved, [0; 54]);
+    }
+
+    #[test]
+    fn upgrades_v0_layout_without_pending_admin() {
+        let (_, migrated) = migrate(&v0_fixture(None));
+        let config = ProgramConfig::try_deserialize(&mut &migrated[..]).unwrap();
+
+        assert_eq!(config.admin, ADMIN);
+        assert_eq!(config.pending_admin, None);
+    }
+
+    #[test]
+    fn upgrades_v1_layout_in_place() {
+        let (old_version, migrated) = migrate(&v1_fixture());
+        let config = ProgramConfig::try_deserialize(&mut &migrated[..]).unwrap();
+
+        assert_eq!(old_version, 1);
+        assert_eq!(config.admin, ADMIN);
+        assert_eq!(config.logistics_wallet, LOGISTICS_WALLET);
+        assert_eq!(config.pause_flags, PAUSE_NEW_ORDERS | PAUSE_REFUNDS);
+        assert_eq!(config.pending_admin, Some(PENDING_ADMIN));
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.fee_notice_period, DEFAULT_FEE_NOTICE_PERIOD);
+        assert_eq!(config.protocol_fee_bps, 0);
+        assert_eq!(config.reserved, [0; 54]);
+    }
+
+    #[test]
+    fn upgrades_v2_layout_in_place() {
+        let (old_version, migrated) = migrate(&v2_fixture(3600));
+        let config = ProgramConfig::try_deserialize(&mut &migrated[..]).unwrap();
+
+        assert_eq!(old_version, 2);
+        assert_eq!(config.admin, ADMIN);
+        assert_eq!(config.pending_admin, Some(PENDING_ADMIN));
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.fee_notice_period, 3600);
+        assert_eq!(config.protocol_fee_bps, 0);
+        assert_eq!(config.reserved, [0; 54]);
+    }
+
+    #[test]
+    fn rejects_unknown_version() {
+        let mut data = v1_fixture();
+        data[ProgramConfigV0::SIZE] = CONFIG_VERSION + 1;
+        assert_eq!(
+            upgrade_config_data(&data).err().unwrap(),
+            ConfigError::UnknownConfigLayout.into()
+        );
+    }
+
+    #[test]
+    fn v0_layout_does_not_decode_as_current() {
+        let data = v0_fixture(None);
+        assert!(ProgramConfig::try_deserialize(&mut &data[..]).is_err());
+    }
+
+    #[test]
+    fn rejects_current_layout() {
+        let (_, migrated) = migrate(&v0_fixture(None));
+        assert_eq!(
+            upgrade_config_data(&migrated).err().unwrap(),
+            ConfigError::ConfigAlreadyMigrated.into()
+        );
+    }
+
+    #[test]
+    fn rejects_legacy_allowlist_layout() {
+        let mut data = v0_fixture(None);
+        data.resize(ProgramConfig::SIZE + 1, 0);
+        assert_eq!(
+            upgrade_config_data(&data).err().unwrap(),
+            ConfigError::LegacyAllowlistPending.into()
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_size() {
+        let mut data = v0_fixture(None);
+        data.pop();
+        assert_eq!(
+            upgrade_config_data(&data).err().unwrap(),
+            ConfigError::UnknownConfigLayout.into()
+        );
+    }
+
+    #[test]
+    fn rejects_other_discriminator() {
+        let mut data = v0_fixture(None);
+        data[0] ^= 0xff;
+        assert_eq!(
+            upgrade_config_data(&data).err().unwrap(),
+            ErrorCode::AccountDiscriminatorMismatch.into()
+        );
+    }
+}
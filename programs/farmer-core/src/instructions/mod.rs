@@ -1,2 +1,68 @@
+pub use accept_admin::*;
+pub use add_allowed_mint::*;
+pub use approve_affiliation::*;
+pub use cancel_admin_transfer::*;
+pub use check_coverage::*;
+pub use close_customer_profile::*;
+pub use close_farmer_profile::*;
+pub use close_offer::*;
+pub use confirm_customer::*;
+pub use confirm_customers::*;
+pub use create_warehouse::*;
+pub use deactivate_offer::*;
+pub use end_affiliation::*;
+pub use grant_staff_roles::*;
 pub use init_config::*;
-pub mod init_config;
\ No newline at end of file
+pub use migrate_allowed_mints::*;
+pub use migrate_config::*;
+pub use propose_admin::*;
+pub use publish_offer::*;
+pub use register_customer::*;
+pub use register_farmer::*;
+pub use reject_affiliation::*;
+pub use remove_allowed_mint::*;
+pub use renew_confirmation::*;
+pub use request_customer_confirmation::*;
+pub use request_warehouse_affiliation::*;
+pub use revoke_customer::*;
+pub use revoke_staff_roles::*;
+pub use set_pause_flags::*;
+pub use set_warehouse_status::*;
+pub use update_config::*;
+pub use update_farmer_profile::*;
+pub use update_warehouse::*;
+pub use withdraw_from_warehouse::*;
+pub mod accept_admin;
+pub mod add_allowed_mint;
+pub mod approve_affiliation;
+pub mod cancel_admin_transfer;
+pub mod check_coverage;
+pub mod close_customer_profile;
+pub mod close_farmer_profile;
+pub mod close_offer;
+pub mod confirm_customer;
+pub mod confirm_customers;
+pub mod create_warehouse;
+pub mod deactivate_offer;
+pub mod end_affiliation;
+pub mod grant_staff_roles;
+pub mod init_config;
+pub mod migrate_allowed_mints;
+pub mod migrate_config;
+pub mod propose_admin;
+pub mod publish_offer;
+pub mod register_customer;
+pub mod register_farmer;
+pub mod reject_affiliation;
+pub mod remove_allowed_mint;
+pub mod renew_confirmation;
+pub mod request_customer_confirmation;
+pub mod request_warehouse_affiliation;
+pub mod revoke_customer;
+pub mod revoke_staff_roles;
+pub mod set_pause_flags;
+pub mod set_warehouse_status;
+pub mod update_config;
+pub mod update_farmer_profile;
+pub mod update_warehouse;
+pub mod withdraw_from_warehouse;
\ No newline at end of file
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::errors::ConfigError;
+use crate::events::AdminTransferProposed;
+use crate::states::{ProgramConfig, SEED_CONFIG};
+
+/// Proposes a new admin for the program configuration.
+///
+/// First step of the two-step admin handover. The proposed key only becomes
+/// admin once it signs `accept_admin`, so a typo'd key can never take
+/// control. Proposing again replaces any previous pending admin.
+///
+/// # Arguments
+/// - `new_admin`: The key that will be allowed to accept the admin role
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated,
+        has_one = admin @ ConfigError::UnauthorizedAdmin
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// The current admin authority
+    pub admin: Signer<'info>,
+}
+
+pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    require_keys_neq!(new_admin, config.admin, ConfigError::AdminUnchanged);
+
+    config.pending_admin = Some(new_admin);
+
+    emit!(AdminTransferProposed {
+        admin: config.admin,
+        pending_admin: new_admin,
+    });
+
+    msg!("Admin transfer proposed");
+    msg!("Pending admin: {}", new_admin);
+
+    Ok(())
+}
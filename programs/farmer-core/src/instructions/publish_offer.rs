@@ -0,0 +1,160 @@
+use anchor_lang::prelude::*;
+use crate::errors::{ConfigError, OrderError};
+use crate::events::OfferPublished;
+use crate::states::{
+    AllowedMint, FarmerAffiliation, FarmerProfile, LotOffer, ProgramConfig, Warehouse,
+    PAUSE_NEW_OFFERS, SEED_AFFILIATION, SEED_CONFIG, SEED_FARMER, SEED_MINT, SEED_OFFER,
+    SEED_WAREHOUSE,
+};
+
+/// Publishes a `LotOffer` fulfilled by one of the farmer's warehouses.
+///
+/// Signed by the farmer or its delegate; the signer pays the rent and is
+/// recorded as `rent_payer`, which `close_offer` refunds. The
+/// warehouse is picked by passing it with the matching affiliation, which must
+/// be approved. The offer id is the profile's `offer_counter`, so the offer
+/// address is known before sending. Blocked while new offers are paused or the
+/// warehouse is suspended or closed; the mint must be allowed.
+///
+/// # Arguments
+/// - `crop_name`: Public crop name (1..=`MAX_NAME_LEN` bytes)
+/// - `cultivar_name`: Optional cultivar (max `MAX_NAME_LEN` bytes)
+/// - `unit_code`: Client-side unit mapping (see README, Unit Encoding)
+/// - `qty`: Quantity on offer, in base units (> 0)
+/// - `price_minor`: Price per base unit, in the mint's minor units (> 0)
+/// - `expires_at`: Optional unix timestamp after which the offer stops selling
+/// - `notes_public`: Optional public notes (max `MAX_NOTES_LEN` bytes)
+#[derive(Accounts)]
+pub struct PublishOffer<'info> {
+    #[account(
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        seeds = [SEED_FARMER, farmer_profile.authority.as_ref()],
+        bump = farmer_profile.bump
+    )]
+    pub farmer_profile: Account<'info, FarmerProfile>,
+
+    #[account(
+        mut,
+        seeds = [
+            SEED_AFFILIATION,
+            farmer_profile.authority.as_ref(),
+            warehouse.key().as_ref()
+        ],
+        bump = affiliation.bump
+    )]
+    pub affiliation: Account<'info, FarmerAffiliation>,
+
+    #[account(
+        seeds = [SEED_WAREHOUSE, warehouse.warehouse_id.to_le_bytes().as_ref()],
+        bump = warehouse.bump
+    )]
+    pub warehouse: Account<'info, Warehouse>,
+
+    #[account(
+        seeds = [SEED_MINT, allowed_mint.mint.as_ref()],
+        bump = allowed_mint.bump
+    )]
+    pub allowed_mint: Account<'info, AllowedMint>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = LotOffer::SIZE,
+        seeds = [
+            SEED_OFFER,
+            farmer_profile.authority.as_ref(),
+            farmer_profile.offer_counter.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub offer: Account<'info, LotOffer>,
+
+    /// The farmer or its delegate (pays for the offer)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn publish_offer(
+    ctx: Context<PublishOffer>,
+    crop_name: String,
+    cultivar_name: Option<String>,
+    unit_code: u16,
+    qty: u64,
+    price_minor: u64,
+    expires_at: Option<i64>,
+    notes_public: Option<String>,
+) -> Result<()> {
+    ctx.accounts
+        .farmer_profile
+        .require_offer_signer(ctx.accounts.authority.key)?;
+    ctx.accounts.config.require_not_paused(PAUSE_NEW_OFFERS)?;
+    ctx.accounts.warehouse.require_open_for(PAUSE_NEW_OFFERS)?;
+    ctx.accounts.affiliation.require_active()?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let profile = &mut ctx.accounts.farmer_profile;
+    let affiliation = &mut ctx.accounts.affiliation;
+    let offer = &mut ctx.accounts.offer;
+
+    offer.farmer = profile.authority;
+    offer.warehouse = affiliation.warehouse;
+    offer.offer_id = profile.offer_counter;
+    offer.crop_name = crop_name;
+    offer.cultivar_name = cultivar_name;
+    offer.unit_code = unit_code;
+    offer.expires_at = expires_at;
+    offer.notes_public = notes_public;
+    offer.mint = ctx.accounts.allowed_mint.mint;
+    offer.price_minor = price_minor;
+    offer.qty_remaining = qty;
+    offer.active = true;
+    offer.created_at = now;
+    offer.rent_payer = ctx.accounts.authority.key();
+    offer.bump = ctx.bumps.offer;
+    offer.validate(now)?;
+
+    profile.offer_counter = profile
+        .offer_counter
+        .checked_add(1)
+        .ok_or(OrderError::MathOverflow)?;
+    profile.active_offers = profile
+        .active_offers
+        .checked_add(1)
+        .ok_or(OrderError::MathOverflow)?;
+    profile.offer_accounts = profile
+        .offer_accounts
+        .checked_add(1)
+        .ok_or(OrderError::MathOverflow)?;
+    affiliation.active_offers = affiliation
+        .active_offers
+        .checked_add(1)
+        .ok_or(OrderError::MathOverflow)?;
+
+    emit!(OfferPublished {
+        offer: offer.key(),
+        farmer: offer.farmer,
+        warehouse: offer.warehouse,
+        crop_name: offer.crop_name.clone(),
+        cultivar_name: offer.cultivar_name.clone(),
+        unit_code,
+        qty,
+        price_minor,
+        mint: offer.mint,
+        expires_at,
+    });
+
+    msg!("Offer published: {} #{}", offer.farmer, offer.offer_id);
+    msg!("Warehouse: {}", offer.warehouse);
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use crate::errors::ConfigError;
+use crate::events::CustomerRegistered;
+use crate::states::{CustomerProfile, ProgramConfig, PAUSE_ONBOARDING, SEED_CONFIG, SEED_CUSTOMER};
+
+/// Creates the signer's `CustomerProfile` PDA.
+///
+/// Any key can register once; the profile address is derived from it. Blocked
+/// while onboarding is paused. The profile is public and holds no address: a
+/// warehouse verifies that off-chain before `confirm_customer`.
+///
+/// # Arguments
+/// - `public_profile_uri`: Public profile page (max `MAX_URI_LEN` bytes, https/ipfs/ar);
+///   empty for none
+#[derive(Accounts)]
+pub struct RegisterCustomer<'info> {
+    #[account(
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = CustomerProfile::SIZE,
+        seeds = [SEED_CUSTOMER, authority.key().as_ref()],
+        bump
+    )]
+    pub customer_profile: Account<'info, CustomerProfile>,
+
+    /// The customer (pays for the profile)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_customer(ctx: Context<RegisterCustomer>, public_profile_uri: String) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_ONBOARDING)?;
+
+    let profile = &mut ctx.accounts.customer_profile;
+
+    profile.authority = ctx.accounts.authority.key();
+    profile.public_profile_uri = public_profile_uri;
+    profile.order_counter = 0;
+    profile.open_orders = 0;
+    profile.linked_warehouses = 0;
+    profile.created_at = Clock::get()?.unix_timestamp;
+    profile.bump = ctx.bumps.customer_profile;
+    profile.validate()?;
+
+    emit!(CustomerRegistered {
+        customer: profile.key(),
+        authority: profile.authority,
+        public_profile_uri: profile.public_profile_uri.clone(),
+    });
+
+    msg!("Customer registered: {}", profile.authority);
+
+    Ok(())
+}
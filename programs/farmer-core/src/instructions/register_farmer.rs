@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use crate::errors::ConfigError;
+use crate::events::FarmerRegistered;
+use crate::states::{FarmerProfile, ProgramConfig, PAUSE_ONBOARDING, SEED_CONFIG, SEED_FARMER};
+
+/// Creates the signer's `FarmerProfile` PDA.
+///
+/// Any key can register once; the profile address is derived from it. Blocked
+/// while onboarding is paused. The farmer pays the rent and gets it back with
+/// `close_farmer_profile`. Payouts go to the farmer key until
+/// `update_farmer_profile` sets another `payout_wallet`; no delegate is set.
+///
+/// # Arguments
+/// - `display_name`: Public name (1..=`MAX_NAME_LEN` bytes)
+/// - `public_profile_uri`: Public profile page (max `MAX_URI_LEN` bytes, https/ipfs/ar);
+///   empty for none
+#[derive(Accounts)]
+pub struct RegisterFarmer<'info> {
+    #[account(
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = FarmerProfile::SIZE,
+        seeds = [SEED_FARMER, authority.key().as_ref()],
+        bump
+    )]
+    pub farmer_profile: Account<'info, FarmerProfile>,
+
+    /// The farmer (pays for the profile)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_farmer(
+    ctx: Context<RegisterFarmer>,
+    display_name: String,
+    public_profile_uri: String,
+) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_ONBOARDING)?;
+
+    let profile = &mut ctx.accounts.farmer_profile;
+
+    profile.authority = ctx.accounts.authority.key();
+    profile.payout_wallet = profile.authority;
+    profile.delegate = None;
+    profile.display_name = display_name;
+    profile.public_profile_uri = public_profile_uri;
+    profile.offer_counter = 0;
+    profile.active_offers = 0;
+    profile.offer_accounts = 0;
+    profile.open_orders = 0;
+    profile.created_at = Clock::get()?.unix_timestamp;
+    profile.bump = ctx.bumps.farmer_profile;
+    profile.validate()?;
+
+    emit!(FarmerRegistered {
+        farmer: profile.key(),
+        authority: profile.authority,
+        display_name: profile.display_name.clone(),
+        public_profile_uri: profile.public_profile_uri.clone(),
+    });
+
+    msg!("Farmer registered: {}", profile.authority);
+    msg!("Display name: {}", profile.display_name);
+
+    Ok(())
+}
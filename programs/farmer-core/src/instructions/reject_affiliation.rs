@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use crate::errors::WarehouseError;
+use crate::events::AffiliationRejected;
+use crate::states::{
+    AffiliationStatus, FarmerAffiliation, Warehouse, SEED_AFFILIATION, SEED_WAREHOUSE,
+};
+
+/// Declines a farmer's pending request.
+///
+/// Signed by the warehouse operator. The farmer may send a new request; the
+/// affiliation PDA stays until the farmer or operator calls `end_affiliation`.
+#[derive(Accounts)]
+pub struct RejectAffiliation<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_AFFILIATION, affiliation.farmer.as_ref(), warehouse.key().as_ref()],
+        bump = affiliation.bump
+    )]
+    pub affiliation: Account<'info, FarmerAffiliation>,
+
+    #[account(
+        seeds = [SEED_WAREHOUSE, warehouse.warehouse_id.to_le_bytes().as_ref()],
+        bump = warehouse.bump,
+        has_one = operator @ WarehouseError::UnauthorizedOperator
+    )]
+    pub warehouse: Account<'info, Warehouse>,
+
+    /// The warehouse operator
+    pub operator: Signer<'info>,
+}
+
+pub fn reject_affiliation(ctx: Context<RejectAffiliation>) -> Result<()> {
+    let affiliation = &mut ctx.accounts.affiliation;
+    affiliation.require_pending()?;
+
+    affiliation.status = AffiliationStatus::Rejected;
+    affiliation.decided_at = Clock::get()?.unix_timestamp;
+
+    emit!(AffiliationRejected {
+        affiliation: affiliation.key(),
+        farmer: affiliation.farmer,
+        operator: ctx.accounts.operator.key(),
+        warehouse: affiliation.warehouse,
+    });
+
+    msg!("Affiliation rejected: {}", affiliation.farmer);
+    msg!("Warehouse: {}", affiliation.warehouse);
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use crate::errors::ConfigError;
+use crate::events::AllowedMintRemoved;
+use crate::states::{AllowedMint, ProgramConfig, SEED_CONFIG, SEED_MINT};
+
+/// Removes a single mint from the allowlist by closing its `AllowedMint` PDA.
+///
+/// Rent is returned to the admin. Fails if the mint has no entry.
+#[derive(Accounts)]
+pub struct RemoveAllowedMint<'info> {
+    #[account(
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated,
+        has_one = admin @ ConfigError::UnauthorizedAdmin
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [SEED_MINT, allowed_mint.mint.as_ref()],
+        bump = allowed_mint.bump
+    )]
+    pub allowed_mint: Account<'info, AllowedMint>,
+
+    /// The current admin authority (receives the rent)
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+pub fn remove_allowed_mint(ctx: Context<RemoveAllowedMint>) -> Result<()> {
+    let mint = ctx.accounts.allowed_mint.mint;
+
+    emit!(AllowedMintRemoved {
+        admin: ctx.accounts.admin.key(),
+        mint,
+    });
+
+    msg!("Allowed mint removed: {}", mint);
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use crate::errors::ConfigError;
+use crate::events::CustomerConfirmationRenewed;
+use crate::states::{
+    ProgramConfig, Warehouse, WarehouseCustomer, WarehouseStaff, PAUSE_ONBOARDING,
+    ROLE_CONFIRM_CUSTOMERS, SEED_CONFIG, SEED_STAFF, SEED_WAREHOUSE, SEED_WCUSTOMER,
+};
+
+/// Extends a customer confirmation after the warehouse has re-verified the
+/// address off-chain.
+///
+/// Works on expired confirmations too, so a customer who has not moved keeps
+/// the same entry. Signed by the warehouse operator or staff holding
+/// `ROLE_CONFIRM_CUSTOMERS`. Blocked while onboarding is paused or the
+/// warehouse is closed. Resets `confirmed_at` to now.
+///
+/// # Arguments
+/// - `notes_hash`: Hash of the new off-chain record; `None` keeps the current one
+/// - `valid_until`: New expiry; `None` applies `Warehouse.confirmation_validity` from now
+#[derive(Accounts)]
+pub struct RenewConfirmation<'info> {
+    #[account(
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        seeds = [SEED_WAREHOUSE, warehouse.warehouse_id.to_le_bytes().as_ref()],
+        bump = warehouse.bump
+    )]
+    pub warehouse: Account<'info, Warehouse>,
+
+    /// The signer's staff grant; only needed when signing as staff
+    #[account(
+        seeds = [SEED_STAFF, warehouse.key().as_ref(), authority.key().as_ref()],
+        bump = staff.bump
+    )]
+    pub staff: Option<Account<'info, WarehouseStaff>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SEED_WCUSTOMER,
+            warehouse.key().as_ref(),
+            warehouse_customer.customer.as_ref()
+        ],
+        bump = warehouse_customer.bump
+    )]
+    pub warehouse_customer: Account<'info, WarehouseCustomer>,
+
+    /// The warehouse operator or staff with `ROLE_CONFIRM_CUSTOMERS`
+    pub authority: Signer<'info>,
+}
+
+pub fn renew_confirmation(
+    ctx: Context<RenewConfirmation>,
+    notes_hash: Option<[u8; 32]>,
+    valid_until: Option<i64>,
+) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_ONBOARDING)?;
+    ctx.accounts.warehouse.require_open_for(PAUSE_ONBOARDING)?;
+    let authority = ctx.accounts.authority.key();
+    ctx.accounts.warehouse.require_role(
+        &authority,
+        ctx.accounts.staff.as_deref(),
+        ROLE_CONFIRM_CUSTOMERS,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let new_valid_until = ctx.accounts.warehouse.confirmation_expiry(valid_until, now)?;
+
+    let entry = &mut ctx.accounts.warehouse_customer;
+    entry.require_renewable()?;
+
+    let old_valid_until = entry.valid_until;
+    entry.confirmed_at = Some(now);
+    entry.valid_until = new_valid_until;
+    if notes_hash.is_some() {
+        entry.notes_hash = notes_hash;
+    }
+
+    emit!(CustomerConfirmationRenewed {
+        warehouse_customer: entry.key(),
+        warehouse: entry.warehouse,
+        customer: entry.customer,
+        authority,
+        notes_hash: entry.notes_hash,
+        old_valid_until,
+        new_valid_until,
+    });
+
+    msg!("Customer confirmation renewed: {}", entry.customer);
+    msg!("Valid until: {:?}", new_valid_until);
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+use crate::errors::{ConfigError, CustomerError, OrderError};
+use crate::events::CustomerConfirmationRequested;
+use crate::states::{
+    AddressEnvelope, CustomerProfile, ProgramConfig, Warehouse, WarehouseCustomer,
+    WarehouseCustomerStatus, PAUSE_ONBOARDING, SEED_CONFIG, SEED_CUSTOMER, SEED_WAREHOUSE,
+    SEED_WCUSTOMER,
+};
+
+/// Asks a warehouse to confirm the signer as a customer.
+///
+/// Creates the `WarehouseCustomer` for this pair in `Pending` (counted in
+/// `CustomerProfile.linked_warehouses`), or moves a revoked or expired one back
+/// to `Pending`. The customer shares the delivery address with the warehouse
+/// off-chain; nothing personal goes on-chain. Blocked while onboarding is
+/// paused or the warehouse is closed.
+///
+/// # Arguments
+/// - `address_envelope`: Optional pointer to the address sealed to
+///   `Warehouse.encryption_key` (URI and ciphertext hash); replaces any earlier one
+#[derive(Accounts)]
+pub struct RequestCustomerConfirmation<'info> {
+    #[account(
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        seeds = [SEED_CUSTOMER, authority.key().as_ref()],
+        bump = customer_profile.bump,
+        has_one = authority @ CustomerError::UnauthorizedCustomer
+    )]
+    pub customer_profile: Account<'info, CustomerProfile>,
+
+    #[account(
+        seeds = [SEED_WAREHOUSE, warehouse.warehouse_id.to_le_bytes().as_ref()],
+        bump = warehouse.bump
+    )]
+    pub warehouse: Account<'info, Warehouse>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = WarehouseCustomer::SIZE,
+        seeds = [SEED_WCUSTOMER, warehouse.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub warehouse_customer: Account<'info, WarehouseCustomer>,
+
+    /// The customer (pays for the request)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn request_customer_confirmation(
+    ctx: Context<RequestCustomerConfirmation>,
+    address_envelope: Option<AddressEnvelope>,
+) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_ONBOARDING)?;
+    ctx.accounts.warehouse.require_open_for(PAUSE_ONBOARDING)?;
+    if let Some(envelope) = &address_envelope {
+        envelope.validate(ctx.accounts.warehouse.encryption_key)?;
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let warehouse = ctx.accounts.warehouse.key();
+    let entry = &mut ctx.accounts.warehouse_customer;
+    entry.require_can_request(now)?;
+
+    // A fresh account has no customer yet
+    if entry.customer == Pubkey::default() {
+        let profile = &mut ctx.accounts.customer_profile;
+        profile.linked_warehouses = profile
+            .linked_warehouses
+            .checked_add(1)
+            .ok_or(OrderError::MathOverflow)?;
+        entry.open_orders = 0;
+    }
+
+    entry.warehouse = warehouse;
+    entry.customer = ctx.accounts.authority.key();
+    entry.status = WarehouseCustomerStatus::Pending;
+    entry.requested_at = now;
+    entry.confirmed_at = None;
+    entry.valid_until = None;
+    entry.notes_hash = None;
+    entry.address_envelope = address_envelope.clone();
+    entry.bump = ctx.bumps.warehouse_customer;
+
+    emit!(CustomerConfirmationRequested {
+        warehouse_customer: entry.key(),
+        warehouse,
+        customer: entry.customer,
+        address_envelope,
+    });
+
+    msg!("Customer confirmation requested: {}", entry.customer);
+    msg!("Warehouse: {}", warehouse);
+
+    Ok(())
+}
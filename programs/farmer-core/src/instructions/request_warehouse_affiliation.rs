@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use crate::errors::{ConfigError, FarmerError};
+use crate::events::AffiliationRequested;
+use crate::states::{
+    AffiliationStatus, FarmerAffiliation, FarmerProfile, ProgramConfig, Warehouse,
+    PAUSE_ONBOARDING, SEED_AFFILIATION, SEED_CONFIG, SEED_FARMER, SEED_WAREHOUSE,
+};
+
+/// Asks a warehouse to fulfil the signer's offers.
+///
+/// Creates the `FarmerAffiliation` PDA for this farmer and warehouse on first
+/// use; a rejected request can be sent again. A farmer may be affiliated with
+/// several warehouses at once. Blocked while onboarding is paused or the
+/// warehouse is closed.
+#[derive(Accounts)]
+pub struct RequestWarehouseAffiliation<'info> {
+    #[account(
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        seeds = [SEED_FARMER, authority.key().as_ref()],
+        bump = farmer_profile.bump,
+        has_one = authority @ FarmerError::UnauthorizedFarmer
+    )]
+    pub farmer_profile: Account<'info, FarmerProfile>,
+
+    #[account(
+        seeds = [SEED_WAREHOUSE, warehouse.warehouse_id.to_le_bytes().as_ref()],
+        bump = warehouse.bump
+    )]
+    pub warehouse: Account<'info, Warehouse>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = FarmerAffiliation::SIZE,
+        seeds = [SEED_AFFILIATION, authority.key().as_ref(), warehouse.key().as_ref()],
+        bump
+    )]
+    pub affiliation: Account<'info, FarmerAffiliation>,
+
+    /// The farmer (pays for the affiliation)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn request_warehouse_affiliation(ctx: Context<RequestWarehouseAffiliation>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_ONBOARDING)?;
+    ctx.accounts.warehouse.require_open_for(PAUSE_ONBOARDING)?;
+
+    let warehouse = ctx.accounts.warehouse.key();
+    let affiliation = &mut ctx.accounts.affiliation;
+
+    // A fresh account deserializes as `Pending`, so only approval needs a check
+    require!(
+        affiliation.status != AffiliationStatus::Approved,
+        FarmerError::AlreadyAffiliated
+    );
+
+    affiliation.warehouse = warehouse;
+    affiliation.farmer = ctx.accounts.authority.key();
+    affiliation.status = AffiliationStatus::Pending;
+    affiliation.requested_at = Clock::get()?.unix_timestamp;
+    affiliation.decided_at = 0;
+    affiliation.bump = ctx.bumps.affiliation;
+
+    emit!(AffiliationRequested {
+        affiliation: affiliation.key(),
+        farmer: affiliation.farmer,
+        warehouse,
+    });
+
+    msg!("Affiliation requested: {}", affiliation.farmer);
+    msg!("Warehouse: {}", warehouse);
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::events::CustomerRevoked;
+use crate::states::{
+    Warehouse, WarehouseCustomer, WarehouseCustomerStatus, WarehouseStaff,
+    ROLE_CONFIRM_CUSTOMERS, SEED_STAFF, SEED_WAREHOUSE, SEED_WCUSTOMER,
+};
+
+/// Declines a pending customer or withdraws a confirmation.
+///
+/// Signed by the warehouse operator or staff holding `ROLE_CONFIRM_CUSTOMERS`.
+/// Always allowed, whatever the pause flags or warehouse status. The customer
+/// may request confirmation again afterwards.
+#[derive(Accounts)]
+pub struct RevokeCustomer<'info> {
+    #[account(
+        seeds = [SEED_WAREHOUSE, warehouse.warehouse_id.to_le_bytes().as_ref()],
+        bump = warehouse.bump
+    )]
+    pub warehouse: Account<'info, Warehouse>,
+
+    /// The signer's staff grant; only needed when signing as staff
+    #[account(
+        seeds = [SEED_STAFF, warehouse.key().as_ref(), authority.key().as_ref()],
+        bump = staff.bump
+    )]
+    pub staff: Option<Account<'info, WarehouseStaff>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SEED_WCUSTOMER,
+            warehouse.key().as_ref(),
+            warehouse_customer.customer.as_ref()
+        ],
+        bump = warehouse_customer.bump
+    )]
+    pub warehouse_customer: Account<'info, WarehouseCustomer>,
+
+    /// The warehouse operator or staff with `ROLE_CONFIRM_CUSTOMERS`
+    pub authority: Signer<'info>,
+}
+
+pub fn revoke_customer(ctx: Context<RevokeCustomer>) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    ctx.accounts.warehouse.require_role(
+        &authority,
+        ctx.accounts.staff.as_deref(),
+        ROLE_CONFIRM_CUSTOMERS,
+    )?;
+
+    let entry = &mut ctx.accounts.warehouse_customer;
+    entry.require_revocable()?;
+
+    entry.status = WarehouseCustomerStatus::Revoked;
+
+    emit!(CustomerRevoked {
+        warehouse_customer: entry.key(),
+        warehouse: entry.warehouse,
+        customer: entry.customer,
+        authority,
+    });
+
+    msg!("Customer revoked: {}", entry.customer);
+    msg!("Warehouse: {}", entry.warehouse);
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use crate::errors::{ConfigError, WarehouseError};
+use crate::events::StaffRolesRevoked;
+use crate::states::{
+    ProgramConfig, Warehouse, WarehouseStaff, SEED_CONFIG, SEED_STAFF, SEED_WAREHOUSE,
+};
+
+/// Revokes warehouse roles from a staff member.
+///
+/// Signed by the warehouse operator or the admin. Once no role is left the
+/// `WarehouseStaff` PDA is closed and its rent goes to the signer.
+///
+/// # Arguments
+/// - `member`: Staff key losing the roles
+/// - `roles`: `ROLE_*` bits to remove (non-empty, known bits only)
+#[derive(Accounts)]
+#[instruction(member: Pubkey)]
+pub struct RevokeStaffRoles<'info> {
+    #[account(
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        seeds = [SEED_WAREHOUSE, warehouse.warehouse_id.to_le_bytes().as_ref()],
+        bump = warehouse.bump,
+        constraint = authority.key() == warehouse.operator
+            || authority.key() == config.admin
+            @ WarehouseError::UnauthorizedWarehouseAuthority
+    )]
+    pub warehouse: Account<'info, Warehouse>,
+
+    #[account(
+        mut,
+        seeds = [SEED_STAFF, warehouse.key().as_ref(), member.as_ref()],
+        bump = staff.bump
+    )]
+    pub staff: Account<'info, WarehouseStaff>,
+
+    /// The warehouse operator or the admin (receives the rent on close)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+pub fn revoke_staff_roles(ctx: Context<RevokeStaffRoles>, member: Pubkey, roles: u8) -> Result<()> {
+    WarehouseStaff::validate_roles(roles)?;
+
+    let staff = &mut ctx.accounts.staff;
+    staff.roles &= !roles;
+    let new_roles = staff.roles;
+
+    if new_roles == 0 {
+        staff.close(ctx.accounts.authority.to_account_info())?;
+    }
+
+    emit!(StaffRolesRevoked {
+        warehouse: ctx.accounts.warehouse.key(),
+        member,
+        authority: ctx.accounts.authority.key(),
+        roles,
+        new_roles,
+    });
+
+    msg!("Staff roles revoked: {}", member);
+    msg!("Roles: {:#04x}", new_roles);
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use crate::errors::ConfigError;
+use crate::events::PauseFlagsUpdated;
+use crate::states::{ProgramConfig, SEED_CONFIG, PAUSE_ALL};
+
+/// Sets the program pause mask.
+///
+/// Each `PAUSE_*` bit stops one class of actions (new orders, new offers,
+/// onboarding, settlements, refunds), so an incident can stop new business
+/// while in-flight orders still settle and refunds still go out.
+///
+/// Writing the mask also clears `PAUSE_LEGACY_ALL`, which is how configs
+/// created with the old `paused: bool` are migrated.
+///
+/// # Arguments
+/// - `pause_flags`: The new mask (only `PAUSE_ALL` bits are accepted)
+#[derive(Accounts)]
+pub struct SetPauseFlags<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated,
+        has_one = admin @ ConfigError::UnauthorizedAdmin
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// The current admin authority
+    pub admin: Signer<'info>,
+}
+
+pub fn set_pause_flags(ctx: Context<SetPauseFlags>, pause_flags: u8) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    require!(
+        pause_flags & !PAUSE_ALL == 0,
+        ConfigError::InvalidPauseFlags
+    );
+
+    let old_pause_flags = config.pause_flags;
+    config.pause_flags = pause_flags;
+
+    emit!(PauseFlagsUpdated {
+        admin: config.admin,
+        old_pause_flags,
+        new_pause_flags: pause_flags,
+    });
+
+    msg!("Pause flags updated");
+    msg!("Pause flags: {:#04x}", config.pause_flags);
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use crate::errors::{ConfigError, WarehouseError};
+use crate::events::WarehouseStatusChanged;
+use crate::states::{ProgramConfig, Warehouse, WarehouseStatus, SEED_CONFIG, SEED_WAREHOUSE};
+
+/// Moves a warehouse between `Active`, `Suspended` and `Closed`.
+///
+/// Only the admin can call this. A suspended warehouse takes no new orders or
+/// offers but can finish the ones in flight; a closed warehouse only processes
+/// refunds. `Closed` is final, and setting the current status is rejected.
+///
+/// # Arguments
+/// - `status`: New warehouse status
+#[derive(Accounts)]
+pub struct SetWarehouseStatus<'info> {
+    #[account(
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated,
+        has_one = admin @ ConfigError::UnauthorizedAdmin
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        seeds = [SEED_WAREHOUSE, warehouse.warehouse_id.to_le_bytes().as_ref()],
+        bump = warehouse.bump
+    )]
+    pub warehouse: Account<'info, Warehouse>,
+
+    /// The current admin authority
+    pub admin: Signer<'info>,
+}
+
+pub fn set_warehouse_status(
+    ctx: Context<SetWarehouseStatus>,
+    status: WarehouseStatus,
+) -> Result<()> {
+    let warehouse = &mut ctx.accounts.warehouse;
+    let old_status = warehouse.status;
+
+    require!(
+        old_status.can_transition_to(status),
+        WarehouseError::InvalidStatusTransition
+    );
+    warehouse.status = status;
+
+    emit!(WarehouseStatusChanged {
+        admin: ctx.accounts.admin.key(),
+        warehouse: warehouse.key(),
+        old_status,
+        new_status: status,
+    });
+
+    msg!("Warehouse status updated: {}", warehouse.warehouse_id);
+    msg!("Status: {:?} -> {:?}", old_status, status);
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use crate::errors::ConfigError;
+use crate::events::ConfigUpdated;
+use crate::states::{ProgramConfig, BPS_DENOMINATOR, SEED_CONFIG};
+
+/// Updates fields of the program configuration account.
+///
+/// Only the current `ProgramConfig.admin` may call this instruction. Every
+/// argument is optional so the admin can change a single field without
+/// resending the others; `None` leaves the stored value untouched. Pause
+/// flags are managed through `set_pause_flags` and the mint allowlist through
+/// `add_allowed_mint` / `remove_allowed_mint`.
+///
+/// # Arguments
+/// - `logistics_wallet`: New fee receiver wallet
+/// - `fee_notice_period`: Seconds a warehouse fee increase waits before it applies
+/// - `protocol_fee_bps`: Protocol cut of warehouse fees at settlement (max 10_000)
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated,
+        has_one = admin @ ConfigError::UnauthorizedAdmin
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// The current admin authority
+    pub admin: Signer<'info>,
+}
+
+pub fn update_config(
+    ctx: Context<UpdateConfig>,
+    logistics_wallet: Option<Pubkey>,
+    fee_notice_period: Option<i64>,
+    protocol_fee_bps: Option<u16>,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    let old_logistics_wallet = config.logistics_wallet;
+    let old_fee_notice_period = config.fee_notice_period;
+    let old_protocol_fee_bps = config.protocol_fee_bps;
+
+    if let Some(wallet) = logistics_wallet {
+        config.logistics_wallet = wallet;
+    }
+    if let Some(period) = fee_notice_period {
+        require!(period >= 0, ConfigError::InvalidFeeNoticePeriod);
+        config.fee_notice_period = period;
+    }
+    if let Some(bps) = protocol_fee_bps {
+        require!(
+            bps as u64 <= BPS_DENOMINATOR,
+            ConfigError::InvalidProtocolFeeBps
+        );
+        config.protocol_fee_bps = bps;
+    }
+
+    emit!(ConfigUpdated {
+        admin: config.admin,
+        old_logistics_wallet,
+        new_logistics_wallet: config.logistics_wallet,
+        old_fee_notice_period,
+        new_fee_notice_period: config.fee_notice_period,
+        old_protocol_fee_bps,
+        new_protocol_fee_bps: config.protocol_fee_bps,
+    });
+
+    msg!("Program config updated");
+    msg!("Logistics wallet: {}", config.logistics_wallet);
+    msg!("Fee notice period: {}s", config.fee_notice_period);
+    msg!("Protocol fee bps: {}", config.protocol_fee_bps);
+
+    Ok(())
+}
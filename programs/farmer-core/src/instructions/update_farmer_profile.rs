@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use crate::errors::FarmerError;
+use crate::events::{FarmerDelegateChanged, FarmerPayoutWalletChanged, FarmerProfileUpdated};
+use crate::states::{FarmerProfile, SEED_FARMER};
+
+/// Updates the signer's `FarmerProfile`: public fields, payout wallet and delegate.
+///
+/// Only the farmer can call this; the delegate cannot, so it never controls
+/// where payouts go. Every argument is optional and `None` leaves the stored
+/// value untouched; an empty `public_profile_uri` or a default `delegate` key
+/// clears it.
+///
+/// # Arguments
+/// - `display_name`: Public name (1..=`MAX_NAME_LEN` bytes)
+/// - `public_profile_uri`: Public profile page (max `MAX_URI_LEN` bytes, https/ipfs/ar)
+/// - `payout_wallet`: Owner of the token accounts that receive settlements
+/// - `delegate`: Key allowed to publish and deactivate offers; `Pubkey::default()` removes it
+#[derive(Accounts)]
+pub struct UpdateFarmerProfile<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_FARMER, authority.key().as_ref()],
+        bump = farmer_profile.bump,
+        has_one = authority @ FarmerError::UnauthorizedFarmer
+    )]
+    pub farmer_profile: Account<'info, FarmerProfile>,
+
+    /// The farmer
+    pub authority: Signer<'info>,
+}
+
+pub fn update_farmer_profile(
+    ctx: Context<UpdateFarmerProfile>,
+    display_name: Option<String>,
+    public_profile_uri: Option<String>,
+    payout_wallet: Option<Pubkey>,
+    delegate: Option<Pubkey>,
+) -> Result<()> {
+    let profile = &mut ctx.accounts.farmer_profile;
+    let farmer = profile.key();
+
+    if let Some(name) = display_name {
+        profile.display_name = name;
+    }
+    if let Some(uri) = public_profile_uri {
+        profile.public_profile_uri = uri;
+    }
+
+    if let Some(new_payout_wallet) = payout_wallet {
+        if new_payout_wallet != profile.payout_wallet {
+            emit!(FarmerPayoutWalletChanged {
+                farmer,
+                authority: profile.authority,
+                old_payout_wallet: profile.payout_wallet,
+                new_payout_wallet,
+            });
+            profile.payout_wallet = new_payout_wallet;
+        }
+    }
+
+    if let Some(delegate) = delegate {
+        let new_delegate = (delegate != Pubkey::default()).then_some(delegate);
+        if new_delegate != profile.delegate {
+            emit!(FarmerDelegateChanged {
+                farmer,
+                authority: profile.authority,
+                old_delegate: profile.delegate,
+                new_delegate,
+            });
+            profile.delegate = new_delegate;
+        }
+    }
+
+    profile.validate()?;
+
+    emit!(FarmerProfileUpdated {
+        farmer,
+        authority: profile.authority,
+        display_name: profile.display_name.clone(),
+        public_profile_uri: profile.public_profile_uri.clone(),
+    });
+
+    msg!("Farmer profile updated: {}", profile.authority);
+    msg!("Display name: {}", profile.display_name);
+    msg!("Payout wallet: {}", profile.payout_wallet);
+
+    Ok(())
+}
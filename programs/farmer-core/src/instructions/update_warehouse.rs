@@ -0,0 +1,185 @@
+use anchor_lang::prelude::*;
+use crate::errors::{ConfigError, WarehouseError};
+use crate::events::{
+    WarehouseEncryptionKeyChanged, WarehouseFeeChanged, WarehouseFeeReceiverChanged,
+    WarehouseOperatorChanged, WarehouseUpdated,
+};
+use crate::states::{
+    ProgramConfig, Warehouse, WarehouseStaff, ZipPrefix, ROLE_MANAGE, SEED_CONFIG, SEED_STAFF,
+    SEED_WAREHOUSE,
+};
+
+/// Updates a warehouse's public details, confirmation policy, fee, fee receiver,
+/// operator key or encryption key.
+///
+/// Signed by the warehouse operator or the admin; the admin path lets a lost
+/// operator key be replaced. Staff holding `ROLE_MANAGE` may also sign (passing
+/// their `WarehouseStaff` PDA), but only to change the public details, not the
+/// fee, the fee receiver, the operator or the encryption key. Every argument is
+/// optional and `None` leaves the stored value untouched.
+///
+/// Fee cuts apply immediately. Increases are stored in `pending_fee_bps` and
+/// only apply at `fee_effective_at`, `ProgramConfig.fee_notice_period` seconds
+/// later, so orders placed in the meantime keep the announced fee. A new fee
+/// change replaces any increase that is still pending.
+///
+/// # Arguments
+/// - `name`: Display name (max `MAX_NAME_LEN` bytes)
+/// - `pickup_notes`: Public pickup instructions (max `MAX_NOTES_LEN` bytes)
+/// - `fee_bps`: New service fee (max 10_000)
+/// - `deliver_zip_prefixes`: Replacement coverage list (max `MAX_ZIP_PREFIXES`)
+/// - `delivery_fee_rules_uri`: Fee rules URI (max `MAX_URI_LEN` bytes); an empty string
+///   clears it
+/// - `operator`: New operator key; must not be `Pubkey::default()`
+/// - `fee_receiver`: New wallet for the warehouse's fees at settlement
+/// - `confirmation_validity`: Default lifetime of new customer confirmations, in seconds;
+///   0 removes it (no expiry)
+/// - `encryption_key`: X25519 public key for customer address envelopes; all zeroes removes it.
+///   Requests already sealed to the old key keep it in their envelope
+#[derive(Accounts)]
+pub struct UpdateWarehouse<'info> {
+    #[account(
+        seeds = [SEED_CONFIG],
+        bump,
+        constraint = ProgramConfig::is_current(&config) @ ConfigError::ConfigNotMigrated
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        seeds = [SEED_WAREHOUSE, warehouse.warehouse_id.to_le_bytes().as_ref()],
+        bump = warehouse.bump
+    )]
+    pub warehouse: Account<'info, Warehouse>,
+
+    /// The signer's staff grant; only needed when signing as staff
+    #[account(
+        seeds = [SEED_STAFF, warehouse.key().as_ref(), authority.key().as_ref()],
+        bump = staff.bump
+    )]
+    pub staff: Option<Account<'info, WarehouseStaff>>,
+
+    /// The warehouse operator, the admin, or staff with `ROLE_MANAGE`
+    pub authority: Signer<'info>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_warehouse(
+    ctx: Context<UpdateWarehouse>,
+    name: Option<String>,
+    pickup_notes: Option<String>,
+    fee_bps: Option<u16>,
+    deliver_zip_prefixes: Option<Vec<ZipPrefix>>,
+    delivery_fee_rules_uri: Option<String>,
+    operator: Option<Pubkey>,
+    fee_receiver: Option<Pubkey>,
+    confirmation_validity: Option<i64>,
+    encryption_key: Option<[u8; 32]>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let notice_period = ctx.accounts.config.fee_notice_period;
+    let authority = ctx.accounts.authority.key();
+    let warehouse_key = ctx.accounts.warehouse.key();
+    let is_owner = authority == ctx.accounts.warehouse.operator
+        || authority == ctx.accounts.config.admin;
+    if !is_owner {
+        let staff = ctx
+            .accounts
+            .staff
+            .as_deref()
+            .ok_or(WarehouseError::UnauthorizedWarehouseAuthority)?;
+        require!(
+            fee_bps.is_none()
+                && operator.is_none()
+                && fee_receiver.is_none()
+                && encryption_key.is_none(),
+            WarehouseError::UnauthorizedWarehouseAuthority
+        );
+        ctx.accounts
+            .warehouse
+            .require_role(&authority, Some(staff), ROLE_MANAGE)?;
+    }
+
+    let warehouse = &mut ctx.accounts.warehouse;
+
+    warehouse.apply_pending_fee(now);
+
+    if let Some(name) = name {
+        warehouse.name = name;
+    }
+    if let Some(notes) = pickup_notes {
+        warehouse.pickup_notes = notes;
+    }
+    if let Some(prefixes) = deliver_zip_prefixes {
+        warehouse.deliver_zip_prefixes = prefixes;
+    }
+    if let Some(uri) = delivery_fee_rules_uri {
+        warehouse.delivery_fee_rules_uri = (!uri.is_empty()).then_some(uri);
+    }
+    if let Some(validity) = confirmation_validity {
+        warehouse.confirmation_validity = (validity != 0).then_some(validity);
+    }
+
+    if let Some(new_fee_bps) = fee_bps {
+        let old_fee_bps = warehouse.fee_bps;
+        let effective_at = warehouse.schedule_fee(new_fee_bps, now, notice_period)?;
+
+        emit!(WarehouseFeeChanged {
+            warehouse: warehouse_key,
+            old_fee_bps,
+            new_fee_bps,
+            effective_at,
+        });
+        msg!("Fee bps: {} -> {} at {}", old_fee_bps, new_fee_bps, effective_at);
+    }
+
+    if let Some(new_operator) = operator {
+        if new_operator != warehouse.operator {
+            emit!(WarehouseOperatorChanged {
+                warehouse: warehouse_key,
+                authority,
+                old_operator: warehouse.operator,
+                new_operator,
+            });
+            warehouse.operator = new_operator;
+        }
+    }
+
+    if let Some(new_fee_receiver) = fee_receiver {
+        if new_fee_receiver != warehouse.fee_receiver {
+            emit!(WarehouseFeeReceiverChanged {
+                warehouse: warehouse_key,
+                authority,
+                old_fee_receiver: warehouse.fee_receiver,
+                new_fee_receiver,
+            });
+            warehouse.fee_receiver = new_fee_receiver;
+        }
+    }
+
+    if let Some(key) = encryption_key {
+        let new_key = (key != [0; 32]).then_some(key);
+        if new_key != warehouse.encryption_key {
+            emit!(WarehouseEncryptionKeyChanged {
+                warehouse: warehouse_key,
+                authority,
+                old_key: warehouse.encryption_key,
+                new_key,
+            });
+            warehouse.encryption_key = new_key;
+        }
+    }
+
+    warehouse.validate()?;
+
+    emit!(WarehouseUpdated {
+        warehouse: warehouse_key,
+        authority,
+    });
+
+    msg!("Warehouse updated: {}", warehouse.warehouse_id);
+    msg!("Operator: {}", warehouse.operator);
+    msg!("Fee receiver: {}", warehouse.fee_receiver);
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::errors::{CustomerError, OrderError};
+use crate::events::CustomerWithdrawn;
+use crate::states::{
+    CustomerProfile, Warehouse, WarehouseCustomer, SEED_CUSTOMER, SEED_WAREHOUSE, SEED_WCUSTOMER,
+};
+
+/// Ends the signer's relationship with a warehouse and closes the
+/// `WarehouseCustomer`, refunding its rent to the customer.
+///
+/// Works in any status (pending, confirmed, revoked) and whatever the pause
+/// flags or warehouse status, so a customer can always leave. Refused while the
+/// customer has open orders at the warehouse. Closing only removes the account
+/// from current state: its past contents, `notes_hash` and the address
+/// envelope included, stay readable in transaction history. The
+/// `CustomerWithdrawn` event tells the warehouse to delete its off-chain
+/// address record.
+#[derive(Accounts)]
+pub struct WithdrawFromWarehouse<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_CUSTOMER, authority.key().as_ref()],
+        bump = customer_profile.bump,
+        has_one = authority @ CustomerError::UnauthorizedCustomer
+    )]
+    pub customer_profile: Account<'info, CustomerProfile>,
+
+    #[account(
+        seeds = [SEED_WAREHOUSE, warehouse.warehouse_id.to_le_bytes().as_ref()],
+        bump = warehouse.bump
+    )]
+    pub warehouse: Account<'info, Warehouse>,
+
+    #[account(
+        mut,
+        seeds = [SEED_WCUSTOMER, warehouse.key().as_ref(), authority.key().as_ref()],
+        bump = warehouse_customer.bump,
+        close = authority
+    )]
+    pub warehouse_customer: Account<'info, WarehouseCustomer>,
+
+    /// The customer (receives the rent)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+pub fn withdraw_from_warehouse(ctx: Context<WithdrawFromWarehouse>) -> Result<()> {
+    let entry = &ctx.accounts.warehouse_customer;
+    entry.require_withdrawable()?;
+
+    let profile = &mut ctx.accounts.customer_profile;
+    profile.linked_warehouses = profile
+        .linked_warehouses
+        .checked_sub(1)
+        .ok_or(OrderError::MathOverflow)?;
+
+    emit!(CustomerWithdrawn {
+        warehouse_customer: entry.key(),
+        warehouse: entry.warehouse,
+        customer: entry.customer,
+    });
+
+    msg!("Customer withdrawn: {}", entry.customer);
+    msg!("Warehouse: {}", entry.warehouse);
+
+    Ok(())
+}
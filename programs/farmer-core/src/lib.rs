@@ -1,9 +1,11 @@
 #![allow(unexpected_cfgs)]
 
 use crate::instructions::*;
+use crate::states::{AddressEnvelope, CustomerConfirmation, WarehouseStatus, ZipPrefix};
 use anchor_lang::prelude::*;
 
 pub mod errors;
+pub mod events;
 pub mod instructions;
 pub mod states;
 
@@ -17,9 +19,318 @@ pub mod farmer_core {
     pub fn init_config(
         ctx: Context<InitConfig>,
         logistics_wallet: Pubkey,
-        paused: bool,
-        allowed_mints: Vec<Pubkey>,
+        pause_flags: u8,
     ) -> Result<()> {
-        instructions::init_config::init_config(ctx, logistics_wallet, paused, allowed_mints)
+        instructions::init_config::init_config(ctx, logistics_wallet, pause_flags)
+    }
+
+    /// Updates the program configuration (admin only)
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        logistics_wallet: Option<Pubkey>,
+        fee_notice_period: Option<i64>,
+        protocol_fee_bps: Option<u16>,
+    ) -> Result<()> {
+        instructions::update_config::update_config(
+            ctx,
+            logistics_wallet,
+            fee_notice_period,
+            protocol_fee_bps,
+        )
+    }
+
+    /// Proposes a new admin (current admin only)
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::propose_admin::propose_admin(ctx, new_admin)
+    }
+
+    /// Accepts a pending admin transfer (proposed admin only)
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin::accept_admin(ctx)
+    }
+
+    /// Cancels a pending admin transfer (current admin only)
+    pub fn cancel_admin_transfer(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+        instructions::cancel_admin_transfer::cancel_admin_transfer(ctx)
+    }
+
+    /// Sets the pause mask (admin only)
+    pub fn set_pause_flags(ctx: Context<SetPauseFlags>, pause_flags: u8) -> Result<()> {
+        instructions::set_pause_flags::set_pause_flags(ctx, pause_flags)
+    }
+
+    /// Adds a mint to the allowlist (admin only)
+    pub fn add_allowed_mint(
+        ctx: Context<AddAllowedMint>,
+        min_order_subtotal: u64,
+        max_order_subtotal: u64,
+        service_fee_floor: Option<u64>,
+    ) -> Result<()> {
+        instructions::add_allowed_mint::add_allowed_mint(
+            ctx,
+            min_order_subtotal,
+            max_order_subtotal,
+            service_fee_floor,
+        )
+    }
+
+    /// Removes a mint from the allowlist (admin only)
+    pub fn remove_allowed_mint(ctx: Context<RemoveAllowedMint>) -> Result<()> {
+        instructions::remove_allowed_mint::remove_allowed_mint(ctx)
+    }
+
+    /// Moves legacy `allowed_mints` entries into `AllowedMint` PDAs (admin only)
+    pub fn migrate_allowed_mints<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigrateAllowedMints<'info>>,
+        min_order_subtotal: u64,
+        max_order_subtotal: u64,
+        service_fee_floor: Option<u64>,
+    ) -> Result<()> {
+        instructions::migrate_allowed_mints::migrate_allowed_mints(
+            ctx,
+            min_order_subtotal,
+            max_order_subtotal,
+            service_fee_floor,
+        )
+    }
+
+    /// Upgrades the config account to the current layout (admin only)
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        instructions::migrate_config::migrate_config(ctx)
+    }
+
+    /// Creates a warehouse (admin only)
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_warehouse(
+        ctx: Context<CreateWarehouse>,
+        warehouse_id: u64,
+        operator: Pubkey,
+        name: String,
+        pickup_notes: String,
+        fee_bps: u16,
+        deliver_zip_prefixes: Vec<ZipPrefix>,
+        delivery_fee_rules_uri: Option<String>,
+    ) -> Result<()> {
+        instructions::create_warehouse::create_warehouse(
+            ctx,
+            warehouse_id,
+            operator,
+            name,
+            pickup_notes,
+            fee_bps,
+            deliver_zip_prefixes,
+            delivery_fee_rules_uri,
+        )
+    }
+
+    /// Updates a warehouse (operator, admin, or staff with the manage role)
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_warehouse(
+        ctx: Context<UpdateWarehouse>,
+        name: Option<String>,
+        pickup_notes: Option<String>,
+        fee_bps: Option<u16>,
+        deliver_zip_prefixes: Option<Vec<ZipPrefix>>,
+        delivery_fee_rules_uri: Option<String>,
+        operator: Option<Pubkey>,
+        fee_receiver: Option<Pubkey>,
+        confirmation_validity: Option<i64>,
+        encryption_key: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::update_warehouse::update_warehouse(
+            ctx,
+            name,
+            pickup_notes,
+            fee_bps,
+            deliver_zip_prefixes,
+            delivery_fee_rules_uri,
+            operator,
+            fee_receiver,
+            confirmation_validity,
+            encryption_key,
+        )
+    }
+
+    /// Grants staff roles at a warehouse (operator or admin)
+    pub fn grant_staff_roles(
+        ctx: Context<GrantStaffRoles>,
+        member: Pubkey,
+        roles: u8,
+    ) -> Result<()> {
+        instructions::grant_staff_roles::grant_staff_roles(ctx, member, roles)
+    }
+
+    /// Revokes staff roles at a warehouse (operator or admin)
+    pub fn revoke_staff_roles(
+        ctx: Context<RevokeStaffRoles>,
+        member: Pubkey,
+        roles: u8,
+    ) -> Result<()> {
+        instructions::revoke_staff_roles::revoke_staff_roles(ctx, member, roles)
+    }
+
+    /// Sets a warehouse's status (admin only)
+    pub fn set_warehouse_status(
+        ctx: Context<SetWarehouseStatus>,
+        status: WarehouseStatus,
+    ) -> Result<()> {
+        instructions::set_warehouse_status::set_warehouse_status(ctx, status)
+    }
+
+    /// Returns whether a warehouse delivers to a ZIP prefix (read-only view)
+    pub fn check_coverage(ctx: Context<CheckCoverage>, zip_prefix: ZipPrefix) -> Result<bool> {
+        instructions::check_coverage::check_coverage(ctx, zip_prefix)
+    }
+
+    /// Registers the signer as a farmer
+    pub fn register_farmer(
+        ctx: Context<RegisterFarmer>,
+        display_name: String,
+        public_profile_uri: String,
+    ) -> Result<()> {
+        instructions::register_farmer::register_farmer(ctx, display_name, public_profile_uri)
+    }
+
+    /// Updates the signer's farmer profile, payout wallet and delegate (farmer only)
+    pub fn update_farmer_profile(
+        ctx: Context<UpdateFarmerProfile>,
+        display_name: Option<String>,
+        public_profile_uri: Option<String>,
+        payout_wallet: Option<Pubkey>,
+        delegate: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::update_farmer_profile::update_farmer_profile(
+            ctx,
+            display_name,
+            public_profile_uri,
+            payout_wallet,
+            delegate,
+        )
+    }
+
+    /// Closes the signer's farmer profile once no offers or orders are open
+    pub fn close_farmer_profile(ctx: Context<CloseFarmerProfile>) -> Result<()> {
+        instructions::close_farmer_profile::close_farmer_profile(ctx)
+    }
+
+    /// Asks a warehouse to fulfil the signer's offers (farmer only)
+    pub fn request_warehouse_affiliation(ctx: Context<RequestWarehouseAffiliation>) -> Result<()> {
+        instructions::request_warehouse_affiliation::request_warehouse_affiliation(ctx)
+    }
+
+    /// Approves a pending affiliation request (warehouse operator only)
+    pub fn approve_affiliation(ctx: Context<ApproveAffiliation>) -> Result<()> {
+        instructions::approve_affiliation::approve_affiliation(ctx)
+    }
+
+    /// Rejects a pending affiliation request (warehouse operator only)
+    pub fn reject_affiliation(ctx: Context<RejectAffiliation>) -> Result<()> {
+        instructions::reject_affiliation::reject_affiliation(ctx)
+    }
+
+    /// Ends a farmer's affiliation with a warehouse (farmer or warehouse operator)
+    pub fn end_affiliation(ctx: Context<EndAffiliation>) -> Result<()> {
+        instructions::end_affiliation::end_affiliation(ctx)
+    }
+
+    /// Publishes an offer fulfilled by one of the farmer's warehouses (farmer or delegate)
+    #[allow(clippy::too_many_arguments)]
+    pub fn publish_offer(
+        ctx: Context<PublishOffer>,
+        crop_name: String,
+        cultivar_name: Option<String>,
+        unit_code: u16,
+        qty: u64,
+        price_minor: u64,
+        expires_at: Option<i64>,
+        notes_public: Option<String>,
+    ) -> Result<()> {
+        instructions::publish_offer::publish_offer(
+            ctx,
+            crop_name,
+            cultivar_name,
+            unit_code,
+            qty,
+            price_minor,
+            expires_at,
+            notes_public,
+        )
+    }
+
+    /// Stops purchases on one of a farmer's offers (farmer or delegate)
+    pub fn deactivate_offer(ctx: Context<DeactivateOffer>) -> Result<()> {
+        instructions::deactivate_offer::deactivate_offer(ctx)
+    }
+
+    /// Closes a deactivated offer and refunds its rent (farmer only)
+    pub fn close_offer(ctx: Context<CloseOffer>) -> Result<()> {
+        instructions::close_offer::close_offer(ctx)
+    }
+
+    /// Registers the signer as a customer
+    pub fn register_customer(
+        ctx: Context<RegisterCustomer>,
+        public_profile_uri: String,
+    ) -> Result<()> {
+        instructions::register_customer::register_customer(ctx, public_profile_uri)
+    }
+
+    /// Asks a warehouse to confirm the signer as a customer (customer only)
+    pub fn request_customer_confirmation(
+        ctx: Context<RequestCustomerConfirmation>,
+        address_envelope: Option<AddressEnvelope>,
+    ) -> Result<()> {
+        instructions::request_customer_confirmation::request_customer_confirmation(
+            ctx,
+            address_envelope,
+        )
+    }
+
+    /// Confirms a pending customer (warehouse operator or confirming staff)
+    pub fn confirm_customer(
+        ctx: Context<ConfirmCustomer>,
+        notes_hash: Option<[u8; 32]>,
+        valid_until: Option<i64>,
+        envelope_hash: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::confirm_customer::confirm_customer(
+            ctx,
+            notes_hash,
+            valid_until,
+            envelope_hash,
+        )
+    }
+
+    /// Confirms a batch of pending customers (warehouse operator or confirming staff)
+    pub fn confirm_customers<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ConfirmCustomers<'info>>,
+        valid_until: Option<i64>,
+        confirmations: Vec<CustomerConfirmation>,
+    ) -> Result<()> {
+        instructions::confirm_customers::confirm_customers(ctx, valid_until, confirmations)
+    }
+
+    /// Extends a customer confirmation, expired or not (warehouse operator or confirming staff)
+    pub fn renew_confirmation(
+        ctx: Context<RenewConfirmation>,
+        notes_hash: Option<[u8; 32]>,
+        valid_until: Option<i64>,
+    ) -> Result<()> {
+        instructions::renew_confirmation::renew_confirmation(ctx, notes_hash, valid_until)
+    }
+
+    /// Revokes a customer's request or confirmation (warehouse operator or confirming staff)
+    pub fn revoke_customer(ctx: Context<RevokeCustomer>) -> Result<()> {
+        instructions::revoke_customer::revoke_customer(ctx)
+    }
+
+    /// Ends the customer's relationship with a warehouse and closes the entry (customer only)
+    pub fn withdraw_from_warehouse(ctx: Context<WithdrawFromWarehouse>) -> Result<()> {
+        instructions::withdraw_from_warehouse::withdraw_from_warehouse(ctx)
+    }
+
+    /// Closes the customer's profile and refunds the rent (customer only)
+    pub fn close_customer_profile(ctx: Context<CloseCustomerProfile>) -> Result<()> {
+        instructions::close_customer_profile::close_customer_profile(ctx)
     }
 }
@@ -1,4 +1,7 @@
 use anchor_lang::prelude::*;
+use crate::errors::{
+    ConfigError, CustomerError, FarmerError, OfferError, OrderError, WarehouseError,
+};
 
 // ============================================================================
 // CONSTANTS
@@ -8,7 +11,86 @@ pub const MAX_NAME_LEN: usize = 100;
 pub const MAX_URI_LEN: usize = 200;
 pub const MAX_NOTES_LEN: usize = 500;
 pub const MAX_ZIP_PREFIXES: usize = 100;
-pub const MAX_ALLOWED_MINTS: usize = 50;
+pub const MIN_ZIP_PREFIX_LEN: u8 = 3;
+pub const MAX_ZIP_PREFIX_LEN: u8 = 5;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Most customers one `confirm_customers` call takes. Each costs 35 bytes of
+/// transaction space (account key, index, two `None` hashes); 25 still fits
+/// with a staff signer, `valid_until` and a compute budget instruction. Every
+/// hash an entry carries costs 32 more, so fewer of those fit.
+pub const MAX_CONFIRM_BATCH: usize = 25;
+
+/// Schemes accepted for public profile URIs.
+pub const ALLOWED_URI_SCHEMES: [&str; 3] = ["https://", "ipfs://", "ar://"];
+
+/// True if `uri` starts with one of `ALLOWED_URI_SCHEMES` and has something after it.
+pub fn has_allowed_scheme(uri: &str) -> bool {
+    ALLOWED_URI_SCHEMES
+        .iter()
+        .any(|scheme| uri.len() > scheme.len() && uri.starts_with(scheme))
+}
+
+/// Layout version written to `ProgramConfig.version`. Bump it whenever a field
+/// is carved out of `ProgramConfig.reserved`, and teach `migrate_config` the step.
+pub const CONFIG_VERSION: u8 = 3;
+
+/// Notice period for warehouse fee increases on new and migrated configs (7 days).
+pub const DEFAULT_FEE_NOTICE_PERIOD: i64 = 7 * 24 * 60 * 60;
+
+/// With the `fixed-admin` feature, only this key may call `init_config` instead
+/// of the upgrade authority. Set `FARMER_CORE_ADMIN` (base58) when building.
+#[cfg(feature = "fixed-admin")]
+pub const ADMIN: Pubkey = Pubkey::from_str_const(env!("FARMER_CORE_ADMIN"));
+
+// ============================================================================
+// PAUSE FLAGS
+// ============================================================================
+// Bits of `ProgramConfig.pause_flags`. A set bit pauses that class of actions.
+
+pub const PAUSE_NEW_ORDERS: u8 = 1 << 1;
+pub const PAUSE_NEW_OFFERS: u8 = 1 << 2;
+pub const PAUSE_ONBOARDING: u8 = 1 << 3;
+pub const PAUSE_SETTLEMENTS: u8 = 1 << 4;
+pub const PAUSE_REFUNDS: u8 = 1 << 5;
+pub const PAUSE_ALL: u8 = PAUSE_NEW_ORDERS
+    | PAUSE_NEW_OFFERS
+    | PAUSE_ONBOARDING
+    | PAUSE_SETTLEMENTS
+    | PAUSE_REFUNDS;
+/// Bit 0 is what a legacy `paused: bool` set to `true` decodes as. It pauses
+/// everything until the admin writes a new mask with `set_pause_flags`.
+pub const PAUSE_LEGACY_ALL: u8 = 1 << 0;
+
+// ============================================================================
+// STAFF ROLES
+// ============================================================================
+// Bits of `WarehouseStaff.roles`. The warehouse operator implicitly holds all of
+// them; other keys need a `WarehouseStaff` grant.
+
+pub const ROLE_CONFIRM_CUSTOMERS: u8 = 1 << 0;
+pub const ROLE_QUOTE: u8 = 1 << 1;
+pub const ROLE_DISPATCH: u8 = 1 << 2;
+pub const ROLE_COMPLETE: u8 = 1 << 3;
+pub const ROLE_REFUND: u8 = 1 << 4;
+/// Edit the warehouse's public details; fee and operator changes stay with the operator
+pub const ROLE_MANAGE: u8 = 1 << 5;
+pub const ROLE_ALL: u8 = ROLE_CONFIRM_CUSTOMERS
+    | ROLE_QUOTE
+    | ROLE_DISPATCH
+    | ROLE_COMPLETE
+    | ROLE_REFUND
+    | ROLE_MANAGE;
+
+// ============================================================================
+// WAREHOUSE STATUS
+// ============================================================================
+// Action classes (the `PAUSE_*` bits) a warehouse blocks outside `Active`.
+
+/// Suspended: nothing new starts; orders already placed can still finish.
+pub const SUSPENDED_BLOCKS: u8 = PAUSE_NEW_ORDERS | PAUSE_NEW_OFFERS;
+/// Closed: winding down, only refunds go through.
+pub const CLOSED_BLOCKS: u8 = PAUSE_ALL & !PAUSE_REFUNDS;
 
 // ============================================================================
 // SEED PHRASES
@@ -23,6 +105,9 @@ pub const SEED_PACK: &[u8] = b"pack";
 pub const SEED_OFFER: &[u8] = b"offer";
 pub const SEED_ORDER: &[u8] = b"order";
 pub const SEED_ESCROW: &[u8] = b"escrow";
+pub const SEED_MINT: &[u8] = b"mint";
+pub const SEED_STAFF: &[u8] = b"staff";
+pub const SEED_AFFILIATION: &[u8] = b"affiliation";
 
 // ============================================================================
 // STATE ACCOUNTS
@@ -30,16 +115,1736 @@ pub const SEED_ESCROW: &[u8] = b"escrow";
 
 #[account]
 pub struct ProgramConfig {
+    pub admin: Pubkey,
+    pub logistics_wallet: Pubkey,
+    pub pause_flags: u8,
+    pub pending_admin: Option<Pubkey>,
+    /// Layout version (`CONFIG_VERSION`); 0 means the unversioned layout
+    pub version: u8,
+    /// Seconds a warehouse fee increase waits before it applies (added in v2)
+    pub fee_notice_period: i64,
+    /// Protocol cut of warehouse fees paid to `logistics_wallet`, in bps (added in v3)
+    pub protocol_fee_bps: u16,
+    /// Zeroed space for future fields, so they can be added without a realloc
+    pub reserved: [u8; 54],
+}
+
+impl ProgramConfig {
+    pub const SIZE: usize = 8 // discriminator
+        + 32 // admin
+        + 32 // logistics_wallet
+        + 1 // pause_flags
+        + 1 + 32 // pending_admin
+        + 1 // version
+        + 8 // fee_notice_period
+        + 2 // protocol_fee_bps
+        + 54; // reserved
+
+    /// True if `config` is stored in the current layout. A config written by
+    /// the original program with at most one allowed mint also decodes as
+    /// `ProgramConfig`, so the account size is checked as well as the version.
+    pub fn is_current(config: &Account<ProgramConfig>) -> bool {
+        config.version == CONFIG_VERSION && config.to_account_info().data_len() == Self::SIZE
+    }
+
+    /// Fails with the matching `ConfigError` if any action in `flag` is paused.
+    pub fn require_not_paused(&self, flag: u8) -> Result<()> {
+        let paused = if self.pause_flags & PAUSE_LEGACY_ALL != 0 {
+            flag
+        } else {
+            self.pause_flags & flag
+        };
+
+        if paused & PAUSE_NEW_ORDERS != 0 {
+            return err!(ConfigError::NewOrdersPaused);
+        }
+        if paused & PAUSE_NEW_OFFERS != 0 {
+            return err!(ConfigError::NewOffersPaused);
+        }
+        if paused & PAUSE_ONBOARDING != 0 {
+            return err!(ConfigError::OnboardingPaused);
+        }
+        if paused & PAUSE_SETTLEMENTS != 0 {
+            return err!(ConfigError::SettlementsPaused);
+        }
+        if paused & PAUSE_REFUNDS != 0 {
+            return err!(ConfigError::RefundsPaused);
+        }
+        Ok(())
+    }
+}
+
+/// Mint allowlist entry. A mint is accepted for payment iff this PDA exists.
+///
+/// Order creation enforces the limits with `check_order_subtotal` and prices
+/// the service fee with `service_fee`, which applies the floor.
+#[account]
+pub struct AllowedMint {
+    pub mint: Pubkey,
+    /// Snapshot of `Mint.decimals` taken when the entry was added
+    pub decimals: u8,
+    /// Smallest accepted order subtotal, in minor units
+    pub min_order_subtotal: u64,
+    /// Largest accepted single-order subtotal, in minor units
+    pub max_order_subtotal: u64,
+    /// Minimum service fee charged per order, in minor units
+    pub service_fee_floor: Option<u64>,
+    pub bump: u8,
+}
+
+impl AllowedMint {
+    pub const SIZE: usize = 8 // discriminator
+        + 32 // mint
+        + 1 // decimals
+        + 8 // min_order_subtotal
+        + 8 // max_order_subtotal
+        + 1 + 8 // service_fee_floor
+        + 1; // bump
+
+    /// Fails unless `min_order_subtotal <= max_order_subtotal` and `max_order_subtotal > 0`.
+    pub fn validate_limits(&self) -> Result<()> {
+        require!(
+            self.max_order_subtotal > 0 && self.min_order_subtotal <= self.max_order_subtotal,
+            ConfigError::InvalidMintLimits
+        );
+        Ok(())
+    }
+
+    /// Fails if an order subtotal is outside this mint's limits.
+    pub fn check_order_subtotal(&self, subtotal_minor: u64) -> Result<()> {
+        require!(
+            subtotal_minor >= self.min_order_subtotal,
+            OrderError::SubtotalBelowMinimum
+        );
+        require!(
+            subtotal_minor <= self.max_order_subtotal,
+            OrderError::SubtotalAboveMaximum
+        );
+        Ok(())
+    }
+
+    /// Service fee for `subtotal_minor` at `fee_bps`, raised to the floor if one is set.
+    pub fn service_fee(&self, subtotal_minor: u64, fee_bps: u16) -> Result<u64> {
+        let fee = (subtotal_minor as u128)
+            .checked_mul(fee_bps as u128)
+            .ok_or(OrderError::MathOverflow)?
+            / BPS_DENOMINATOR as u128;
+        let fee = u64::try_from(fee).map_err(|_| OrderError::MathOverflow)?;
+        Ok(fee.max(self.service_fee_floor.unwrap_or(0)))
+    }
+}
+
+/// Fulfillment partner. Created by the admin; `operator` runs day-to-day actions.
+#[account]
+pub struct Warehouse {
+    pub warehouse_id: u64,
+    pub operator: Pubkey,
+    /// Set by the admin; limits what the warehouse can do (see `require_open_for`)
+    pub status: WarehouseStatus,
+    /// Wallet that receives the warehouse's service and delivery fees
+    pub fee_receiver: Pubkey,
+    pub name: String,
+    pub pickup_notes: String,
+    /// Service fee on the order subtotal, in basis points
+    pub fee_bps: u16,
+    /// Scheduled fee increase, applied once `fee_effective_at` is reached
+    pub pending_fee_bps: Option<u16>,
+    /// Unix timestamp at which `pending_fee_bps` applies; 0 when nothing is pending
+    pub fee_effective_at: i64,
+    /// Default lifetime of customer confirmations, in seconds; `None` means they do not expire
+    pub confirmation_validity: Option<i64>,
+    /// X25519 key customers seal their address envelope to; `None` means envelopes are not accepted
+    pub encryption_key: Option<[u8; 32]>,
+    /// Public document describing how delivery fees are quoted
+    pub delivery_fee_rules_uri: Option<String>,
+    /// Areas served, for discovery only (eligibility is the customer confirmation)
+    pub deliver_zip_prefixes: Vec<ZipPrefix>,
+    pub bump: u8,
+}
+
+impl Warehouse {
+    pub const SIZE: usize = 8 // discriminator
+        + 8 // warehouse_id
+        + 32 // operator
+        + 1 // status
+        + 32 // fee_receiver
+        + 4 + MAX_NAME_LEN // name
+        + 4 + MAX_NOTES_LEN // pickup_notes
+        + 2 // fee_bps
+        + 1 + 2 // pending_fee_bps
+        + 8 // fee_effective_at
+        + 1 + 8 // confirmation_validity
+        + 1 + 32 // encryption_key
+        + 1 + 4 + MAX_URI_LEN // delivery_fee_rules_uri
+        + 4 + ZipPrefix::SIZE * MAX_ZIP_PREFIXES // deliver_zip_prefixes
+        + 1; // bump
+
+    /// Fails if the operator is unset, a string or the ZIP list exceeds its bound, a
+    /// ZIP prefix is invalid or listed twice, `fee_bps` exceeds 100% or the
+    /// confirmation validity is not positive.
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.operator != Pubkey::default(),
+            WarehouseError::InvalidOperator
+        );
+        require!(self.name.len() <= MAX_NAME_LEN, WarehouseError::NameTooLong);
+        require!(
+            self.confirmation_validity.is_none_or(|validity| validity > 0),
+            WarehouseError::InvalidConfirmationValidity
+        );
+        require!(
+            self.pickup_notes.len() <= MAX_NOTES_LEN,
+            WarehouseError::PickupNotesTooLong
+        );
+        require!(
+            self.fee_bps as u64 <= BPS_DENOMINATOR,
+            WarehouseError::InvalidFeeBps
+        );
+        if let Some(uri) = &self.delivery_fee_rules_uri {
+            require!(uri.len() <= MAX_URI_LEN, WarehouseError::UriTooLong);
+        }
+        require!(
+            self.deliver_zip_prefixes.len() <= MAX_ZIP_PREFIXES,
+            WarehouseError::TooManyZipPrefixes
+        );
+        for zip in &self.deliver_zip_prefixes {
+            zip.validate()?;
+        }
+        // Sorting a copy keeps the check O(n log n) for the full 100 prefixes
+        let mut sorted = self.deliver_zip_prefixes.clone();
+        sorted.sort_unstable_by_key(|zip| (zip.len, zip.prefix));
+        require!(
+            sorted.windows(2).all(|pair| pair[0] != pair[1]),
+            WarehouseError::DuplicateZipPrefix
+        );
+        Ok(())
+    }
+
+    /// True if one of `deliver_zip_prefixes` covers `zip` (see `ZipPrefix::covers`).
+    pub fn serves(&self, zip: &ZipPrefix) -> bool {
+        self.deliver_zip_prefixes
+            .iter()
+            .any(|prefix| prefix.covers(zip))
+    }
+
+    /// Fails with the error naming the status if it blocks an action in `flag`.
+    ///
+    /// `flag` uses the `PAUSE_*` action classes, so instructions pass the same
+    /// bit to `ProgramConfig::require_not_paused` and here.
+    pub fn require_open_for(&self, flag: u8) -> Result<()> {
+        match self.status {
+            WarehouseStatus::Active => Ok(()),
+            WarehouseStatus::Suspended if flag & SUSPENDED_BLOCKS != 0 => {
+                err!(WarehouseError::WarehouseSuspended)
+            }
+            WarehouseStatus::Closed if flag & CLOSED_BLOCKS != 0 => {
+                err!(WarehouseError::WarehouseClosed)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails unless `signer` is the operator or holds every bit of `role` through `staff`.
+    ///
+    /// `staff` must already be constrained to the `["staff", warehouse, signer]` PDA.
+    pub fn require_role(
+        &self,
+        signer: &Pubkey,
+        staff: Option<&WarehouseStaff>,
+        role: u8,
+    ) -> Result<()> {
+        if *signer == self.operator {
+            return Ok(());
+        }
+        match staff {
+            Some(staff) if staff.member == *signer && staff.roles & role == role => Ok(()),
+            _ => err!(WarehouseError::MissingStaffRole),
+        }
+    }
+
+    /// Expiry for a customer confirmation made at `now`: `valid_until` if given
+    /// (must be in the future), otherwise `now + confirmation_validity`.
+    pub fn confirmation_expiry(&self, valid_until: Option<i64>, now: i64) -> Result<Option<i64>> {
+        if let Some(valid_until) = valid_until {
+            require!(valid_until > now, CustomerError::InvalidConfirmationExpiry);
+            return Ok(Some(valid_until));
+        }
+        self.confirmation_validity
+            .map(|validity| now.checked_add(validity).ok_or(OrderError::MathOverflow.into()))
+            .transpose()
+    }
+
+    /// Service fee in force at `now`, counting a pending increase whose notice has run out.
+    pub fn current_fee_bps(&self, now: i64) -> u16 {
+        match self.pending_fee_bps {
+            Some(pending) if now >= self.fee_effective_at => pending,
+            _ => self.fee_bps,
+        }
+    }
+
+    /// Moves a pending increase into `fee_bps` once its notice period is over.
+    pub fn apply_pending_fee(&mut self, now: i64) {
+        self.fee_bps = self.current_fee_bps(now);
+        if self.fee_effective_at <= now {
+            self.pending_fee_bps = None;
+            self.fee_effective_at = 0;
+        }
+    }
+
+    /// Changes the fee: cuts apply at once, increases after `notice_period` seconds.
+    ///
+    /// Any earlier pending increase is replaced. Returns when the new fee applies.
+    pub fn schedule_fee(&mut self, fee_bps: u16, now: i64, notice_period: i64) -> Result<i64> {
+        require!(
+            fee_bps as u64 <= BPS_DENOMINATOR,
+            WarehouseError::InvalidFeeBps
+        );
+        self.apply_pending_fee(now);
+
+        if fee_bps <= self.fee_bps || notice_period == 0 {
+            self.fee_bps = fee_bps;
+            self.pending_fee_bps = None;
+            self.fee_effective_at = 0;
+            return Ok(now);
+        }
+
+        let effective_at = now
+            .checked_add(notice_period)
+            .ok_or(OrderError::MathOverflow)?;
+        self.pending_fee_bps = Some(fee_bps);
+        self.fee_effective_at = effective_at;
+        Ok(effective_at)
+    }
+}
+
+/// How a completed order's funds are paid out, in minor units.
+///
+/// The customer escrows `subtotal + delivery_fee`. The farmer gets the subtotal
+/// less the service fee, paid to the associated token account of
+/// `FarmerProfile.payout_wallet`, which `complete_order` is to create if missing
+/// (deferred with that instruction); the service and delivery fees go to
+/// `Warehouse.fee_receiver`, less a `ProgramConfig.protocol_fee_bps` cut (rounded
+/// down) sent to `logistics_wallet`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SettlementSplit {
+    pub farmer_payout: u64,
+    pub warehouse_payout: u64,
+    pub protocol_payout: u64,
+}
+
+impl SettlementSplit {
+    pub fn compute(
+        subtotal_minor: u64,
+        service_fee_minor: u64,
+        delivery_fee_minor: u64,
+        protocol_fee_bps: u16,
+    ) -> Result<Self> {
+        let farmer_payout = subtotal_minor
+            .checked_sub(service_fee_minor)
+            .ok_or(OrderError::MathOverflow)?;
+        let warehouse_fees = service_fee_minor
+            .checked_add(delivery_fee_minor)
+            .ok_or(OrderError::MathOverflow)?;
+        // bps <= 10_000, so the cut never exceeds `warehouse_fees`
+        let protocol_payout =
+            (warehouse_fees as u128 * protocol_fee_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+
+        Ok(Self {
+            farmer_payout,
+            warehouse_payout: warehouse_fees - protocol_payout,
+            protocol_payout,
+        })
+    }
+
+    /// Sum of all payouts; equals what the customer escrowed.
+    pub fn total(&self) -> Result<u64> {
+        self.farmer_payout
+            .checked_add(self.warehouse_payout)
+            .and_then(|sum| sum.checked_add(self.protocol_payout))
+            .ok_or_else(|| OrderError::MathOverflow.into())
+    }
+}
+
+/// ZIP code prefix a warehouse delivers to, e.g. `{ prefix: 123, len: 3 }` for 123xx.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ZipPrefix {
+    pub prefix: u32,
+    /// Number of digits in `prefix`, counting leading zeros
+    pub len: u8,
+}
+
+impl ZipPrefix {
+    pub const SIZE: usize = 4 + 1;
+
+    /// Fails unless `len` is `MIN_ZIP_PREFIX_LEN..=MAX_ZIP_PREFIX_LEN` and
+    /// `prefix` fits in `len` digits.
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            (MIN_ZIP_PREFIX_LEN..=MAX_ZIP_PREFIX_LEN).contains(&self.len)
+                && self.prefix < 10u32.pow(self.len as u32),
+            WarehouseError::InvalidZipPrefix
+        );
+        Ok(())
+    }
+
+    /// True if `zip` lies inside this prefix: `123` covers `123`, `1234` and
+    /// `12345`, but not `12` or `0123`.
+    pub fn covers(&self, zip: &ZipPrefix) -> bool {
+        zip.len >= self.len
+            && 10u32
+                .checked_pow((zip.len - self.len) as u32)
+                .is_some_and(|scale| zip.prefix / scale == self.prefix)
+    }
+}
+
+/// Lifecycle of a warehouse, moved by the admin with `set_warehouse_status`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WarehouseStatus {
+    /// Fully operational
+    Active,
+    /// No new orders or offers; existing orders can finish
+    Suspended,
+    /// Winding down: only refunds. Final
+    Closed,
+}
+
+impl WarehouseStatus {
+    /// Active <-> Suspended, and either into Closed.
+    pub fn can_transition_to(self, next: WarehouseStatus) -> bool {
+        matches!(
+            (self, next),
+            (Self::Active, Self::Suspended)
+                | (Self::Suspended, Self::Active)
+                | (Self::Active, Self::Closed)
+                | (Self::Suspended, Self::Closed)
+        )
+    }
+}
+
+/// Roles one staff member holds at one warehouse. Closed when the last role is revoked.
+#[account]
+pub struct WarehouseStaff {
+    pub warehouse: Pubkey,
+    pub member: Pubkey,
+    /// `ROLE_*` bits held by `member`
+    pub roles: u8,
+    pub bump: u8,
+}
+
+impl WarehouseStaff {
+    pub const SIZE: usize = 8 // discriminator
+        + 32 // warehouse
+        + 32 // member
+        + 1 // roles
+        + 1; // bump
+
+    /// Fails if `roles` is empty or has unknown bits.
+    pub fn validate_roles(roles: u8) -> Result<()> {
+        require!(
+            roles != 0 && roles & !ROLE_ALL == 0,
+            WarehouseError::InvalidRoles
+        );
+        Ok(())
+    }
+
+    /// Fails if `member` is unset or is the warehouse operator, who already
+    /// holds every role.
+    pub fn validate_member(member: &Pubkey, operator: &Pubkey) -> Result<()> {
+        require!(
+            *member != Pubkey::default() && member != operator,
+            WarehouseError::InvalidStaffMember
+        );
+        Ok(())
+    }
+}
+
+/// A farmer's public identity, one per farmer key.
+#[account]
+pub struct FarmerProfile {
+    /// Farmer key that signs for the profile (also the PDA seed)
+    pub authority: Pubkey,
+    /// Owner of the token accounts that receive the farmer's payouts; the farmer key by default
+    pub payout_wallet: Pubkey,
+    /// Key that may publish and deactivate offers for the farmer, e.g. a phone hot wallet
+    pub delegate: Option<Pubkey>,
+    pub display_name: String,
+    /// Public profile page; empty, or a URI with an allowed scheme
+    pub public_profile_uri: String,
+    /// Next offer id; offers derive their PDA from it
+    pub offer_counter: u64,
+    /// Offers still active, across all warehouses
+    pub active_offers: u32,
+    /// `LotOffer` accounts held, active or not; each must be closed with
+    /// `close_offer` before the profile, so a new profile never reuses a live offer id
+    pub offer_accounts: u32,
+    /// Orders not yet finished; maintained by the order instructions
+    pub open_orders: u32,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl FarmerProfile {
+    pub const SIZE: usize = 8 // discriminator
+        + 32 // authority
+        + 32 // payout_wallet
+        + 1 + 32 // delegate
+        + 4 + MAX_NAME_LEN // display_name
+        + 4 + MAX_URI_LEN // public_profile_uri
+        + 8 // offer_counter
+        + 4 // active_offers
+        + 4 // offer_accounts
+        + 4 // open_orders
+        + 8 // created_at
+        + 1; // bump
+
+    /// Fails if the name is empty or too long, the URI is too long or has a
+    /// scheme outside `ALLOWED_URI_SCHEMES`, the payout wallet is unset, or the
+    /// delegate is unset or the farmer itself.
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            !self.display_name.is_empty(),
+            FarmerError::EmptyDisplayName
+        );
+        require!(
+            self.display_name.len() <= MAX_NAME_LEN,
+            FarmerError::DisplayNameTooLong
+        );
+        require!(
+            self.public_profile_uri.len() <= MAX_URI_LEN,
+            FarmerError::ProfileUriTooLong
+        );
+        require!(
+            self.public_profile_uri.is_empty() || has_allowed_scheme(&self.public_profile_uri),
+            FarmerError::InvalidProfileUriScheme
+        );
+        require!(
+            self.payout_wallet != Pubkey::default(),
+            FarmerError::InvalidPayoutWallet
+        );
+        if let Some(delegate) = self.delegate {
+            require!(
+                delegate != Pubkey::default() && delegate != self.authority,
+                FarmerError::InvalidDelegate
+            );
+        }
+        Ok(())
+    }
+
+    /// Fails unless `signer` is the farmer or its delegate. Only offer
+    /// instructions accept the delegate; profile and payout changes need the farmer.
+    pub fn require_offer_signer(&self, signer: &Pubkey) -> Result<()> {
+        require!(
+            *signer == self.authority || self.delegate == Some(*signer),
+            FarmerError::UnauthorizedOfferSigner
+        );
+        Ok(())
+    }
+
+    /// Fails while the farmer still has active offers, offer accounts or open orders.
+    pub fn require_closable(&self) -> Result<()> {
+        require!(self.active_offers == 0, FarmerError::ActiveOffersRemaining);
+        require!(self.offer_accounts == 0, FarmerError::OfferAccountsRemaining);
+        require!(self.open_orders == 0, FarmerError::OpenOrdersRemaining);
+        Ok(())
+    }
+}
+
+/// A farmer's relationship with one warehouse. A farmer may hold several.
+///
+/// The farmer requests, the warehouse operator approves or rejects. Offers name
+/// the warehouse that fulfils them and need an approved affiliation with it.
+#[account]
+pub struct FarmerAffiliation {
+    /// First field so a warehouse's affiliations can be listed with a prefix filter
+    pub warehouse: Pubkey,
+    /// Farmer key
+    pub farmer: Pubkey,
+    pub status: AffiliationStatus,
+    pub requested_at: i64,
+    /// When the operator approved or rejected; 0 while pending
+    pub decided_at: i64,
+    /// Active offers fulfilled by this warehouse; the affiliation cannot end while non-zero
+    pub active_offers: u32,
+    pub bump: u8,
+}
+
+impl FarmerAffiliation {
+    pub const SIZE: usize = 8 // discriminator
+        + 32 // warehouse
+        + 32 // farmer
+        + 1 // status
+        + 8 // requested_at
+        + 8 // decided_at
+        + 4 // active_offers
+        + 1; // bump
+
+    /// Fails unless the request is still waiting for the operator.
+    pub fn require_pending(&self) -> Result<()> {
+        require!(
+            self.status == AffiliationStatus::Pending,
+            FarmerError::AffiliationNotPending
+        );
+        Ok(())
+    }
+
+    /// Fails unless the warehouse has approved the farmer. Offer and order
+    /// instructions call this at the time of the action.
+    pub fn require_active(&self) -> Result<()> {
+        require!(
+            self.status == AffiliationStatus::Approved,
+            FarmerError::AffiliationNotActive
+        );
+        Ok(())
+    }
+
+    /// Fails while offers fulfilled by this warehouse are still active.
+    pub fn require_closable(&self) -> Result<()> {
+        require!(
+            self.active_offers == 0,
+            FarmerError::ActiveOffersAtWarehouse
+        );
+        Ok(())
+    }
+}
+
+/// Outcome of a `FarmerAffiliation` request.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AffiliationStatus {
+    /// Waiting for the warehouse operator
+    Pending,
+    /// The farmer can publish offers fulfilled by the warehouse
+    Approved,
+    /// Declined by the operator; the farmer may request again
+    Rejected,
+}
+
+/// A public listing of one lot, fulfilled by one of the farmer's warehouses.
+#[account]
+pub struct LotOffer {
+    /// Farmer key
+    pub farmer: Pubkey,
+    /// Warehouse that fulfils orders on this offer
+    pub warehouse: Pubkey,
+    /// `FarmerProfile::offer_counter` at publication (also the PDA seed)
+    pub offer_id: u64,
+    pub crop_name: String,
+    pub cultivar_name: Option<String>,
+    /// Client-side unit mapping; quantities are in that unit's base unit (g, ml, count)
+    pub unit_code: u16,
+    pub expires_at: Option<i64>,
+    pub notes_public: Option<String>,
+    /// Payment mint (allowlisted at publication)
+    pub mint: Pubkey,
+    /// Price per base unit, in the mint's minor units
+    pub price_minor: u64,
+    pub qty_remaining: u64,
+    pub active: bool,
+    pub created_at: i64,
+    /// Signer that paid the rent at publication (farmer or delegate); refunded on close
+    pub rent_payer: Pubkey,
+    pub bump: u8,
+}
+
+impl LotOffer {
+    pub const SIZE: usize = 8 // discriminator
+        + 32 // farmer
+        + 32 // warehouse
+        + 8 // offer_id
+        + 4 + MAX_NAME_LEN // crop_name
+        + 1 + 4 + MAX_NAME_LEN // cultivar_name
+        + 2 // unit_code
+        + 1 + 8 // expires_at
+        + 1 + 4 + MAX_NOTES_LEN // notes_public
+        + 32 // mint
+        + 8 // price_minor
+        + 8 // qty_remaining
+        + 1 // active
+        + 8 // created_at
+        + 32 // rent_payer
+        + 1; // bump
+
+    /// Fails if a string is empty or too long, quantity or price is zero, or
+    /// `expires_at` is not after `now`.
+    pub fn validate(&self, now: i64) -> Result<()> {
+        require!(!self.crop_name.is_empty(), OfferError::EmptyCropName);
+        require!(
+            self.crop_name.len() <= MAX_NAME_LEN,
+            OfferError::CropNameTooLong
+        );
+        if let Some(cultivar) = &self.cultivar_name {
+            require!(
+                cultivar.len() <= MAX_NAME_LEN,
+                OfferError::CultivarNameTooLong
+            );
+        }
+        if let Some(notes) = &self.notes_public {
+            require!(notes.len() <= MAX_NOTES_LEN, OfferError::NotesTooLong);
+        }
+        require!(self.qty_remaining > 0, OfferError::InvalidQuantity);
+        require!(self.price_minor > 0, OfferError::InvalidPrice);
+        if let Some(expires_at) = self.expires_at {
+            require!(expires_at > now, OfferError::InvalidExpiry);
+        }
+        Ok(())
+    }
+}
+
+/// A customer's public identity, one per customer key. Holds no address or
+/// other personal data; warehouses keep that off-chain.
+#[account]
+pub struct CustomerProfile {
+    /// Customer key that signs for the profile (also the PDA seed)
+    pub authority: Pubkey,
+    /// Public profile page; empty, or a URI with an allowed scheme
+    pub public_profile_uri: String,
+    /// Next order id; orders derive their PDA from it
+    pub order_counter: u64,
+    /// Orders not yet completed, canceled or expired; maintained by the order instructions
+    pub open_orders: u32,
+    /// `WarehouseCustomer` entries held; each must be withdrawn before closing
+    pub linked_warehouses: u32,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl CustomerProfile {
+    pub const SIZE: usize = 8 // discriminator
+        + 32 // authority
+        + 4 + MAX_URI_LEN // public_profile_uri
+        + 8 // order_counter
+        + 4 // open_orders
+        + 4 // linked_warehouses
+        + 8 // created_at
+        + 1; // bump
+
+    /// Fails if the URI is too long or has a scheme outside `ALLOWED_URI_SCHEMES`.
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.public_profile_uri.len() <= MAX_URI_LEN,
+            CustomerError::ProfileUriTooLong
+        );
+        require!(
+            self.public_profile_uri.is_empty() || has_allowed_scheme(&self.public_profile_uri),
+            CustomerError::InvalidProfileUriScheme
+        );
+        Ok(())
+    }
+
+    /// Fails while the customer has open orders or is still linked to a warehouse.
+    pub fn require_closable(&self) -> Result<()> {
+        require!(self.open_orders == 0, CustomerError::OpenOrdersRemaining);
+        require!(
+            self.linked_warehouses == 0,
+            CustomerError::LinkedWarehousesRemaining
+        );
+        Ok(())
+    }
+}
+
+/// Whether a warehouse has verified a customer's address off-chain. Ordering
+/// from a warehouse's offers requires `Confirmed`.
+///
+/// The customer requests, the operator (or staff with `ROLE_CONFIRM_CUSTOMERS`)
+/// confirms or revokes. Only a hash of the warehouse's record is stored.
+#[account]
+pub struct WarehouseCustomer {
+    /// First field so a warehouse's customers can be listed with a prefix filter
+    pub warehouse: Pubkey,
+    /// Customer key
+    pub customer: Pubkey,
+    pub status: WarehouseCustomerStatus,
+    pub requested_at: i64,
+    /// Last confirmation or renewal
+    pub confirmed_at: Option<i64>,
+    /// The confirmation no longer counts from this time; `None` means it does not expire
+    pub valid_until: Option<i64>,
+    /// Hash of the warehouse's off-chain record; never the record itself
+    pub notes_hash: Option<[u8; 32]>,
+    /// Pointer to the customer's encrypted address, from the latest request
+    pub address_envelope: Option<AddressEnvelope>,
+    /// The customer's open orders at this warehouse; maintained by the order instructions
+    pub open_orders: u32,
+    pub bump: u8,
+}
+
+impl WarehouseCustomer {
+    pub const SIZE: usize = 8 // discriminator
+        + 32 // warehouse
+        + 32 // customer
+        + 1 // status
+        + 8 // requested_at
+        + 1 + 8 // confirmed_at
+        + 1 + 8 // valid_until
+        + 1 + 32 // notes_hash
+        + 1 + AddressEnvelope::SIZE // address_envelope
+        + 4 // open_orders
+        + 1; // bump
+
+    /// True once `valid_until` has passed.
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.valid_until.is_some_and(|valid_until| now >= valid_until)
+    }
+
+    /// Fails unless the account is new, revoked or its confirmation has expired.
+    /// A fresh account deserializes as `Pending` with no customer, so that is
+    /// told apart here.
+    pub fn require_can_request(&self, now: i64) -> Result<()> {
+        match self.status {
+            WarehouseCustomerStatus::Pending if self.customer != Pubkey::default() => {
+                err!(CustomerError::ConfirmationAlreadyPending)
+            }
+            WarehouseCustomerStatus::Confirmed if !self.is_expired(now) => {
+                err!(CustomerError::AlreadyConfirmed)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails unless the request is waiting for the warehouse.
+    pub fn require_pending(&self) -> Result<()> {
+        require!(
+            self.status == WarehouseCustomerStatus::Pending,
+            CustomerError::ConfirmationNotPending
+        );
+        Ok(())
+    }
+
+    /// Fails once revoked; pending requests and confirmations can be revoked.
+    pub fn require_revocable(&self) -> Result<()> {
+        require!(
+            self.status != WarehouseCustomerStatus::Revoked,
+            CustomerError::AlreadyRevoked
+        );
+        Ok(())
+    }
+
+    /// Fails while the customer has open orders at this warehouse.
+    pub fn require_withdrawable(&self) -> Result<()> {
+        require!(self.open_orders == 0, CustomerError::OpenOrdersRemaining);
+        Ok(())
+    }
+
+    /// Fails unless `envelope_hash` is the ciphertext hash of the stored
+    /// envelope, or both are absent, so a confirmation commits to what the
+    /// warehouse decrypted.
+    pub fn require_envelope_hash(&self, envelope_hash: Option<[u8; 32]>) -> Result<()> {
+        require!(
+            envelope_hash == self.address_envelope.as_ref().map(|e| e.ciphertext_hash),
+            CustomerError::EnvelopeHashMismatch
+        );
+        Ok(())
+    }
+
+    /// Fails unless the warehouse has confirmed the customer, expired or not.
+    /// Renewal only needs this.
+    pub fn require_renewable(&self) -> Result<()> {
+        require!(
+            self.status == WarehouseCustomerStatus::Confirmed,
+            CustomerError::CustomerNotConfirmed
+        );
+        Ok(())
+    }
+
+    /// Fails unless the warehouse has confirmed the customer and the
+    /// confirmation has not expired. Order instructions call this at the time
+    /// of the action.
+    pub fn require_confirmed(&self, now: i64) -> Result<()> {
+        self.require_renewable()?;
+        require!(!self.is_expired(now), CustomerError::ConfirmationExpired);
+        Ok(())
+    }
+}
+
+/// Pending -> Confirmed | Revoked, Confirmed -> Revoked, Revoked -> Pending.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WarehouseCustomerStatus {
+    /// Waiting for the warehouse to verify the address off-chain
+    Pending,
+    /// The customer can order from the warehouse's offers
+    Confirmed,
+    /// Declined or withdrawn by the warehouse; the customer may request again
+    Revoked,
+}
+
+/// Per-customer arguments of `confirm_customers`, in the order of the
+/// `WarehouseCustomer` remaining accounts; same meaning as in `confirm_customer`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CustomerConfirmation {
+    pub notes_hash: Option<[u8; 32]>,
+    pub envelope_hash: Option<[u8; 32]>,
+}
+
+/// Where a customer's address, sealed to `Warehouse.encryption_key`, can be
+/// fetched. The address itself never goes on-chain; `ciphertext_hash` lets
+/// either side prove which ciphertext was handed over.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AddressEnvelope {
+    /// Location of the ciphertext; a URI with an allowed scheme
+    pub uri: String,
+    /// SHA-256 of the ciphertext
+    pub ciphertext_hash: [u8; 32],
+    /// The warehouse key it was sealed to; must be the current one when requesting
+    pub encryption_key: [u8; 32],
+}
+
+impl AddressEnvelope {
+    pub const SIZE: usize = 4 + MAX_URI_LEN // uri
+        + 32 // ciphertext_hash
+        + 32; // encryption_key
+
+    /// Fails if the URI is too long or lacks an allowed scheme, or the
+    /// envelope was not sealed to `encryption_key`, the warehouse's current key.
+    pub fn validate(&self, encryption_key: Option<[u8; 32]>) -> Result<()> {
+        require!(self.uri.len() <= MAX_URI_LEN, CustomerError::EnvelopeUriTooLong);
+        require!(has_allowed_scheme(&self.uri), CustomerError::InvalidEnvelopeUri);
+        let encryption_key = encryption_key.ok_or(CustomerError::EncryptionKeyNotSet)?;
+        require!(
+            self.encryption_key == encryption_key,
+            CustomerError::EnvelopeKeyMismatch
+        );
+        Ok(())
+    }
+}
+
+// ============================================================================
+// LEGACY LAYOUTS
+// ============================================================================
+// Old account layouts, kept only so migration instructions can decode them.
+
+/// `ProgramConfig` as deployed by the original program, before the pause mask
+/// and the `AllowedMint` PDAs. Shares the `ProgramConfig` discriminator; told
+/// apart by its size, which is always larger than `ProgramConfig::SIZE`.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyProgramConfig {
     pub admin: Pubkey,
     pub logistics_wallet: Pubkey,
     pub paused: bool,
     pub allowed_mints: Vec<Pubkey>,
 }
 
-impl ProgramConfig {
+impl LegacyProgramConfig {
+    /// Capacity the original program reserved for `allowed_mints`
+    pub const MAX_ALLOWED_MINTS: usize = 50;
+
     pub const SIZE: usize = 8 // discriminator
         + 32 // admin
         + 32 // logistics_wallet
         + 1 // paused
-        + 4 + (32 * MAX_ALLOWED_MINTS); // allowed_mints vector
-}
\ No newline at end of file
+        + 4 + (32 * Self::MAX_ALLOWED_MINTS); // allowed_mints vector
+}
+
+/// `ProgramConfig` as stored before `version` and `reserved` were added
+/// (layout version 0). Shares the `ProgramConfig` discriminator.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ProgramConfigV0 {
+    pub admin: Pubkey,
+    pub logistics_wallet: Pubkey,
+    pub pause_flags: u8,
+    pub pending_admin: Option<Pubkey>,
+}
+
+impl ProgramConfigV0 {
+    pub const SIZE: usize = 8 // discriminator
+        + 32 // admin
+        + 32 // logistics_wallet
+        + 1 // pause_flags
+        + 1 + 32; // pending_admin
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPERATOR: Pubkey = Pubkey::new_from_array([1; 32]);
+    const MEMBER: Pubkey = Pubkey::new_from_array([2; 32]);
+    const ROLES: [u8; 6] = [
+        ROLE_CONFIRM_CUSTOMERS,
+        ROLE_QUOTE,
+        ROLE_DISPATCH,
+        ROLE_COMPLETE,
+        ROLE_REFUND,
+        ROLE_MANAGE,
+    ];
+
+    fn warehouse() -> Warehouse {
+        Warehouse {
+            warehouse_id: 1,
+            operator: OPERATOR,
+            status: WarehouseStatus::Active,
+            fee_receiver: OPERATOR,
+            name: String::new(),
+            pickup_notes: String::new(),
+            fee_bps: 0,
+            pending_fee_bps: None,
+            fee_effective_at: 0,
+            confirmation_validity: None,
+            encryption_key: None,
+            delivery_fee_rules_uri: None,
+            deliver_zip_prefixes: Vec::new(),
+            bump: 255,
+        }
+    }
+
+    fn farmer() -> FarmerProfile {
+        FarmerProfile {
+            authority: MEMBER,
+            payout_wallet: MEMBER,
+            delegate: None,
+            display_name: "Green Acres".to_string(),
+            public_profile_uri: String::new(),
+            offer_counter: 0,
+            active_offers: 0,
+            offer_accounts: 0,
+            open_orders: 0,
+            created_at: 0,
+            bump: 255,
+        }
+    }
+
+    fn zip(prefix: u32, len: u8) -> ZipPrefix {
+        ZipPrefix { prefix, len }
+    }
+
+    fn staff(roles: u8) -> WarehouseStaff {
+        WarehouseStaff {
+            warehouse: Pubkey::new_unique(),
+            member: MEMBER,
+            roles,
+            bump: 255,
+        }
+    }
+
+    fn allowed_mint(service_fee_floor: Option<u64>) -> AllowedMint {
+        AllowedMint {
+            mint: Pubkey::new_unique(),
+            decimals: 6,
+            min_order_subtotal: 100,
+            max_order_subtotal: 1_000_000,
+            service_fee_floor,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn order_subtotal_must_be_within_mint_limits() {
+        let entry = allowed_mint(None);
+        assert!(entry.check_order_subtotal(100).is_ok());
+        assert!(entry.check_order_subtotal(1_000_000).is_ok());
+        assert_eq!(
+            entry.check_order_subtotal(99).unwrap_err(),
+            OrderError::SubtotalBelowMinimum.into()
+        );
+        assert_eq!(
+            entry.check_order_subtotal(1_000_001).unwrap_err(),
+            OrderError::SubtotalAboveMaximum.into()
+        );
+    }
+
+    #[test]
+    fn service_fee_rounds_down_and_applies_the_floor() {
+        // 2.5% of 999 is 24.975
+        assert_eq!(allowed_mint(None).service_fee(999, 250).unwrap(), 24);
+        assert_eq!(allowed_mint(Some(30)).service_fee(999, 250).unwrap(), 30);
+        assert_eq!(allowed_mint(Some(30)).service_fee(2_000, 250).unwrap(), 50);
+        assert_eq!(allowed_mint(Some(5)).service_fee(1_000, 0).unwrap(), 5);
+        assert_eq!(
+            allowed_mint(None).service_fee(u64::MAX, 10_000).unwrap(),
+            u64::MAX
+        );
+        assert_eq!(
+            allowed_mint(None).service_fee(u64::MAX, u16::MAX).unwrap_err(),
+            OrderError::MathOverflow.into()
+        );
+    }
+
+    #[test]
+    fn operator_holds_every_role() {
+        for role in ROLES {
+            assert!(warehouse().require_role(&OPERATOR, None, role).is_ok());
+        }
+    }
+
+    #[test]
+    fn staff_holds_only_granted_roles() {
+        for granted in ROLES {
+            let staff = staff(granted);
+            for role in ROLES {
+                let result = warehouse().require_role(&MEMBER, Some(&staff), role);
+                if role == granted {
+                    assert!(result.is_ok());
+                } else {
+                    assert_eq!(
+                        result.err().unwrap(),
+                        WarehouseError::MissingStaffRole.into()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn staff_grant_of_another_member_is_ignored() {
+        let staff = staff(ROLE_ALL);
+        let other = Pubkey::new_unique();
+        assert_eq!(
+            warehouse()
+                .require_role(&other, Some(&staff), ROLE_QUOTE)
+                .err()
+                .unwrap(),
+            WarehouseError::MissingStaffRole.into()
+        );
+        assert!(warehouse().require_role(&MEMBER, None, ROLE_QUOTE).is_err());
+    }
+
+    #[test]
+    fn paused_new_business_still_lets_orders_settle_and_refund() {
+        let mut config = ProgramConfig {
+            admin: OPERATOR,
+            logistics_wallet: OPERATOR,
+            pause_flags: PAUSE_NEW_ORDERS | PAUSE_NEW_OFFERS,
+            pending_admin: None,
+            version: CONFIG_VERSION,
+            fee_notice_period: DEFAULT_FEE_NOTICE_PERIOD,
+            protocol_fee_bps: 0,
+            reserved: [0; 54],
+        };
+        assert!(config.require_not_paused(PAUSE_SETTLEMENTS).is_ok());
+        assert!(config.require_not_paused(PAUSE_REFUNDS).is_ok());
+
+        config.pause_flags = PAUSE_SETTLEMENTS | PAUSE_REFUNDS;
+        assert_eq!(
+            config.require_not_paused(PAUSE_SETTLEMENTS).unwrap_err(),
+            ConfigError::SettlementsPaused.into()
+        );
+        assert_eq!(
+            config.require_not_paused(PAUSE_REFUNDS).unwrap_err(),
+            ConfigError::RefundsPaused.into()
+        );
+        assert!(config.require_not_paused(PAUSE_NEW_ORDERS).is_ok());
+
+        config.pause_flags = PAUSE_LEGACY_ALL;
+        assert_eq!(
+            config.require_not_paused(PAUSE_REFUNDS).unwrap_err(),
+            ConfigError::RefundsPaused.into()
+        );
+    }
+
+    #[test]
+    fn suspended_blocks_only_new_business() {
+        let mut warehouse = warehouse();
+        warehouse.status = WarehouseStatus::Suspended;
+
+        for flag in [PAUSE_NEW_ORDERS, PAUSE_NEW_OFFERS] {
+            assert_eq!(
+                warehouse.require_open_for(flag).err().unwrap(),
+                WarehouseError::WarehouseSuspended.into()
+            );
+        }
+        for flag in [PAUSE_ONBOARDING, PAUSE_SETTLEMENTS, PAUSE_REFUNDS] {
+            assert!(warehouse.require_open_for(flag).is_ok());
+        }
+    }
+
+    #[test]
+    fn closed_allows_only_refunds() {
+        let mut warehouse = warehouse();
+        warehouse.status = WarehouseStatus::Closed;
+
+        for flag in [
+            PAUSE_NEW_ORDERS,
+            PAUSE_NEW_OFFERS,
+            PAUSE_ONBOARDING,
+            PAUSE_SETTLEMENTS,
+        ] {
+            assert_eq!(
+                warehouse.require_open_for(flag).err().unwrap(),
+                WarehouseError::WarehouseClosed.into()
+            );
+        }
+        assert!(warehouse.require_open_for(PAUSE_REFUNDS).is_ok());
+    }
+
+    #[test]
+    fn closed_is_final() {
+        use WarehouseStatus::*;
+
+        assert!(Active.can_transition_to(Suspended));
+        assert!(Suspended.can_transition_to(Active));
+        assert!(Active.can_transition_to(Closed));
+        assert!(Suspended.can_transition_to(Closed));
+        assert!(!Closed.can_transition_to(Active));
+        assert!(!Closed.can_transition_to(Suspended));
+        assert!(!Active.can_transition_to(Active));
+    }
+
+    #[test]
+    fn zip_prefix_len_and_digits_are_bounded() {
+        assert!(zip(123, 3).validate().is_ok());
+        assert!(zip(0, 3).validate().is_ok());
+        assert!(zip(99_999, 5).validate().is_ok());
+        assert!(zip(12, 2).validate().is_err());
+        assert!(zip(123_456, 6).validate().is_err());
+        assert!(zip(1_000, 3).validate().is_err());
+        assert!(zip(100_000, 5).validate().is_err());
+    }
+
+    #[test]
+    fn zip_prefix_covers_longer_codes_in_its_area() {
+        let prefix = zip(123, 3);
+
+        assert!(prefix.covers(&zip(123, 3)));
+        assert!(prefix.covers(&zip(1234, 4)));
+        assert!(prefix.covers(&zip(12_399, 5)));
+        assert!(!prefix.covers(&zip(12_400, 5)));
+        // Shorter than the prefix: the area is wider than what is served
+        assert!(!prefix.covers(&zip(12, 2)));
+        // Leading zeros count: 0123 is another area
+        assert!(!prefix.covers(&zip(123, 4)));
+        // 0012 covers 00123
+        assert!(zip(12, 4).covers(&zip(123, 5)));
+    }
+
+    #[test]
+    fn warehouse_serves_any_listed_prefix() {
+        let mut warehouse = warehouse();
+        assert!(!warehouse.serves(&zip(12_345, 5)));
+
+        warehouse.deliver_zip_prefixes = vec![zip(123, 3), zip(4_567, 4)];
+        assert!(warehouse.serves(&zip(12_345, 5)));
+        assert!(warehouse.serves(&zip(45_678, 5)));
+        assert!(!warehouse.serves(&zip(45_688, 5)));
+    }
+
+    #[test]
+    fn warehouse_rejects_invalid_or_duplicate_prefixes() {
+        let mut warehouse = warehouse();
+        warehouse.deliver_zip_prefixes = vec![zip(123, 3), zip(123, 4), zip(1_234, 4)];
+        assert!(warehouse.validate().is_ok());
+
+        warehouse.deliver_zip_prefixes.push(zip(123, 3));
+        assert_eq!(
+            warehouse.validate().unwrap_err(),
+            WarehouseError::DuplicateZipPrefix.into()
+        );
+
+        warehouse.deliver_zip_prefixes = vec![zip(12, 2)];
+        assert_eq!(
+            warehouse.validate().unwrap_err(),
+            WarehouseError::InvalidZipPrefix.into()
+        );
+    }
+
+    #[test]
+    fn warehouse_needs_an_operator() {
+        let mut warehouse = warehouse();
+        warehouse.operator = Pubkey::default();
+        assert_eq!(
+            warehouse.validate().unwrap_err(),
+            WarehouseError::InvalidOperator.into()
+        );
+    }
+
+    #[test]
+    fn profile_uri_needs_an_allowed_scheme() {
+        assert!(has_allowed_scheme("https://example.com/farm"));
+        assert!(has_allowed_scheme("ipfs://bafybeigdyrzt"));
+        assert!(has_allowed_scheme("ar://abc123"));
+        assert!(!has_allowed_scheme("http://example.com"));
+        assert!(!has_allowed_scheme("javascript:alert(1)"));
+        assert!(!has_allowed_scheme("HTTPS://example.com"));
+        assert!(!has_allowed_scheme("https://"));
+        assert!(!has_allowed_scheme(""));
+    }
+
+    #[test]
+    fn farmer_profile_bounds() {
+        let mut profile = farmer();
+        assert!(profile.validate().is_ok());
+
+        profile.public_profile_uri = "ipfs://bafybeigdyrzt".to_string();
+        assert!(profile.validate().is_ok());
+
+        profile.public_profile_uri = "ftp://example.com".to_string();
+        assert_eq!(
+            profile.validate().unwrap_err(),
+            FarmerError::InvalidProfileUriScheme.into()
+        );
+
+        profile.public_profile_uri = format!("https://{}", "u".repeat(MAX_URI_LEN));
+        assert_eq!(
+            profile.validate().unwrap_err(),
+            FarmerError::ProfileUriTooLong.into()
+        );
+
+        profile = farmer();
+        profile.display_name = String::new();
+        assert_eq!(
+            profile.validate().unwrap_err(),
+            FarmerError::EmptyDisplayName.into()
+        );
+        profile.display_name = "n".repeat(MAX_NAME_LEN + 1);
+        assert_eq!(
+            profile.validate().unwrap_err(),
+            FarmerError::DisplayNameTooLong.into()
+        );
+
+        profile = farmer();
+        profile.payout_wallet = Pubkey::default();
+        assert_eq!(
+            profile.validate().unwrap_err(),
+            FarmerError::InvalidPayoutWallet.into()
+        );
+    }
+
+    #[test]
+    fn delegate_manages_offers_only() {
+        let mut profile = farmer();
+        assert!(profile.require_offer_signer(&MEMBER).is_ok());
+        assert_eq!(
+            profile.require_offer_signer(&OPERATOR).unwrap_err(),
+            FarmerError::UnauthorizedOfferSigner.into()
+        );
+
+        profile.delegate = Some(OPERATOR);
+        assert!(profile.validate().is_ok());
+        assert!(profile.require_offer_signer(&OPERATOR).is_ok());
+        assert!(profile.require_offer_signer(&MEMBER).is_ok());
+
+        profile.delegate = Some(MEMBER);
+        assert_eq!(
+            profile.validate().unwrap_err(),
+            FarmerError::InvalidDelegate.into()
+        );
+        profile.delegate = Some(Pubkey::default());
+        assert_eq!(
+            profile.validate().unwrap_err(),
+            FarmerError::InvalidDelegate.into()
+        );
+    }
+
+    #[test]
+    fn farmer_profile_closes_only_when_idle() {
+        let mut profile = farmer();
+        assert!(profile.require_closable().is_ok());
+
+        profile.active_offers = 1;
+        assert_eq!(
+            profile.require_closable().unwrap_err(),
+            FarmerError::ActiveOffersRemaining.into()
+        );
+
+        profile.active_offers = 0;
+        profile.offer_accounts = 1;
+        assert_eq!(
+            profile.require_closable().unwrap_err(),
+            FarmerError::OfferAccountsRemaining.into()
+        );
+
+        profile.offer_accounts = 0;
+        profile.open_orders = 2;
+        assert_eq!(
+            profile.require_closable().unwrap_err(),
+            FarmerError::OpenOrdersRemaining.into()
+        );
+    }
+
+    #[test]
+    fn affiliation_gates_offers_and_its_own_closing() {
+        let mut affiliation = FarmerAffiliation {
+            warehouse: Pubkey::new_unique(),
+            farmer: MEMBER,
+            status: AffiliationStatus::Pending,
+            requested_at: 0,
+            decided_at: 0,
+            active_offers: 0,
+            bump: 255,
+        };
+        assert!(affiliation.require_pending().is_ok());
+
+        for status in [AffiliationStatus::Pending, AffiliationStatus::Rejected] {
+            affiliation.status = status;
+            assert_eq!(
+                affiliation.require_active().unwrap_err(),
+                FarmerError::AffiliationNotActive.into()
+            );
+        }
+
+        affiliation.status = AffiliationStatus::Approved;
+        assert!(affiliation.require_active().is_ok());
+        assert_eq!(
+            affiliation.require_pending().unwrap_err(),
+            FarmerError::AffiliationNotPending.into()
+        );
+
+        affiliation.active_offers = 1;
+        assert_eq!(
+            affiliation.require_closable().unwrap_err(),
+            FarmerError::ActiveOffersAtWarehouse.into()
+        );
+    }
+
+    #[test]
+    fn offer_bounds() {
+        let now = 1_000;
+        let offer = || LotOffer {
+            farmer: MEMBER,
+            warehouse: Pubkey::new_unique(),
+            offer_id: 0,
+            crop_name: "Tomato".to_string(),
+            cultivar_name: None,
+            unit_code: 2,
+            expires_at: None,
+            notes_public: None,
+            mint: Pubkey::new_unique(),
+            price_minor: 5,
+            qty_remaining: 10_000,
+            active: true,
+            created_at: now,
+            rent_payer: MEMBER,
+            bump: 255,
+        };
+        assert!(offer().validate(now).is_ok());
+
+        let rejects = |break_it: fn(&mut LotOffer), error: OfferError| {
+            let mut offer = offer();
+            break_it(&mut offer);
+            assert_eq!(offer.validate(now).unwrap_err(), error.into());
+        };
+        rejects(|o| o.crop_name.clear(), OfferError::EmptyCropName);
+        rejects(
+            |o| o.crop_name = "a".repeat(MAX_NAME_LEN + 1),
+            OfferError::CropNameTooLong,
+        );
+        rejects(
+            |o| o.cultivar_name = Some("a".repeat(MAX_NAME_LEN + 1)),
+            OfferError::CultivarNameTooLong,
+        );
+        rejects(
+            |o| o.notes_public = Some("a".repeat(MAX_NOTES_LEN + 1)),
+            OfferError::NotesTooLong,
+        );
+        rejects(|o| o.qty_remaining = 0, OfferError::InvalidQuantity);
+        rejects(|o| o.price_minor = 0, OfferError::InvalidPrice);
+        // Expiring exactly now is already too late
+        rejects(|o| o.expires_at = Some(1_000), OfferError::InvalidExpiry);
+    }
+
+    #[test]
+    fn customer_confirmation_state_machine() {
+        let mut entry = WarehouseCustomer {
+            warehouse: Pubkey::new_unique(),
+            customer: Pubkey::default(),
+            status: WarehouseCustomerStatus::Pending,
+            requested_at: 0,
+            confirmed_at: None,
+            valid_until: None,
+            notes_hash: None,
+            address_envelope: None,
+            open_orders: 0,
+            bump: 255,
+        };
+        // Fresh account: zeroed, so `Pending` without a customer
+        assert!(entry.require_can_request(0).is_ok());
+
+        entry.customer = MEMBER;
+        assert_eq!(
+            entry.require_can_request(0).unwrap_err(),
+            CustomerError::ConfirmationAlreadyPending.into()
+        );
+        assert!(entry.require_pending().is_ok());
+        assert!(entry.require_revocable().is_ok());
+        assert_eq!(
+            entry.require_confirmed(0).unwrap_err(),
+            CustomerError::CustomerNotConfirmed.into()
+        );
+        assert_eq!(
+            entry.require_renewable().unwrap_err(),
+            CustomerError::CustomerNotConfirmed.into()
+        );
+
+        entry.status = WarehouseCustomerStatus::Confirmed;
+        assert!(entry.require_confirmed(i64::MAX).is_ok());
+        assert!(entry.require_renewable().is_ok());
+        assert!(entry.require_revocable().is_ok());
+        assert_eq!(
+            entry.require_can_request(0).unwrap_err(),
+            CustomerError::AlreadyConfirmed.into()
+        );
+        assert_eq!(
+            entry.require_pending().unwrap_err(),
+            CustomerError::ConfirmationNotPending.into()
+        );
+
+        entry.status = WarehouseCustomerStatus::Revoked;
+        assert!(entry.require_can_request(0).is_ok());
+        assert_eq!(
+            entry.require_revocable().unwrap_err(),
+            CustomerError::AlreadyRevoked.into()
+        );
+        assert_eq!(
+            entry.require_pending().unwrap_err(),
+            CustomerError::ConfirmationNotPending.into()
+        );
+        assert_eq!(
+            entry.require_confirmed(0).unwrap_err(),
+            CustomerError::CustomerNotConfirmed.into()
+        );
+        assert_eq!(
+            entry.require_renewable().unwrap_err(),
+            CustomerError::CustomerNotConfirmed.into()
+        );
+    }
+
+    #[test]
+    fn confirmation_expires_at_valid_until() {
+        let mut entry = WarehouseCustomer {
+            warehouse: Pubkey::new_unique(),
+            customer: MEMBER,
+            status: WarehouseCustomerStatus::Confirmed,
+            requested_at: 0,
+            confirmed_at: Some(0),
+            valid_until: Some(100),
+            notes_hash: None,
+            address_envelope: None,
+            open_orders: 0,
+            bump: 255,
+        };
+        assert!(entry.require_confirmed(99).is_ok());
+        assert_eq!(
+            entry.require_can_request(99).unwrap_err(),
+            CustomerError::AlreadyConfirmed.into()
+        );
+
+        assert_eq!(
+            entry.require_confirmed(100).unwrap_err(),
+            CustomerError::ConfirmationExpired.into()
+        );
+        // Expired: the warehouse may renew and the customer may request again
+        assert!(entry.require_renewable().is_ok());
+        assert!(entry.require_can_request(100).is_ok());
+
+        entry.valid_until = None;
+        assert!(entry.require_confirmed(i64::MAX).is_ok());
+    }
+
+    #[test]
+    fn confirmation_expiry_defaults_to_warehouse_validity() {
+        let mut warehouse = warehouse();
+        assert_eq!(warehouse.confirmation_expiry(None, 1_000).unwrap(), None);
+        assert_eq!(
+            warehouse.confirmation_expiry(Some(2_000), 1_000).unwrap(),
+            Some(2_000)
+        );
+        assert_eq!(
+            warehouse.confirmation_expiry(Some(1_000), 1_000).unwrap_err(),
+            CustomerError::InvalidConfirmationExpiry.into()
+        );
+
+        warehouse.confirmation_validity = Some(365 * 86_400);
+        assert!(warehouse.validate().is_ok());
+        assert_eq!(
+            warehouse.confirmation_expiry(None, 1_000).unwrap(),
+            Some(1_000 + 365 * 86_400)
+        );
+        // An explicit expiry overrides the default
+        assert_eq!(
+            warehouse.confirmation_expiry(Some(2_000), 1_000).unwrap(),
+            Some(2_000)
+        );
+        assert_eq!(
+            warehouse.confirmation_expiry(None, i64::MAX).unwrap_err(),
+            OrderError::MathOverflow.into()
+        );
+
+        for validity in [0, -1] {
+            warehouse.confirmation_validity = Some(validity);
+            assert_eq!(
+                warehouse.validate().unwrap_err(),
+                WarehouseError::InvalidConfirmationValidity.into()
+            );
+        }
+    }
+
+    #[test]
+    fn customer_profile_bounds() {
+        let mut profile = CustomerProfile {
+            authority: MEMBER,
+            public_profile_uri: String::new(),
+            order_counter: 0,
+            open_orders: 0,
+            linked_warehouses: 0,
+            created_at: 0,
+            bump: 255,
+        };
+        assert!(profile.validate().is_ok());
+
+        profile.public_profile_uri = "ar://abc123".to_string();
+        assert!(profile.validate().is_ok());
+
+        profile.public_profile_uri = "http://example.com".to_string();
+        assert_eq!(
+            profile.validate().unwrap_err(),
+            CustomerError::InvalidProfileUriScheme.into()
+        );
+
+        profile.public_profile_uri = format!("https://{}", "u".repeat(MAX_URI_LEN));
+        assert_eq!(
+            profile.validate().unwrap_err(),
+            CustomerError::ProfileUriTooLong.into()
+        );
+    }
+
+    #[test]
+    fn customer_leaves_only_without_open_orders() {
+        let mut profile = CustomerProfile {
+            authority: MEMBER,
+            public_profile_uri: String::new(),
+            order_counter: 3,
+            open_orders: 0,
+            linked_warehouses: 0,
+            created_at: 0,
+            bump: 255,
+        };
+        assert!(profile.require_closable().is_ok());
+
+        profile.linked_warehouses = 1;
+        assert_eq!(
+            profile.require_closable().unwrap_err(),
+            CustomerError::LinkedWarehousesRemaining.into()
+        );
+        profile.open_orders = 1;
+        assert_eq!(
+            profile.require_closable().unwrap_err(),
+            CustomerError::OpenOrdersRemaining.into()
+        );
+
+        let mut entry = WarehouseCustomer {
+            warehouse: Pubkey::new_unique(),
+            customer: MEMBER,
+            status: WarehouseCustomerStatus::Confirmed,
+            requested_at: 0,
+            confirmed_at: Some(0),
+            valid_until: None,
+            notes_hash: Some([1; 32]),
+            address_envelope: None,
+            open_orders: 1,
+            bump: 255,
+        };
+        assert_eq!(
+            entry.require_withdrawable().unwrap_err(),
+            CustomerError::OpenOrdersRemaining.into()
+        );
+        entry.open_orders = 0;
+        assert!(entry.require_withdrawable().is_ok());
+    }
+
+    #[test]
+    fn address_envelope_must_match_warehouse_key_and_confirmation() {
+        let key = [7; 32];
+        let envelope = AddressEnvelope {
+            uri: "ipfs://bafyaddress".to_string(),
+            ciphertext_hash: [9; 32],
+            encryption_key: key,
+        };
+        assert!(envelope.validate(Some(key)).is_ok());
+        assert_eq!(
+            envelope.validate(None).unwrap_err(),
+            CustomerError::EncryptionKeyNotSet.into()
+        );
+        // Sealed to a key the warehouse has since rotated away from
+        assert_eq!(
+            envelope.validate(Some([8; 32])).unwrap_err(),
+            CustomerError::EnvelopeKeyMismatch.into()
+        );
+        let rejects = |uri: String, error: CustomerError| {
+            let envelope = AddressEnvelope { uri, ..envelope.clone() };
+            assert_eq!(envelope.validate(Some(key)).unwrap_err(), error.into());
+        };
+        rejects(String::new(), CustomerError::InvalidEnvelopeUri);
+        rejects("http://example.com".to_string(), CustomerError::InvalidEnvelopeUri);
+        rejects(
+            format!("https://{}", "u".repeat(MAX_URI_LEN)),
+            CustomerError::EnvelopeUriTooLong,
+        );
+
+        let mut entry = WarehouseCustomer {
+            warehouse: Pubkey::new_unique(),
+            customer: MEMBER,
+            status: WarehouseCustomerStatus::Pending,
+            requested_at: 0,
+            confirmed_at: None,
+            valid_until: None,
+            notes_hash: None,
+            address_envelope: None,
+            open_orders: 0,
+            bump: 255,
+        };
+        assert!(entry.require_envelope_hash(None).is_ok());
+        assert_eq!(
+            entry.require_envelope_hash(Some([9; 32])).unwrap_err(),
+            CustomerError::EnvelopeHashMismatch.into()
+        );
+
+        entry.address_envelope = Some(envelope);
+        assert!(entry.require_envelope_hash(Some([9; 32])).is_ok());
+        assert_eq!(
+            entry.require_envelope_hash(None).unwrap_err(),
+            CustomerError::EnvelopeHashMismatch.into()
+        );
+        assert_eq!(
+            entry.require_envelope_hash(Some([1; 32])).unwrap_err(),
+            CustomerError::EnvelopeHashMismatch.into()
+        );
+    }
+
+    #[test]
+    fn settlement_without_protocol_fee_pays_warehouse_all_fees() {
+        let split = SettlementSplit::compute(10_000, 300, 500, 0).unwrap();
+
+        assert_eq!(split.farmer_payout, 9_700);
+        assert_eq!(split.warehouse_payout, 800);
+        assert_eq!(split.protocol_payout, 0);
+        assert_eq!(split.total().unwrap(), 10_500);
+    }
+
+    #[test]
+    fn settlement_protocol_cut_rounds_down_and_conserves_funds() {
+        // 10% of 333 = 33.3 -> 33 to the protocol, 300 to the warehouse
+        let split = SettlementSplit::compute(1_000, 333, 0, 1_000).unwrap();
+
+        assert_eq!(split.farmer_payout, 667);
+        assert_eq!(split.protocol_payout, 33);
+        assert_eq!(split.warehouse_payout, 300);
+        assert_eq!(split.total().unwrap(), 1_000);
+
+        let all = SettlementSplit::compute(5, 2, 3, 10_000).unwrap();
+        assert_eq!((all.warehouse_payout, all.protocol_payout), (0, 5));
+    }
+
+    #[test]
+    fn settlement_rejects_fee_overflow() {
+        assert!(SettlementSplit::compute(u64::MAX, u64::MAX, 1, 0).is_err());
+        // A service fee floor above the subtotal cannot be settled
+        assert!(SettlementSplit::compute(5, 6, 0, 0).is_err());
+    }
+
+    #[test]
+    fn roles_must_be_known_and_non_empty() {
+        assert!(WarehouseStaff::validate_roles(ROLE_ALL).is_ok());
+        assert!(WarehouseStaff::validate_roles(0).is_err());
+        assert!(WarehouseStaff::validate_roles(ROLE_ALL + 1).is_err());
+    }
+
+    #[test]
+    fn staff_member_must_not_be_unset_or_the_operator() {
+        assert!(WarehouseStaff::validate_member(&MEMBER, &OPERATOR).is_ok());
+        assert_eq!(
+            WarehouseStaff::validate_member(&Pubkey::default(), &OPERATOR).unwrap_err(),
+            WarehouseError::InvalidStaffMember.into()
+        );
+        assert_eq!(
+            WarehouseStaff::validate_member(&OPERATOR, &OPERATOR).unwrap_err(),
+            WarehouseError::InvalidStaffMember.into()
+        );
+    }
+}
@@ -0,0 +1,121 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+
+describe("accept_admin", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const { getConfigPDA } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should hand over admin to the proposed key and back", async () => {
+      const newAdmin = Keypair.generate();
+
+      await program.methods
+        .proposeAdmin(newAdmin.publicKey)
+        .accounts({ config: configPDA, admin: admin.publicKey })
+        .rpc();
+
+      await program.methods
+        .acceptAdmin()
+        .accounts({ config: configPDA, newAdmin: newAdmin.publicKey })
+        .signers([newAdmin])
+        .rpc();
+
+      let config = await program.account.programConfig.fetch(configPDA);
+      expect(config.admin.toString()).to.equal(newAdmin.publicKey.toString());
+      expect(config.pendingAdmin).to.be.null;
+
+      // Hand control back to the provider wallet for the other test files
+      await program.methods
+        .proposeAdmin(admin.publicKey)
+        .accounts({ config: configPDA, admin: newAdmin.publicKey })
+        .signers([newAdmin])
+        .rpc();
+      await program.methods
+        .acceptAdmin()
+        .accounts({ config: configPDA, newAdmin: admin.publicKey })
+        .rpc();
+
+      config = await program.account.programConfig.fetch(configPDA);
+      expect(config.admin.toString()).to.equal(admin.publicKey.toString());
+      expect(config.pendingAdmin).to.be.null;
+    });
+  });
+
+  describe("error cases", () => {
+    it("should fail when no transfer is pending", async () => {
+      const someone = Keypair.generate();
+
+      try {
+        await program.methods
+          .acceptAdmin()
+          .accounts({ config: configPDA, newAdmin: someone.publicKey })
+          .signers([someone])
+          .rpc();
+
+        expect.fail("Should have thrown an error for missing pending admin");
+      } catch (err) {
+        expect(err.toString()).to.include("NoPendingAdmin");
+      }
+    });
+
+    it("should fail when signer is not the pending admin", async () => {
+      const proposed = Keypair.generate();
+      const attacker = Keypair.generate();
+
+      await program.methods
+        .proposeAdmin(proposed.publicKey)
+        .accounts({ config: configPDA, admin: admin.publicKey })
+        .rpc();
+
+      try {
+        await program.methods
+          .acceptAdmin()
+          .accounts({ config: configPDA, newAdmin: attacker.publicKey })
+          .signers([attacker])
+          .rpc();
+
+        expect.fail("Should have thrown an error for wrong pending admin");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedPendingAdmin");
+      } finally {
+        await program.methods
+          .cancelAdminTransfer()
+          .accounts({ config: configPDA, admin: admin.publicKey })
+          .rpc();
+      }
+
+      const config = await program.account.programConfig.fetch(configPDA);
+      expect(config.admin.toString()).to.equal(admin.publicKey.toString());
+    });
+  });
+});
@@ -0,0 +1,195 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { createMint, TOKEN_2022_PROGRAM_ID } from "./helpers/token";
+import { pdaHelpers } from "./helpers/pda";
+
+describe("add_allowed_mint", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const { getConfigPDA, getAllowedMintPDA } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  const addAllowedMint = (
+    mint: PublicKey,
+    minOrder = new anchor.BN(0),
+    maxOrder = new anchor.BN(1_000_000_000),
+    feeFloor: anchor.BN | null = null
+  ) =>
+    program.methods
+      .addAllowedMint(minOrder, maxOrder, feeFloor)
+      .accounts({
+        config: configPDA,
+        allowedMint: getAllowedMintPDA(mint)[0],
+        mint,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should create the entry for an SPL Token mint", async () => {
+      const mint = await createMint(provider, 6);
+
+      await addAllowedMint(mint);
+
+      const [allowedMintPDA, bump] = getAllowedMintPDA(mint);
+      const entry = await program.account.allowedMint.fetch(allowedMintPDA);
+      expect(entry.mint.toString()).to.equal(mint.toString());
+      expect(entry.bump).to.equal(bump);
+    });
+
+    it("should create the entry for a Token-2022 mint", async () => {
+      const mint = await createMint(provider, 9, TOKEN_2022_PROGRAM_ID);
+
+      await addAllowedMint(mint);
+
+      const entry = await program.account.allowedMint.fetch(
+        getAllowedMintPDA(mint)[0]
+      );
+      expect(entry.mint.toString()).to.equal(mint.toString());
+      expect(entry.decimals).to.equal(9);
+    });
+
+    it("should snapshot decimals and store the order limits", async () => {
+      const mint = await createMint(provider, 4);
+
+      await addAllowedMint(
+        mint,
+        new anchor.BN(500),
+        new anchor.BN(2_000_000),
+        new anchor.BN(25)
+      );
+
+      const entry = await program.account.allowedMint.fetch(
+        getAllowedMintPDA(mint)[0]
+      );
+      expect(entry.decimals).to.equal(4);
+      expect(entry.minOrderSubtotal.toNumber()).to.equal(500);
+      expect(entry.maxOrderSubtotal.toNumber()).to.equal(2_000_000);
+      expect(entry.serviceFeeFloor.toNumber()).to.equal(25);
+    });
+
+    it("should emit AllowedMintAdded", async () => {
+      const mint = await createMint(provider, 6);
+
+      let event: any = null;
+      const listener = program.addEventListener("allowedMintAdded", (e) => {
+        event = e;
+      });
+
+      await addAllowedMint(mint);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.mint.toString()).to.equal(mint.toString());
+      expect(event.admin.toString()).to.equal(admin.publicKey.toString());
+      expect(event.decimals).to.equal(6);
+    });
+  });
+
+  describe("error cases", () => {
+    it("should reject a duplicate mint", async () => {
+      const mint = await createMint(provider, 6);
+      await addAllowedMint(mint);
+
+      try {
+        await addAllowedMint(mint);
+        expect.fail("Should have thrown an error for duplicate mint");
+      } catch (err) {
+        expect(err.toString()).to.include("MintAlreadyAllowed");
+      }
+    });
+
+    it("should reject a min order above the max order", async () => {
+      const mint = await createMint(provider, 6);
+
+      try {
+        await addAllowedMint(mint, new anchor.BN(1_001), new anchor.BN(1_000));
+        expect.fail("Should have thrown an error for invalid limits");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidMintLimits");
+      }
+    });
+
+    it("should reject a zero max order", async () => {
+      const mint = await createMint(provider, 6);
+
+      try {
+        await addAllowedMint(mint, new anchor.BN(0), new anchor.BN(0));
+        expect.fail("Should have thrown an error for invalid limits");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidMintLimits");
+      }
+    });
+
+    it("should reject an account that is not a mint", async () => {
+      try {
+        await addAllowedMint(Keypair.generate().publicKey);
+        expect.fail("Should have thrown an error for non-mint account");
+      } catch (err) {
+        const errorStr = err.toString();
+        expect(
+          errorStr.includes("AccountNotInitialized") ||
+            errorStr.includes("AccountOwnedByWrongProgram")
+        ).to.be.true;
+      }
+    });
+
+    it("should fail when signer is not the admin", async () => {
+      const attacker = Keypair.generate();
+      const mint = await createMint(provider, 6);
+
+      try {
+        await program.methods
+          .addAllowedMint(new anchor.BN(0), new anchor.BN(1_000), null)
+          .accounts({
+            config: configPDA,
+            allowedMint: getAllowedMintPDA(mint)[0],
+            mint,
+            admin: attacker.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([attacker])
+          .rpc();
+
+        expect.fail("Should have thrown an error for unauthorized admin");
+      } catch (err) {
+        const errorStr = err.toString();
+        // The unfunded attacker may fail to pay for the entry first
+        expect(
+          errorStr.includes("UnauthorizedAdmin") ||
+            errorStr.includes("insufficient")
+        ).to.be.true;
+      }
+    });
+  });
+});
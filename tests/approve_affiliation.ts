@@ -0,0 +1,262 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+const PAUSE_ONBOARDING = 1 << 3;
+const SUSPENDED = { suspended: {} };
+const CLOSED = { closed: {} };
+
+describe("approve_affiliation", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const {
+    getConfigPDA,
+    getWarehousePDA,
+    getFarmerPDA,
+    getAffiliationPDA,
+  } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  // Registered farmer with a funded key
+  const newFarmer = async () => {
+    const farmer = await newFunded(provider);
+    await program.methods
+      .registerFarmer("Green Acres", "")
+      .accounts({
+        config: configPDA,
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        authority: farmer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([farmer])
+      .rpc();
+    return farmer;
+  };
+
+  const createWarehouse = async () => {
+    const warehouseId = new anchor.BN(
+      Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)
+    );
+    const operator = await newFunded(provider);
+    const [warehouse] = getWarehousePDA(warehouseId);
+
+    await program.methods
+      .createWarehouse(warehouseId, operator.publicKey, "Hub", "", 500, [], null)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return { warehouse, operator };
+  };
+
+  const requestAffiliation = (
+    farmer: Keypair,
+    warehouse: PublicKey,
+    signer: Keypair = farmer
+  ) =>
+    program.methods
+      .requestWarehouseAffiliation()
+      .accounts({
+        config: configPDA,
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        warehouse,
+        affiliation: getAffiliationPDA(farmer.publicKey, warehouse)[0],
+        authority: signer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc();
+
+  const approveAffiliation = (
+    farmer: PublicKey,
+    warehouse: PublicKey,
+    operator: Keypair
+  ) =>
+    program.methods
+      .approveAffiliation()
+      .accounts({
+        config: configPDA,
+        affiliation: getAffiliationPDA(farmer, warehouse)[0],
+        warehouse,
+        operator: operator.publicKey,
+      })
+      .signers([operator])
+      .rpc();
+
+  const setPauseFlags = (pauseFlags: number) =>
+    program.methods
+      .setPauseFlags(pauseFlags)
+      .accounts({ config: configPDA, admin: admin.publicKey })
+      .rpc();
+
+  const setStatus = (warehouse: PublicKey, status: object) =>
+    program.methods
+      .setWarehouseStatus(status as any)
+      .accounts({ config: configPDA, warehouse, admin: admin.publicKey })
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should approve a pending request", async () => {
+      const farmer = await newFarmer();
+      const { warehouse, operator } = await createWarehouse();
+      await requestAffiliation(farmer, warehouse);
+
+      await approveAffiliation(farmer.publicKey, warehouse, operator);
+
+      const affiliation = await program.account.farmerAffiliation.fetch(
+        getAffiliationPDA(farmer.publicKey, warehouse)[0]
+      );
+      expect(affiliation.status).to.deep.equal({ approved: {} });
+      expect(affiliation.decidedAt.toNumber()).to.be.greaterThan(0);
+    });
+
+    it("should allow a suspended warehouse", async () => {
+      const farmer = await newFarmer();
+      const { warehouse, operator } = await createWarehouse();
+      await requestAffiliation(farmer, warehouse);
+      await setStatus(warehouse, SUSPENDED);
+
+      await approveAffiliation(farmer.publicKey, warehouse, operator);
+
+      const affiliation = await program.account.farmerAffiliation.fetch(
+        getAffiliationPDA(farmer.publicKey, warehouse)[0]
+      );
+      expect(affiliation.status).to.deep.equal({ approved: {} });
+    });
+
+    it("should emit AffiliationApproved", async () => {
+      const farmer = await newFarmer();
+      const { warehouse, operator } = await createWarehouse();
+      await requestAffiliation(farmer, warehouse);
+
+      let event: any = null;
+      const listener = program.addEventListener(
+        "affiliationApproved",
+        (e) => {
+          event = e;
+        }
+      );
+
+      await approveAffiliation(farmer.publicKey, warehouse, operator);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.farmer.toString()).to.equal(farmer.publicKey.toString());
+      expect(event.operator.toString()).to.equal(
+        operator.publicKey.toString()
+      );
+      expect(event.warehouse.toString()).to.equal(warehouse.toString());
+    });
+  });
+
+  describe("error cases", () => {
+    it("should fail when signer is not the operator", async () => {
+      const farmer = await newFarmer();
+      const { warehouse } = await createWarehouse();
+      await requestAffiliation(farmer, warehouse);
+
+      try {
+        await approveAffiliation(farmer.publicKey, warehouse, farmer);
+        expect.fail("Should have thrown an error for a non-operator");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedOperator");
+      }
+    });
+
+    it("should fail for a warehouse the farmer did not request", async () => {
+      const farmer = await newFarmer();
+      const requested = await createWarehouse();
+      const other = await createWarehouse();
+      await requestAffiliation(farmer, requested.warehouse);
+
+      try {
+        await approveAffiliation(
+          farmer.publicKey,
+          other.warehouse,
+          other.operator
+        );
+        expect.fail("Should have thrown an error for another warehouse");
+      } catch (err) {
+        expect(err.toString()).to.include("AccountNotInitialized");
+      }
+    });
+
+    it("should fail for a request that is no longer pending", async () => {
+      const farmer = await newFarmer();
+      const { warehouse, operator } = await createWarehouse();
+      await requestAffiliation(farmer, warehouse);
+      await approveAffiliation(farmer.publicKey, warehouse, operator);
+
+      try {
+        await approveAffiliation(farmer.publicKey, warehouse, operator);
+        expect.fail("Should have thrown an error for an approved request");
+      } catch (err) {
+        expect(err.toString()).to.include("AffiliationNotPending");
+      }
+    });
+
+    it("should fail once the warehouse is closed", async () => {
+      const farmer = await newFarmer();
+      const { warehouse, operator } = await createWarehouse();
+      await requestAffiliation(farmer, warehouse);
+      await setStatus(warehouse, CLOSED);
+
+      try {
+        await approveAffiliation(farmer.publicKey, warehouse, operator);
+        expect.fail("Should have thrown an error for a closed warehouse");
+      } catch (err) {
+        expect(err.toString()).to.include("WarehouseClosed");
+      }
+    });
+
+    it("should fail while onboarding is paused", async () => {
+      const farmer = await newFarmer();
+      const { warehouse, operator } = await createWarehouse();
+      await requestAffiliation(farmer, warehouse);
+      await setPauseFlags(PAUSE_ONBOARDING);
+
+      try {
+        await approveAffiliation(farmer.publicKey, warehouse, operator);
+        expect.fail("Should have thrown an error while paused");
+      } catch (err) {
+        expect(err.toString()).to.include("OnboardingPaused");
+      } finally {
+        await setPauseFlags(0);
+      }
+    });
+  });
+});
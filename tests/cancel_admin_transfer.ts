@@ -0,0 +1,111 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+
+describe("cancel_admin_transfer", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const { getConfigPDA } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should clear the pending admin", async () => {
+      const proposed = Keypair.generate();
+
+      await program.methods
+        .proposeAdmin(proposed.publicKey)
+        .accounts({ config: configPDA, admin: admin.publicKey })
+        .rpc();
+
+      await program.methods
+        .cancelAdminTransfer()
+        .accounts({ config: configPDA, admin: admin.publicKey })
+        .rpc();
+
+      const config = await program.account.programConfig.fetch(configPDA);
+      expect(config.pendingAdmin).to.be.null;
+      expect(config.admin.toString()).to.equal(admin.publicKey.toString());
+
+      // The canceled key can no longer accept
+      try {
+        await program.methods
+          .acceptAdmin()
+          .accounts({ config: configPDA, newAdmin: proposed.publicKey })
+          .signers([proposed])
+          .rpc();
+
+        expect.fail("Should have thrown an error after cancellation");
+      } catch (err) {
+        expect(err.toString()).to.include("NoPendingAdmin");
+      }
+    });
+  });
+
+  describe("error cases", () => {
+    it("should fail when no transfer is pending", async () => {
+      try {
+        await program.methods
+          .cancelAdminTransfer()
+          .accounts({ config: configPDA, admin: admin.publicKey })
+          .rpc();
+
+        expect.fail("Should have thrown an error for missing pending admin");
+      } catch (err) {
+        expect(err.toString()).to.include("NoPendingAdmin");
+      }
+    });
+
+    it("should fail when signer is not the admin", async () => {
+      const attacker = Keypair.generate();
+
+      await program.methods
+        .proposeAdmin(Keypair.generate().publicKey)
+        .accounts({ config: configPDA, admin: admin.publicKey })
+        .rpc();
+
+      try {
+        await program.methods
+          .cancelAdminTransfer()
+          .accounts({ config: configPDA, admin: attacker.publicKey })
+          .signers([attacker])
+          .rpc();
+
+        expect.fail("Should have thrown an error for unauthorized admin");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedAdmin");
+      } finally {
+        await program.methods
+          .cancelAdminTransfer()
+          .accounts({ config: configPDA, admin: admin.publicKey })
+          .rpc();
+      }
+    });
+  });
+});
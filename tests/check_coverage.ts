@@ -0,0 +1,117 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+
+describe("check_coverage", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const { getConfigPDA, getWarehousePDA } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+  let warehouse: PublicKey;
+
+  // Simulates the view and decodes the returned bool
+  const checkCoverage = (prefix: number, len: number) =>
+    program.methods
+      .checkCoverage({ prefix, len })
+      .accounts({ warehouse })
+      .view();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+
+    // Serves 123xx and 04567
+    const warehouseId = new anchor.BN(
+      Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)
+    );
+    [warehouse] = getWarehousePDA(warehouseId);
+    await program.methods
+      .createWarehouse(
+        warehouseId,
+        Keypair.generate().publicKey,
+        "Coverage Hub",
+        "",
+        500,
+        [
+          { prefix: 123, len: 3 },
+          { prefix: 4567, len: 5 },
+        ],
+        null
+      )
+      .accounts({
+        config: configPDA,
+        warehouse,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  describe("success cases", () => {
+    it("should cover ZIP codes inside a served prefix", async () => {
+      expect(await checkCoverage(12345, 5)).to.be.true;
+      expect(await checkCoverage(1239, 4)).to.be.true;
+      expect(await checkCoverage(123, 3)).to.be.true;
+    });
+
+    it("should keep leading zeros significant", async () => {
+      expect(await checkCoverage(4567, 5)).to.be.true;
+      expect(await checkCoverage(45670, 5)).to.be.false;
+    });
+
+    it("should not cover other areas", async () => {
+      expect(await checkCoverage(12400, 5)).to.be.false;
+      expect(await checkCoverage(999, 3)).to.be.false;
+    });
+
+    it("should not cover an area wider than a served prefix", async () => {
+      // 456 (0456x) is only partly served through 04567
+      expect(await checkCoverage(456, 4)).to.be.false;
+    });
+  });
+
+  describe("error cases", () => {
+    it("should reject an invalid ZIP prefix", async () => {
+      try {
+        await checkCoverage(12, 2);
+        expect.fail("Should have thrown an error for a 2-digit prefix");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidZipPrefix");
+      }
+    });
+
+    it("should reject an account that is not a warehouse", async () => {
+      try {
+        await program.methods
+          .checkCoverage({ prefix: 12345, len: 5 })
+          .accounts({ warehouse: configPDA })
+          .view();
+        expect.fail("Should have thrown an error for the config account");
+      } catch (err) {
+        expect(err.toString()).to.include("AccountDiscriminatorMismatch");
+      }
+    });
+  });
+});
@@ -0,0 +1,242 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+describe("close_customer_profile", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const {
+    getConfigPDA,
+    getWarehousePDA,
+    getCustomerPDA,
+    getWarehouseCustomerPDA,
+  } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  const registerCustomer = (customer: Keypair, publicProfileUri = "") =>
+    program.methods
+      .registerCustomer(publicProfileUri)
+      .accounts({
+        config: configPDA,
+        customerProfile: getCustomerPDA(customer.publicKey)[0],
+        authority: customer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customer])
+      .rpc();
+
+  // Registered customer with a funded key
+  const newCustomer = async () => {
+    const customer = await newFunded(provider);
+    await registerCustomer(customer);
+    return customer;
+  };
+
+  const createWarehouse = async () => {
+    const warehouseId = new anchor.BN(
+      Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)
+    );
+    const operator = await newFunded(provider);
+    const [warehouse] = getWarehousePDA(warehouseId);
+
+    await program.methods
+      .createWarehouse(warehouseId, operator.publicKey, "Hub", "", 500, [], null)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return { warehouse, operator };
+  };
+
+  const requestConfirmation = (customer: Keypair, warehouse: PublicKey) =>
+    program.methods
+      .requestCustomerConfirmation(null)
+      .accounts({
+        config: configPDA,
+        customerProfile: getCustomerPDA(customer.publicKey)[0],
+        warehouse,
+        warehouseCustomer: getWarehouseCustomerPDA(
+          warehouse,
+          customer.publicKey
+        )[0],
+        authority: customer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customer])
+      .rpc();
+
+  const withdraw = (
+    customer: Keypair,
+    warehouse: PublicKey,
+    signer: Keypair = customer
+  ) =>
+    program.methods
+      .withdrawFromWarehouse()
+      .accounts({
+        customerProfile: getCustomerPDA(customer.publicKey)[0],
+        warehouse,
+        warehouseCustomer: getWarehouseCustomerPDA(
+          warehouse,
+          customer.publicKey
+        )[0],
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const closeCustomerProfile = (
+    customer: Keypair,
+    signer: Keypair = customer
+  ) =>
+    program.methods
+      .closeCustomerProfile()
+      .accounts({
+        customerProfile: getCustomerPDA(customer.publicKey)[0],
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should close the profile and refund the rent", async () => {
+      const customer = await newCustomer();
+      const [customerPDA] = getCustomerPDA(customer.publicKey);
+      const rent = (await provider.connection.getAccountInfo(customerPDA))
+        .lamports;
+      const balanceBefore = await provider.connection.getBalance(
+        customer.publicKey
+      );
+
+      await closeCustomerProfile(customer);
+
+      expect(await provider.connection.getAccountInfo(customerPDA)).to.be.null;
+      const balanceAfter = await provider.connection.getBalance(
+        customer.publicKey
+      );
+      // Rent comes back, minus the transaction fee paid by the customer
+      expect(balanceAfter).to.be.greaterThan(balanceBefore + rent - 10_000);
+    });
+
+    it("should close once every warehouse is withdrawn", async () => {
+      const customer = await newCustomer();
+      const { warehouse } = await createWarehouse();
+      await requestConfirmation(customer, warehouse);
+      await withdraw(customer, warehouse);
+
+      await closeCustomerProfile(customer);
+
+      expect(
+        await provider.connection.getAccountInfo(
+          getCustomerPDA(customer.publicKey)[0]
+        )
+      ).to.be.null;
+    });
+
+    it("should allow registering again with a fresh order counter", async () => {
+      const customer = await newCustomer();
+      await closeCustomerProfile(customer);
+
+      await registerCustomer(customer, "ar://again");
+
+      const profile = await program.account.customerProfile.fetch(
+        getCustomerPDA(customer.publicKey)[0]
+      );
+      expect(profile.publicProfileUri).to.equal("ar://again");
+      expect(profile.orderCounter.toNumber()).to.equal(0);
+      expect(profile.linkedWarehouses).to.equal(0);
+    });
+
+    it("should emit CustomerProfileClosed", async () => {
+      const customer = await newCustomer();
+
+      let event: any = null;
+      const listener = program.addEventListener(
+        "customerProfileClosed",
+        (e) => {
+          event = e;
+        }
+      );
+
+      await closeCustomerProfile(customer);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.customer.toString()).to.equal(
+        getCustomerPDA(customer.publicKey)[0].toString()
+      );
+      expect(event.authority.toString()).to.equal(
+        customer.publicKey.toString()
+      );
+    });
+  });
+
+  describe("error cases", () => {
+    it("should fail while still linked to a warehouse", async () => {
+      const customer = await newCustomer();
+      const { warehouse } = await createWarehouse();
+      await requestConfirmation(customer, warehouse);
+
+      try {
+        await closeCustomerProfile(customer);
+        expect.fail("Should have thrown an error for a linked warehouse");
+      } catch (err) {
+        expect(err.toString()).to.include("LinkedWarehousesRemaining");
+      }
+    });
+
+    it("should fail for a customer that is not registered", async () => {
+      try {
+        await closeCustomerProfile(await newFunded(provider));
+        expect.fail("Should have thrown an error for missing profile");
+      } catch (err) {
+        expect(err.toString()).to.include("AccountNotInitialized");
+      }
+    });
+
+    it("should reject another key's signature", async () => {
+      const customer = await newCustomer();
+      const attacker = await newFunded(provider);
+
+      try {
+        await closeCustomerProfile(customer, attacker);
+        expect.fail("Should have thrown an error for another signer");
+      } catch (err) {
+        expect(err.toString()).to.include("ConstraintSeeds");
+      }
+    });
+  });
+});
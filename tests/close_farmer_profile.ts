@@ -0,0 +1,149 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+describe("close_farmer_profile", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const { getConfigPDA, getFarmerPDA } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  const registerFarmer = (
+    farmer: Keypair,
+    displayName = "Green Acres",
+    publicProfileUri = "https://example.com/green-acres"
+  ) =>
+    program.methods
+      .registerFarmer(displayName, publicProfileUri)
+      .accounts({
+        config: configPDA,
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        authority: farmer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([farmer])
+      .rpc();
+
+  const closeFarmerProfile = (farmer: Keypair, signer: Keypair = farmer) =>
+    program.methods
+      .closeFarmerProfile()
+      .accounts({
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should close the profile and refund the rent", async () => {
+      const farmer = await newFunded(provider);
+      await registerFarmer(farmer);
+      const [farmerPDA] = getFarmerPDA(farmer.publicKey);
+
+      const rent = (await provider.connection.getAccountInfo(farmerPDA))
+        .lamports;
+      const balanceBefore = await provider.connection.getBalance(
+        farmer.publicKey
+      );
+
+      await closeFarmerProfile(farmer);
+
+      expect(await provider.connection.getAccountInfo(farmerPDA)).to.be.null;
+      const balanceAfter = await provider.connection.getBalance(
+        farmer.publicKey
+      );
+      // Rent comes back, minus the transaction fee paid by the farmer
+      expect(balanceAfter).to.be.greaterThan(balanceBefore + rent - 10_000);
+    });
+
+    it("should allow registering again with a fresh offer counter", async () => {
+      const farmer = await newFunded(provider);
+      await registerFarmer(farmer);
+      await closeFarmerProfile(farmer);
+
+      await registerFarmer(farmer, "Green Acres Again");
+
+      const profile = await program.account.farmerProfile.fetch(
+        getFarmerPDA(farmer.publicKey)[0]
+      );
+      expect(profile.displayName).to.equal("Green Acres Again");
+      expect(profile.offerCounter.toNumber()).to.equal(0);
+    });
+
+    it("should emit FarmerProfileClosed", async () => {
+      const farmer = await newFunded(provider);
+      await registerFarmer(farmer);
+
+      let event: any = null;
+      const listener = program.addEventListener(
+        "farmerProfileClosed",
+        (e) => {
+          event = e;
+        }
+      );
+
+      await closeFarmerProfile(farmer);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.farmer.toString()).to.equal(
+        getFarmerPDA(farmer.publicKey)[0].toString()
+      );
+      expect(event.authority.toString()).to.equal(farmer.publicKey.toString());
+    });
+  });
+
+  describe("error cases", () => {
+    it("should fail for a farmer that is not registered", async () => {
+      try {
+        await closeFarmerProfile(await newFunded(provider));
+        expect.fail("Should have thrown an error for missing profile");
+      } catch (err) {
+        expect(err.toString()).to.include("AccountNotInitialized");
+      }
+    });
+
+    it("should reject another key's signature", async () => {
+      const farmer = await newFunded(provider);
+      await registerFarmer(farmer);
+      const attacker = await newFunded(provider);
+
+      try {
+        await closeFarmerProfile(farmer, attacker);
+        expect.fail("Should have thrown an error for another signer");
+      } catch (err) {
+        expect(err.toString()).to.include("ConstraintSeeds");
+      }
+    });
+  });
+});
@@ -0,0 +1,417 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { createMint } from "./helpers/token";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+const CLOSED = { closed: {} };
+
+describe("close_offer", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const {
+    getConfigPDA,
+    getWarehousePDA,
+    getFarmerPDA,
+    getAffiliationPDA,
+    getAllowedMintPDA,
+    getOfferPDA,
+  } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  // Registered farmer with a funded key
+  const newFarmer = async () => {
+    const farmer = await newFunded(provider);
+    await program.methods
+      .registerFarmer("Green Acres", "")
+      .accounts({
+        config: configPDA,
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        authority: farmer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([farmer])
+      .rpc();
+    return farmer;
+  };
+
+  const createWarehouse = async () => {
+    const warehouseId = new anchor.BN(
+      Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)
+    );
+    const operator = await newFunded(provider);
+    const [warehouse] = getWarehousePDA(warehouseId);
+
+    await program.methods
+      .createWarehouse(warehouseId, operator.publicKey, "Hub", "", 500, [], null)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return { warehouse, operator };
+  };
+
+  const requestAffiliation = (
+    farmer: Keypair,
+    warehouse: PublicKey,
+    signer: Keypair = farmer
+  ) =>
+    program.methods
+      .requestWarehouseAffiliation()
+      .accounts({
+        config: configPDA,
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        warehouse,
+        affiliation: getAffiliationPDA(farmer.publicKey, warehouse)[0],
+        authority: signer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc();
+
+  const approveAffiliation = (
+    farmer: PublicKey,
+    warehouse: PublicKey,
+    operator: Keypair
+  ) =>
+    program.methods
+      .approveAffiliation()
+      .accounts({
+        config: configPDA,
+        affiliation: getAffiliationPDA(farmer, warehouse)[0],
+        warehouse,
+        operator: operator.publicKey,
+      })
+      .signers([operator])
+      .rpc();
+
+  // Allowed payment mint, created once for the suite
+  let mint: PublicKey;
+
+  // Registered farmer with an approved affiliation to a new warehouse
+  const newAffiliatedFarmer = async () => {
+    const farmer = await newFarmer();
+    const { warehouse, operator } = await createWarehouse();
+    await requestAffiliation(farmer, warehouse);
+    await approveAffiliation(farmer.publicKey, warehouse, operator);
+    return { farmer, warehouse, operator };
+  };
+
+  const publishOffer = async (
+    farmer: Keypair,
+    warehouse: PublicKey,
+    {
+      cropName = "Tomato",
+      cultivarName = null as string | null,
+      qty = new anchor.BN(10_000),
+      priceMinor = new anchor.BN(5),
+      expiresAt = null as anchor.BN | null,
+      notesPublic = null as string | null,
+      allowedMint = mint,
+      signer = farmer,
+    } = {}
+  ) => {
+    const [farmerProfile] = getFarmerPDA(farmer.publicKey);
+    const { offerCounter } = await program.account.farmerProfile.fetch(
+      farmerProfile
+    );
+    const [offer] = getOfferPDA(farmer.publicKey, offerCounter);
+
+    await program.methods
+      .publishOffer(
+        cropName,
+        cultivarName,
+        2,
+        qty,
+        priceMinor,
+        expiresAt,
+        notesPublic
+      )
+      .accounts({
+        config: configPDA,
+        farmerProfile,
+        affiliation: getAffiliationPDA(farmer.publicKey, warehouse)[0],
+        warehouse,
+        allowedMint: getAllowedMintPDA(allowedMint)[0],
+        offer,
+        authority: signer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc();
+
+    return { offer, offerId: offerCounter };
+  };
+
+  const closeOffer = (
+    farmer: Keypair,
+    offerId: anchor.BN,
+    signer: Keypair = farmer,
+    rentPayer: PublicKey = farmer.publicKey
+  ) =>
+    program.methods
+      .closeOffer()
+      .accounts({
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        offer: getOfferPDA(farmer.publicKey, offerId)[0],
+        rentPayer,
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const deactivateOffer = (
+    farmer: Keypair,
+    warehouse: PublicKey,
+    offerId: anchor.BN,
+    signer: Keypair = farmer
+  ) =>
+    program.methods
+      .deactivateOffer()
+      .accounts({
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        affiliation: getAffiliationPDA(farmer.publicKey, warehouse)[0],
+        offer: getOfferPDA(farmer.publicKey, offerId)[0],
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const setDelegate = (farmer: Keypair, delegate: PublicKey) =>
+    program.methods
+      .updateFarmerProfile(null, null, null, delegate)
+      .accounts({
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        authority: farmer.publicKey,
+      })
+      .signers([farmer])
+      .rpc();
+
+  const setStatus = (warehouse: PublicKey, status: object) =>
+    program.methods
+      .setWarehouseStatus(status as any)
+      .accounts({ config: configPDA, warehouse, admin: admin.publicKey })
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+
+    mint = await createMint(provider, 6);
+    await program.methods
+      .addAllowedMint(new anchor.BN(0), new anchor.BN(1_000_000_000), null)
+      .accounts({
+        config: configPDA,
+        allowedMint: getAllowedMintPDA(mint)[0],
+        mint,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  describe("success cases", () => {
+    it("should close a deactivated offer and refund the rent", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const { offer, offerId } = await publishOffer(farmer, warehouse);
+      await deactivateOffer(farmer, warehouse, offerId);
+
+      const rent = (await provider.connection.getAccountInfo(offer)).lamports;
+      const balanceBefore = await provider.connection.getBalance(
+        farmer.publicKey
+      );
+
+      await closeOffer(farmer, offerId);
+
+      expect(await provider.connection.getAccountInfo(offer)).to.be.null;
+      const balanceAfter = await provider.connection.getBalance(
+        farmer.publicKey
+      );
+      // Rent comes back, minus the transaction fee paid by the farmer
+      expect(balanceAfter).to.be.greaterThan(balanceBefore + rent - 10_000);
+      const profile = await program.account.farmerProfile.fetch(
+        getFarmerPDA(farmer.publicKey)[0]
+      );
+      expect(profile.offerAccounts).to.equal(0);
+      expect(profile.offerCounter.toNumber()).to.equal(1);
+    });
+
+    it("should refund the delegate that published the offer", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const delegate = await newFunded(provider);
+      await setDelegate(farmer, delegate.publicKey);
+      const { offer, offerId } = await publishOffer(farmer, warehouse, {
+        signer: delegate,
+      });
+      await deactivateOffer(farmer, warehouse, offerId);
+
+      const rent = (await provider.connection.getAccountInfo(offer)).lamports;
+      const balanceBefore = await provider.connection.getBalance(
+        delegate.publicKey
+      );
+
+      await closeOffer(farmer, offerId, farmer, delegate.publicKey);
+
+      expect(await provider.connection.getAccountInfo(offer)).to.be.null;
+      expect(await provider.connection.getBalance(delegate.publicKey)).to.equal(
+        balanceBefore + rent
+      );
+    });
+
+    it("should still work once the warehouse is closed", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const { offer, offerId } = await publishOffer(farmer, warehouse);
+      await deactivateOffer(farmer, warehouse, offerId);
+      await setStatus(warehouse, CLOSED);
+
+      await closeOffer(farmer, offerId);
+
+      expect(await provider.connection.getAccountInfo(offer)).to.be.null;
+    });
+
+    it("should unblock closing the profile", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const { offerId } = await publishOffer(farmer, warehouse);
+      const [farmerProfile] = getFarmerPDA(farmer.publicKey);
+      await deactivateOffer(farmer, warehouse, offerId);
+
+      await closeOffer(farmer, offerId);
+      await program.methods
+        .closeFarmerProfile()
+        .accounts({ farmerProfile, authority: farmer.publicKey })
+        .signers([farmer])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(farmerProfile)).to.be
+        .null;
+    });
+
+    it("should let a re-registered farmer publish from offer id 0", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const { offerId } = await publishOffer(farmer, warehouse);
+      const [farmerProfile] = getFarmerPDA(farmer.publicKey);
+      await deactivateOffer(farmer, warehouse, offerId);
+      await closeOffer(farmer, offerId);
+      await program.methods
+        .closeFarmerProfile()
+        .accounts({ farmerProfile, authority: farmer.publicKey })
+        .signers([farmer])
+        .rpc();
+
+      // The affiliation outlives the profile, so the farmer can publish again
+      await program.methods
+        .registerFarmer("Green Acres Again", "")
+        .accounts({
+          config: configPDA,
+          farmerProfile,
+          authority: farmer.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([farmer])
+        .rpc();
+      const { offer, offerId: newOfferId } = await publishOffer(
+        farmer,
+        warehouse
+      );
+
+      expect(newOfferId.toNumber()).to.equal(0);
+      expect((await program.account.lotOffer.fetch(offer)).active).to.be.true;
+      const profile = await program.account.farmerProfile.fetch(farmerProfile);
+      expect(profile.offerCounter.toNumber()).to.equal(1);
+      expect(profile.offerAccounts).to.equal(1);
+    });
+
+    it("should emit OfferClosed", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const { offer, offerId } = await publishOffer(farmer, warehouse);
+      await deactivateOffer(farmer, warehouse, offerId);
+
+      let event: any = null;
+      const listener = program.addEventListener("offerClosed", (e) => {
+        event = e;
+      });
+
+      await closeOffer(farmer, offerId);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.offer.toString()).to.equal(offer.toString());
+      expect(event.farmer.toString()).to.equal(farmer.publicKey.toString());
+      expect(event.warehouse.toString()).to.equal(warehouse.toString());
+    });
+  });
+
+  describe("error cases", () => {
+    it("should fail for an active offer", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const { offerId } = await publishOffer(farmer, warehouse);
+
+      try {
+        await closeOffer(farmer, offerId);
+        expect.fail("Should have thrown an error for an active offer");
+      } catch (err) {
+        expect(err.toString()).to.include("OfferStillActive");
+      }
+    });
+
+    it("should not refund anyone but the rent payer", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const delegate = await newFunded(provider);
+      await setDelegate(farmer, delegate.publicKey);
+      const { offerId } = await publishOffer(farmer, warehouse, {
+        signer: delegate,
+      });
+      await deactivateOffer(farmer, warehouse, offerId);
+
+      try {
+        await closeOffer(farmer, offerId);
+        expect.fail("Should have thrown an error for the wrong rent payer");
+      } catch (err) {
+        expect(err.toString()).to.include("RentPayerMismatch");
+      }
+    });
+
+    it("should not let the delegate close offers", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const { offerId } = await publishOffer(farmer, warehouse);
+      await deactivateOffer(farmer, warehouse, offerId);
+      const delegate = await newFunded(provider);
+      await setDelegate(farmer, delegate.publicKey);
+
+      try {
+        await closeOffer(farmer, offerId, delegate);
+        expect.fail("Should have thrown an error for the delegate");
+      } catch (err) {
+        expect(err.toString()).to.include("ConstraintSeeds");
+      }
+    });
+  });
+});
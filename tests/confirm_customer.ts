@@ -0,0 +1,612 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+// Mirrors the ROLE_* constants in states.rs
+const ROLES = {
+  confirmCustomers: 1 << 0,
+  quote: 1 << 1,
+  dispatch: 1 << 2,
+  complete: 1 << 3,
+  refund: 1 << 4,
+  manage: 1 << 5,
+};
+
+const PAUSE_ONBOARDING = 1 << 3;
+const SUSPENDED = { suspended: {} };
+const CLOSED = { closed: {} };
+
+describe("confirm_customer", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const {
+    getConfigPDA,
+    getWarehousePDA,
+    getStaffPDA,
+    getCustomerPDA,
+    getWarehouseCustomerPDA,
+  } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  const registerCustomer = (customer: Keypair, publicProfileUri = "") =>
+    program.methods
+      .registerCustomer(publicProfileUri)
+      .accounts({
+        config: configPDA,
+        customerProfile: getCustomerPDA(customer.publicKey)[0],
+        authority: customer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customer])
+      .rpc();
+
+  // Registered customer with a funded key
+  const newCustomer = async () => {
+    const customer = await newFunded(provider);
+    await registerCustomer(customer);
+    return customer;
+  };
+
+  const createWarehouse = async () => {
+    const warehouseId = new anchor.BN(
+      Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)
+    );
+    const operator = await newFunded(provider);
+    const [warehouse] = getWarehousePDA(warehouseId);
+
+    await program.methods
+      .createWarehouse(warehouseId, operator.publicKey, "Hub", "", 500, [], null)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return { warehouse, operator };
+  };
+
+  const requestConfirmation = (
+    customer: Keypair,
+    warehouse: PublicKey,
+    addressEnvelope: object | null = null
+  ) =>
+    program.methods
+      .requestCustomerConfirmation(addressEnvelope as any)
+      .accounts({
+        config: configPDA,
+        customerProfile: getCustomerPDA(customer.publicKey)[0],
+        warehouse,
+        warehouseCustomer: getWarehouseCustomerPDA(
+          warehouse,
+          customer.publicKey
+        )[0],
+        authority: customer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customer])
+      .rpc();
+
+  const confirmCustomer = (
+    warehouse: PublicKey,
+    customer: PublicKey,
+    signer: Keypair,
+    notesHash: number[] | null = null,
+    asStaff = false,
+    validUntil: number | null = null,
+    envelopeHash: number[] | null = null
+  ) =>
+    program.methods
+      .confirmCustomer(
+        notesHash,
+        validUntil !== null ? new anchor.BN(validUntil) : null,
+        envelopeHash
+      )
+      .accounts({
+        config: configPDA,
+        warehouse,
+        staff: asStaff ? getStaffPDA(warehouse, signer.publicKey)[0] : null,
+        warehouseCustomer: getWarehouseCustomerPDA(warehouse, customer)[0],
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const revokeCustomer = (
+    warehouse: PublicKey,
+    customer: PublicKey,
+    signer: Keypair,
+    asStaff = false
+  ) =>
+    program.methods
+      .revokeCustomer()
+      .accounts({
+        warehouse,
+        staff: asStaff ? getStaffPDA(warehouse, signer.publicKey)[0] : null,
+        warehouseCustomer: getWarehouseCustomerPDA(warehouse, customer)[0],
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const grant = (
+    warehouse: PublicKey,
+    operator: Keypair,
+    member: PublicKey,
+    roles: number
+  ) =>
+    program.methods
+      .grantStaffRoles(member, roles)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        staff: getStaffPDA(warehouse, member)[0],
+        authority: operator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([operator])
+      .rpc();
+
+  const setPauseFlags = (pauseFlags: number) =>
+    program.methods
+      .setPauseFlags(pauseFlags)
+      .accounts({ config: configPDA, admin: admin.publicKey })
+      .rpc();
+
+  const setStatus = (warehouse: PublicKey, status: object) =>
+    program.methods
+      .setWarehouseStatus(status as any)
+      .accounts({ config: configPDA, warehouse, admin: admin.publicKey })
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  // Sets the warehouse's default confirmation lifetime, in seconds
+  const setValidity = (
+    warehouse: PublicKey,
+    operator: Keypair,
+    seconds: number
+  ) =>
+    program.methods
+      .updateWarehouse(
+        null,
+        null,
+        null,
+        null,
+        null,
+        null,
+        null,
+        new anchor.BN(seconds),
+        null
+      )
+      .accounts({
+        config: configPDA,
+        warehouse,
+        staff: null,
+        authority: operator.publicKey,
+      })
+      .signers([operator])
+      .rpc();
+
+  const setEncryptionKey = (
+    warehouse: PublicKey,
+    operator: Keypair,
+    key: number[]
+  ) =>
+    program.methods
+      .updateWarehouse(null, null, null, null, null, null, null, null, key)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        staff: null,
+        authority: operator.publicKey,
+      })
+      .signers([operator])
+      .rpc();
+
+  const chainTime = async () =>
+    provider.connection.getBlockTime(await provider.connection.getSlot());
+
+  // Customer with a pending request at a fresh warehouse
+  const pendingCustomer = async () => {
+    const customer = await newCustomer();
+    const { warehouse, operator } = await createWarehouse();
+    await requestConfirmation(customer, warehouse);
+    return { customer, warehouse, operator };
+  };
+
+  const ENVELOPE_HASH = [...Buffer.alloc(32, 9)];
+
+  // Pending request carrying an address envelope with `ENVELOPE_HASH`
+  const envelopeCustomer = async () => {
+    const customer = await newCustomer();
+    const { warehouse, operator } = await createWarehouse();
+    const encryptionKey = [...Buffer.alloc(32, 5)];
+    await setEncryptionKey(warehouse, operator, encryptionKey);
+    await requestConfirmation(customer, warehouse, {
+      uri: "ipfs://bafyaddress",
+      ciphertextHash: ENVELOPE_HASH,
+      encryptionKey,
+    });
+    return { customer, warehouse, operator };
+  };
+
+  describe("success cases", () => {
+    it("should confirm a pending customer with a notes hash", async () => {
+      const { customer, warehouse, operator } = await pendingCustomer();
+      const notesHash = [...Buffer.alloc(32, 0xab)];
+
+      await confirmCustomer(warehouse, customer.publicKey, operator, notesHash);
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.status).to.deep.equal({ confirmed: {} });
+      expect(entry.confirmedAt.toNumber()).to.be.greaterThan(0);
+      expect(entry.notesHash).to.deep.equal(notesHash);
+    });
+
+    it("should confirm without a notes hash", async () => {
+      const { customer, warehouse, operator } = await pendingCustomer();
+
+      await confirmCustomer(warehouse, customer.publicKey, operator);
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.status).to.deep.equal({ confirmed: {} });
+      expect(entry.notesHash).to.be.null;
+    });
+
+    it("should not expire without a warehouse default", async () => {
+      const { customer, warehouse, operator } = await pendingCustomer();
+
+      await confirmCustomer(warehouse, customer.publicKey, operator);
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.validUntil).to.be.null;
+    });
+
+    it("should apply the warehouse's default validity", async () => {
+      const { customer, warehouse, operator } = await pendingCustomer();
+      const year = 365 * 86_400;
+      await setValidity(warehouse, operator, year);
+
+      await confirmCustomer(warehouse, customer.publicKey, operator);
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.validUntil.toNumber()).to.equal(
+        entry.confirmedAt.toNumber() + year
+      );
+    });
+
+    it("should let an explicit expiry override the default", async () => {
+      const { customer, warehouse, operator } = await pendingCustomer();
+      await setValidity(warehouse, operator, 365 * 86_400);
+      const validUntil = (await chainTime()) + 30 * 86_400;
+
+      await confirmCustomer(
+        warehouse,
+        customer.publicKey,
+        operator,
+        null,
+        false,
+        validUntil
+      );
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.validUntil.toNumber()).to.equal(validUntil);
+    });
+
+    it("should allow a suspended warehouse", async () => {
+      const { customer, warehouse, operator } = await pendingCustomer();
+      await setStatus(warehouse, SUSPENDED);
+
+      await confirmCustomer(warehouse, customer.publicKey, operator);
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.status).to.deep.equal({ confirmed: {} });
+    });
+
+    it("should emit CustomerConfirmed", async () => {
+      const { customer, warehouse, operator } = await pendingCustomer();
+      const notesHash = [...Buffer.alloc(32, 1)];
+
+      let event: any = null;
+      const listener = program.addEventListener("customerConfirmed", (e) => {
+        event = e;
+      });
+
+      await confirmCustomer(warehouse, customer.publicKey, operator, notesHash);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.warehouseCustomer.toString()).to.equal(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0].toString()
+      );
+      expect(event.warehouse.toString()).to.equal(warehouse.toString());
+      expect(event.customer.toString()).to.equal(customer.publicKey.toString());
+      expect(event.authority.toString()).to.equal(
+        operator.publicKey.toString()
+      );
+      expect(event.notesHash).to.deep.equal(notesHash);
+      expect(event.validUntil).to.be.null;
+      expect(event.envelopeHash).to.be.null;
+    });
+
+    it("should confirm committing to the envelope hash", async () => {
+      const { customer, warehouse, operator } = await envelopeCustomer();
+
+      let event: any = null;
+      const listener = program.addEventListener("customerConfirmed", (e) => {
+        event = e;
+      });
+
+      await confirmCustomer(
+        warehouse,
+        customer.publicKey,
+        operator,
+        null,
+        false,
+        null,
+        ENVELOPE_HASH
+      );
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.status).to.deep.equal({ confirmed: {} });
+      expect(entry.addressEnvelope.ciphertextHash).to.deep.equal(
+        ENVELOPE_HASH
+      );
+
+      expect(event).to.not.be.null;
+      expect(event.envelopeHash).to.deep.equal(ENVELOPE_HASH);
+    });
+  });
+
+  describe("staff privileges", () => {
+    it("should let staff with the confirm-customers role confirm", async () => {
+      const { customer, warehouse, operator } = await pendingCustomer();
+      const clerk = await newFunded(provider);
+      await grant(warehouse, operator, clerk.publicKey, ROLES.confirmCustomers);
+
+      await confirmCustomer(warehouse, customer.publicKey, clerk, null, true);
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.status).to.deep.equal({ confirmed: {} });
+    });
+
+    for (const [name, role] of Object.entries(ROLES)) {
+      if (name === "confirmCustomers") continue;
+
+      it(`should reject staff holding only the ${name} role`, async () => {
+        const { customer, warehouse, operator } = await pendingCustomer();
+        const member = await newFunded(provider);
+        await grant(warehouse, operator, member.publicKey, role);
+
+        try {
+          await confirmCustomer(
+            warehouse,
+            customer.publicKey,
+            member,
+            null,
+            true
+          );
+          expect.fail(`Should have thrown an error for the ${name} role`);
+        } catch (err) {
+          expect(err.toString()).to.include("MissingStaffRole");
+        }
+      });
+    }
+
+    it("should reject a grant from another warehouse", async () => {
+      const { customer, warehouse } = await pendingCustomer();
+      const other = await createWarehouse();
+      const clerk = await newFunded(provider);
+      await grant(
+        other.warehouse,
+        other.operator,
+        clerk.publicKey,
+        ROLES.confirmCustomers
+      );
+
+      try {
+        await program.methods
+          .confirmCustomer(null, null, null)
+          .accounts({
+            config: configPDA,
+            warehouse,
+            staff: getStaffPDA(other.warehouse, clerk.publicKey)[0],
+            warehouseCustomer: getWarehouseCustomerPDA(
+              warehouse,
+              customer.publicKey
+            )[0],
+            authority: clerk.publicKey,
+          })
+          .signers([clerk])
+          .rpc();
+        expect.fail("Should have thrown an error for a foreign grant");
+      } catch (err) {
+        expect(err.toString()).to.include("ConstraintSeeds");
+      }
+    });
+  });
+
+  describe("error cases", () => {
+    it("should fail when signer is neither operator nor staff", async () => {
+      const { customer, warehouse } = await pendingCustomer();
+      const stranger = await newFunded(provider);
+
+      try {
+        await confirmCustomer(warehouse, customer.publicKey, stranger);
+        expect.fail("Should have thrown an error for a stranger");
+      } catch (err) {
+        expect(err.toString()).to.include("MissingStaffRole");
+      }
+    });
+
+    it("should fail for a customer who did not request", async () => {
+      const customer = await newCustomer();
+      const { warehouse, operator } = await createWarehouse();
+
+      try {
+        await confirmCustomer(warehouse, customer.publicKey, operator);
+        expect.fail("Should have thrown an error without a request");
+      } catch (err) {
+        expect(err.toString()).to.include("AccountNotInitialized");
+      }
+    });
+
+    it("should fail without the envelope hash of the request", async () => {
+      const { customer, warehouse, operator } = await envelopeCustomer();
+
+      for (const envelopeHash of [null, [...Buffer.alloc(32, 8)]]) {
+        try {
+          await confirmCustomer(
+            warehouse,
+            customer.publicKey,
+            operator,
+            null,
+            false,
+            null,
+            envelopeHash
+          );
+          expect.fail("Should have thrown an error for the envelope hash");
+        } catch (err) {
+          expect(err.toString()).to.include("EnvelopeHashMismatch");
+        }
+      }
+    });
+
+    it("should fail with an envelope hash when none was sent", async () => {
+      const { customer, warehouse, operator } = await pendingCustomer();
+
+      try {
+        await confirmCustomer(
+          warehouse,
+          customer.publicKey,
+          operator,
+          null,
+          false,
+          null,
+          ENVELOPE_HASH
+        );
+        expect.fail("Should have thrown an error without an envelope");
+      } catch (err) {
+        expect(err.toString()).to.include("EnvelopeHashMismatch");
+      }
+    });
+
+    it("should fail for a customer already confirmed", async () => {
+      const { customer, warehouse, operator } = await pendingCustomer();
+      await confirmCustomer(warehouse, customer.publicKey, operator);
+
+      try {
+        await confirmCustomer(warehouse, customer.publicKey, operator);
+        expect.fail("Should have thrown an error for a confirmed customer");
+      } catch (err) {
+        expect(err.toString()).to.include("ConfirmationNotPending");
+      }
+    });
+
+    it("should fail for a revoked customer", async () => {
+      const { customer, warehouse, operator } = await pendingCustomer();
+      await revokeCustomer(warehouse, customer.publicKey, operator);
+
+      try {
+        await confirmCustomer(warehouse, customer.publicKey, operator);
+        expect.fail("Should have thrown an error for a revoked customer");
+      } catch (err) {
+        expect(err.toString()).to.include("ConfirmationNotPending");
+      }
+    });
+
+    it("should reject an expiry that is not in the future", async () => {
+      const { customer, warehouse, operator } = await pendingCustomer();
+      const past = (await chainTime()) - 60;
+
+      try {
+        await confirmCustomer(
+          warehouse,
+          customer.publicKey,
+          operator,
+          null,
+          false,
+          past
+        );
+        expect.fail("Should have thrown an error for a past expiry");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidConfirmationExpiry");
+      }
+    });
+
+    it("should fail once the warehouse is closed", async () => {
+      const { customer, warehouse, operator } = await pendingCustomer();
+      await setStatus(warehouse, CLOSED);
+
+      try {
+        await confirmCustomer(warehouse, customer.publicKey, operator);
+        expect.fail("Should have thrown an error for a closed warehouse");
+      } catch (err) {
+        expect(err.toString()).to.include("WarehouseClosed");
+      }
+    });
+
+    it("should fail while onboarding is paused", async () => {
+      const { customer, warehouse, operator } = await pendingCustomer();
+      await setPauseFlags(PAUSE_ONBOARDING);
+
+      try {
+        await confirmCustomer(warehouse, customer.publicKey, operator);
+        expect.fail("Should have thrown an error while paused");
+      } catch (err) {
+        expect(err.toString()).to.include("OnboardingPaused");
+      } finally {
+        await setPauseFlags(0);
+      }
+    });
+  });
+});
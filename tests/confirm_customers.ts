@@ -0,0 +1,508 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { ComputeBudgetProgram, Keypair, PublicKey } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+// Mirrors the ROLE_* constants in states.rs
+const ROLES = {
+  confirmCustomers: 1 << 0,
+  quote: 1 << 1,
+  dispatch: 1 << 2,
+  complete: 1 << 3,
+  refund: 1 << 4,
+  manage: 1 << 5,
+};
+
+const PAUSE_ONBOARDING = 1 << 3;
+
+describe("confirm_customers", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const {
+    getConfigPDA,
+    getWarehousePDA,
+    getStaffPDA,
+    getCustomerPDA,
+    getWarehouseCustomerPDA,
+  } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  const registerCustomer = (customer: Keypair, publicProfileUri = "") =>
+    program.methods
+      .registerCustomer(publicProfileUri)
+      .accounts({
+        config: configPDA,
+        customerProfile: getCustomerPDA(customer.publicKey)[0],
+        authority: customer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customer])
+      .rpc();
+
+  // Registered customer with a funded key
+  const newCustomer = async () => {
+    const customer = await newFunded(provider);
+    await registerCustomer(customer);
+    return customer;
+  };
+
+  const createWarehouse = async () => {
+    const warehouseId = new anchor.BN(
+      Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)
+    );
+    const operator = await newFunded(provider);
+    const [warehouse] = getWarehousePDA(warehouseId);
+
+    await program.methods
+      .createWarehouse(warehouseId, operator.publicKey, "Hub", "", 500, [], null)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return { warehouse, operator };
+  };
+
+  const requestConfirmation = (
+    customer: Keypair,
+    warehouse: PublicKey,
+    addressEnvelope: object | null = null
+  ) =>
+    program.methods
+      .requestCustomerConfirmation(addressEnvelope as any)
+      .accounts({
+        config: configPDA,
+        customerProfile: getCustomerPDA(customer.publicKey)[0],
+        warehouse,
+        warehouseCustomer: getWarehouseCustomerPDA(
+          warehouse,
+          customer.publicKey
+        )[0],
+        authority: customer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customer])
+      .rpc();
+
+  // Mirrors MAX_CONFIRM_BATCH in states.rs
+  const MAX_CONFIRM_BATCH = 25;
+
+  type Entry = {
+    account: PublicKey;
+    notesHash?: number[] | null;
+    envelopeHash?: number[] | null;
+  };
+
+  const confirmCustomers = (
+    warehouse: PublicKey,
+    entries: Entry[],
+    signer: Keypair,
+    asStaff = false,
+    validUntil: number | null = null,
+    confirmations = entries.map((e) => ({
+      notesHash: e.notesHash ?? null,
+      envelopeHash: e.envelopeHash ?? null,
+    }))
+  ) =>
+    program.methods
+      .confirmCustomers(
+        validUntil !== null ? new anchor.BN(validUntil) : null,
+        confirmations
+      )
+      .accounts({
+        config: configPDA,
+        warehouse,
+        staff: asStaff ? getStaffPDA(warehouse, signer.publicKey)[0] : null,
+        authority: signer.publicKey,
+      })
+      .remainingAccounts(
+        entries.map((e) => ({
+          pubkey: e.account,
+          isWritable: true,
+          isSigner: false,
+        }))
+      )
+      .preInstructions([
+        ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+      ])
+      .signers([signer])
+      .rpc();
+
+  const grant = (
+    warehouse: PublicKey,
+    operator: Keypair,
+    member: PublicKey,
+    roles: number
+  ) =>
+    program.methods
+      .grantStaffRoles(member, roles)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        staff: getStaffPDA(warehouse, member)[0],
+        authority: operator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([operator])
+      .rpc();
+
+  const setPauseFlags = (pauseFlags: number) =>
+    program.methods
+      .setPauseFlags(pauseFlags)
+      .accounts({ config: configPDA, admin: admin.publicKey })
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  const setEncryptionKey = (
+    warehouse: PublicKey,
+    operator: Keypair,
+    key: number[]
+  ) =>
+    program.methods
+      .updateWarehouse(null, null, null, null, null, null, null, null, key)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        staff: null,
+        authority: operator.publicKey,
+      })
+      .signers([operator])
+      .rpc();
+
+  const chainTime = async () =>
+    provider.connection.getBlockTime(await provider.connection.getSlot());
+
+  // `count` customers with pending requests at `warehouse`
+  const pendingEntries = async (
+    warehouse: PublicKey,
+    count: number
+  ): Promise<Entry[]> =>
+    Promise.all(
+      [...Array(count)].map(async () => {
+        const customer = await newCustomer();
+        await requestConfirmation(customer, warehouse);
+        return {
+          account: getWarehouseCustomerPDA(warehouse, customer.publicKey)[0],
+        };
+      })
+    );
+
+  const ENVELOPE_HASH = [...Buffer.alloc(32, 9)];
+
+  // Pending request at `warehouse` carrying an envelope with `ENVELOPE_HASH`
+  const envelopeEntry = async (
+    warehouse: PublicKey,
+    encryptionKey: number[]
+  ): Promise<Entry> => {
+    const customer = await newCustomer();
+    await requestConfirmation(customer, warehouse, {
+      uri: "ipfs://bafyaddress",
+      ciphertextHash: ENVELOPE_HASH,
+      encryptionKey,
+    });
+    return {
+      account: getWarehouseCustomerPDA(warehouse, customer.publicKey)[0],
+    };
+  };
+
+  const fetchStatus = async (entry: Entry) =>
+    (await program.account.warehouseCustomer.fetch(entry.account)).status;
+
+  describe("success cases", () => {
+    it("should confirm a full batch as staff with an expiry", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const clerk = await newFunded(provider);
+      await grant(warehouse, operator, clerk.publicKey, ROLES.confirmCustomers);
+      const entries = await pendingEntries(warehouse, MAX_CONFIRM_BATCH);
+      const validUntil = (await chainTime()) + 30 * 86_400;
+
+      await confirmCustomers(warehouse, entries, clerk, true, validUntil);
+
+      for (const e of entries) {
+        const entry = await program.account.warehouseCustomer.fetch(e.account);
+        expect(entry.status).to.deep.equal({ confirmed: {} });
+        expect(entry.confirmedAt.toNumber()).to.be.greaterThan(0);
+        expect(entry.validUntil.toNumber()).to.equal(validUntil);
+      }
+    });
+
+    it("should store each entry's notes and envelope hash", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const encryptionKey = [...Buffer.alloc(32, 5)];
+      await setEncryptionKey(warehouse, operator, encryptionKey);
+      const [plain] = await pendingEntries(warehouse, 1);
+      const sealed = await envelopeEntry(warehouse, encryptionKey);
+      const notesHash = [...Buffer.alloc(32, 0xab)];
+
+      await confirmCustomers(
+        warehouse,
+        [
+          { ...plain, notesHash },
+          { ...sealed, envelopeHash: ENVELOPE_HASH },
+        ],
+        operator
+      );
+
+      const first = await program.account.warehouseCustomer.fetch(
+        plain.account
+      );
+      expect(first.status).to.deep.equal({ confirmed: {} });
+      expect(first.notesHash).to.deep.equal(notesHash);
+      const second = await program.account.warehouseCustomer.fetch(
+        sealed.account
+      );
+      expect(second.status).to.deep.equal({ confirmed: {} });
+      expect(second.notesHash).to.be.null;
+    });
+
+    it("should emit CustomerConfirmed for every customer", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const entries = await pendingEntries(warehouse, 3);
+
+      const events: any[] = [];
+      const listener = program.addEventListener("customerConfirmed", (e) => {
+        events.push(e);
+      });
+
+      await confirmCustomers(warehouse, entries, operator);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(events.map((e) => e.warehouseCustomer.toString())).to.deep.equal(
+        entries.map((e) => e.account.toString())
+      );
+      for (const event of events) {
+        expect(event.warehouse.toString()).to.equal(warehouse.toString());
+        expect(event.authority.toString()).to.equal(
+          operator.publicKey.toString()
+        );
+      }
+    });
+  });
+
+  describe("error cases", () => {
+    // The size is checked before any entry is loaded
+    const unloaded = (count: number): Entry[] =>
+      [...Array(count)].map(() => ({ account: Keypair.generate().publicKey }));
+
+    it("should reject more than MAX_CONFIRM_BATCH customers", async () => {
+      const { warehouse, operator } = await createWarehouse();
+
+      try {
+        await confirmCustomers(
+          warehouse,
+          unloaded(MAX_CONFIRM_BATCH + 1),
+          operator
+        );
+        expect.fail("Should have thrown an error for an oversized batch");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidBatchSize");
+      }
+    });
+
+    it("should reject an empty batch", async () => {
+      const { warehouse, operator } = await createWarehouse();
+
+      try {
+        await confirmCustomers(warehouse, [], operator);
+        expect.fail("Should have thrown an error for an empty batch");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidBatchSize");
+      }
+    });
+
+    it("should reject more confirmations than accounts", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const entries = await pendingEntries(warehouse, 1);
+      const confirmations = [0, 1].map(() => ({
+        notesHash: null,
+        envelopeHash: null,
+      }));
+
+      try {
+        await confirmCustomers(
+          warehouse,
+          entries,
+          operator,
+          false,
+          null,
+          confirmations
+        );
+        expect.fail("Should have thrown an error for a length mismatch");
+      } catch (err) {
+        expect(err.toString()).to.include("BatchLengthMismatch");
+      }
+    });
+
+    it("should reject a customer of another warehouse", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const other = await createWarehouse();
+      const [own] = await pendingEntries(warehouse, 1);
+      const [foreign] = await pendingEntries(other.warehouse, 1);
+
+      try {
+        await confirmCustomers(warehouse, [own, foreign], operator);
+        expect.fail("Should have thrown an error for a foreign customer");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidWarehouseCustomerAccount");
+      }
+      expect(await fetchStatus(own)).to.deep.equal({ pending: {} });
+      expect(await fetchStatus(foreign)).to.deep.equal({ pending: {} });
+    });
+
+    it("should reject an entry passed read-only", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const [entry] = await pendingEntries(warehouse, 1);
+
+      try {
+        await program.methods
+          .confirmCustomers(null, [{ notesHash: null, envelopeHash: null }])
+          .accounts({
+            config: configPDA,
+            warehouse,
+            staff: null,
+            authority: operator.publicKey,
+          })
+          .remainingAccounts([
+            { pubkey: entry.account, isWritable: false, isSigner: false },
+          ])
+          .signers([operator])
+          .rpc();
+        expect.fail("Should have thrown an error for a read-only entry");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidWarehouseCustomerAccount");
+      }
+    });
+
+    it("should reject an account that is not a warehouse customer", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const customer = await newCustomer();
+
+      try {
+        await confirmCustomers(
+          warehouse,
+          [{ account: getCustomerPDA(customer.publicKey)[0] }],
+          operator
+        );
+        expect.fail("Should have thrown an error for a customer profile");
+      } catch (err) {
+        expect(err.toString()).to.include("AccountDiscriminatorMismatch");
+      }
+    });
+
+    it("should revert the whole batch when one entry is not pending", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const [fresh, done] = await pendingEntries(warehouse, 2);
+      await confirmCustomers(warehouse, [done], operator);
+
+      try {
+        await confirmCustomers(warehouse, [fresh, done], operator);
+        expect.fail("Should have thrown an error for a confirmed customer");
+      } catch (err) {
+        expect(err.toString()).to.include("ConfirmationNotPending");
+      }
+      expect(await fetchStatus(fresh)).to.deep.equal({ pending: {} });
+    });
+
+    it("should reject a customer listed twice", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const [entry] = await pendingEntries(warehouse, 1);
+
+      try {
+        await confirmCustomers(warehouse, [entry, entry], operator);
+        expect.fail("Should have thrown an error for a duplicate");
+      } catch (err) {
+        expect(err.toString()).to.include("ConfirmationNotPending");
+      }
+      expect(await fetchStatus(entry)).to.deep.equal({ pending: {} });
+    });
+
+    it("should fail without the envelope hash of a request", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const encryptionKey = [...Buffer.alloc(32, 5)];
+      await setEncryptionKey(warehouse, operator, encryptionKey);
+      const [plain] = await pendingEntries(warehouse, 1);
+      const sealed = await envelopeEntry(warehouse, encryptionKey);
+
+      try {
+        await confirmCustomers(warehouse, [plain, sealed], operator);
+        expect.fail("Should have thrown an error for the envelope hash");
+      } catch (err) {
+        expect(err.toString()).to.include("EnvelopeHashMismatch");
+      }
+      expect(await fetchStatus(plain)).to.deep.equal({ pending: {} });
+    });
+
+    it("should reject staff without the confirm-customers role", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const member = await newFunded(provider);
+      await grant(warehouse, operator, member.publicKey, ROLES.dispatch);
+      const entries = await pendingEntries(warehouse, 1);
+
+      try {
+        await confirmCustomers(warehouse, entries, member, true);
+        expect.fail("Should have thrown an error for the dispatch role");
+      } catch (err) {
+        expect(err.toString()).to.include("MissingStaffRole");
+      }
+    });
+
+    it("should fail when signer is neither operator nor staff", async () => {
+      const { warehouse } = await createWarehouse();
+      const stranger = await newFunded(provider);
+      const entries = await pendingEntries(warehouse, 1);
+
+      try {
+        await confirmCustomers(warehouse, entries, stranger);
+        expect.fail("Should have thrown an error for a stranger");
+      } catch (err) {
+        expect(err.toString()).to.include("MissingStaffRole");
+      }
+    });
+
+    it("should fail while onboarding is paused", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const entries = await pendingEntries(warehouse, 1);
+      await setPauseFlags(PAUSE_ONBOARDING);
+
+      try {
+        await confirmCustomers(warehouse, entries, operator);
+        expect.fail("Should have thrown an error while paused");
+      } catch (err) {
+        expect(err.toString()).to.include("OnboardingPaused");
+      } finally {
+        await setPauseFlags(0);
+      }
+    });
+  });
+});
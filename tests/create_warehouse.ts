@@ -0,0 +1,292 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+// Mirrors the MAX_* constants in states.rs
+const MAX_NAME_LEN = 100;
+const MAX_NOTES_LEN = 500;
+const MAX_URI_LEN = 200;
+const MAX_ZIP_PREFIXES = 100;
+
+describe("create_warehouse", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const { getConfigPDA, getWarehousePDA } = pdaHelpers(program.programId);
+
+  // Fresh id per test so reruns against the same validator don't collide
+  const newWarehouseId = () =>
+    new anchor.BN(Math.floor(Math.random() * Number.MAX_SAFE_INTEGER));
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  const createWarehouse = (
+    warehouseId: anchor.BN,
+    {
+      operator = Keypair.generate().publicKey,
+      name = "North Hub",
+      pickupNotes = "Dock 3, weekdays 8-17",
+      feeBps = 500,
+      zipPrefixes = [{ prefix: 123, len: 3 }],
+      feeRulesUri = "https://example.com/fees.json" as string | null,
+      signer = null as Keypair | null,
+    } = {}
+  ) => {
+    const builder = program.methods
+      .createWarehouse(
+        warehouseId,
+        operator,
+        name,
+        pickupNotes,
+        feeBps,
+        zipPrefixes,
+        feeRulesUri
+      )
+      .accounts({
+        config: configPDA,
+        warehouse: getWarehousePDA(warehouseId)[0],
+        admin: signer ? signer.publicKey : admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      });
+    return signer ? builder.signers([signer]).rpc() : builder.rpc();
+  };
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should create a warehouse with every field set", async () => {
+      const warehouseId = newWarehouseId();
+      const operator = Keypair.generate().publicKey;
+
+      await createWarehouse(warehouseId, {
+        operator,
+        zipPrefixes: [
+          { prefix: 123, len: 3 },
+          { prefix: 4567, len: 4 },
+        ],
+      });
+
+      const [warehousePDA, bump] = getWarehousePDA(warehouseId);
+      const warehouse = await program.account.warehouse.fetch(warehousePDA);
+      expect(warehouse.warehouseId.toString()).to.equal(warehouseId.toString());
+      expect(warehouse.operator.toString()).to.equal(operator.toString());
+      expect(warehouse.feeReceiver.toString()).to.equal(operator.toString());
+      expect(warehouse.name).to.equal("North Hub");
+      expect(warehouse.pickupNotes).to.equal("Dock 3, weekdays 8-17");
+      expect(warehouse.feeBps).to.equal(500);
+      expect(warehouse.deliveryFeeRulesUri).to.equal(
+        "https://example.com/fees.json"
+      );
+      expect(warehouse.deliverZipPrefixes).to.deep.equal([
+        { prefix: 123, len: 3 },
+        { prefix: 4567, len: 4 },
+      ]);
+      expect(warehouse.bump).to.equal(bump);
+    });
+
+    it("should create a warehouse without fee rules or coverage", async () => {
+      const warehouseId = newWarehouseId();
+
+      await createWarehouse(warehouseId, {
+        feeRulesUri: null,
+        zipPrefixes: [],
+      });
+
+      const warehouse = await program.account.warehouse.fetch(
+        getWarehousePDA(warehouseId)[0]
+      );
+      expect(warehouse.deliveryFeeRulesUri).to.be.null;
+      expect(warehouse.deliverZipPrefixes).to.have.length(0);
+    });
+
+    it("should accept the maximum bounds", async () => {
+      const warehouseId = newWarehouseId();
+
+      await createWarehouse(warehouseId, {
+        name: "n".repeat(MAX_NAME_LEN),
+        feeBps: 10_000,
+        feeRulesUri: "u".repeat(MAX_URI_LEN),
+      });
+
+      const warehouse = await program.account.warehouse.fetch(
+        getWarehousePDA(warehouseId)[0]
+      );
+      expect(warehouse.name).to.have.length(MAX_NAME_LEN);
+      expect(warehouse.feeBps).to.equal(10_000);
+    });
+
+    it("should emit WarehouseCreated", async () => {
+      const warehouseId = newWarehouseId();
+      const operator = Keypair.generate().publicKey;
+
+      let event: any = null;
+      const listener = program.addEventListener("warehouseCreated", (e) => {
+        event = e;
+      });
+
+      await createWarehouse(warehouseId, { operator, feeBps: 250 });
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.warehouse.toString()).to.equal(
+        getWarehousePDA(warehouseId)[0].toString()
+      );
+      expect(event.warehouseId.toString()).to.equal(warehouseId.toString());
+      expect(event.operator.toString()).to.equal(operator.toString());
+      expect(event.feeBps).to.equal(250);
+    });
+  });
+
+  describe("error cases", () => {
+    it("should reject fee_bps above 10_000", async () => {
+      try {
+        await createWarehouse(newWarehouseId(), { feeBps: 10_001 });
+        expect.fail("Should have thrown an error for invalid fee");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidFeeBps");
+      }
+    });
+
+    it("should reject the default key as operator", async () => {
+      try {
+        await createWarehouse(newWarehouseId(), {
+          operator: PublicKey.default,
+        });
+        expect.fail("Should have thrown an error for an unset operator");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidOperator");
+      }
+    });
+
+    it("should reject a name over MAX_NAME_LEN", async () => {
+      try {
+        await createWarehouse(newWarehouseId(), {
+          name: "n".repeat(MAX_NAME_LEN + 1),
+        });
+        expect.fail("Should have thrown an error for long name");
+      } catch (err) {
+        expect(err.toString()).to.include("NameTooLong");
+      }
+    });
+
+    it("should reject pickup notes over MAX_NOTES_LEN", async () => {
+      try {
+        await createWarehouse(newWarehouseId(), {
+          pickupNotes: "p".repeat(MAX_NOTES_LEN + 1),
+          feeRulesUri: null,
+        });
+        expect.fail("Should have thrown an error for long notes");
+      } catch (err) {
+        expect(err.toString()).to.include("PickupNotesTooLong");
+      }
+    });
+
+    it("should reject a fee rules URI over MAX_URI_LEN", async () => {
+      try {
+        await createWarehouse(newWarehouseId(), {
+          feeRulesUri: "u".repeat(MAX_URI_LEN + 1),
+        });
+        expect.fail("Should have thrown an error for long URI");
+      } catch (err) {
+        expect(err.toString()).to.include("UriTooLong");
+      }
+    });
+
+    it("should reject more than MAX_ZIP_PREFIXES prefixes", async () => {
+      const zipPrefixes = Array.from({ length: MAX_ZIP_PREFIXES + 1 }, (_, i) => ({
+        prefix: 10_000 + i,
+        len: 5,
+      }));
+
+      try {
+        await createWarehouse(newWarehouseId(), {
+          zipPrefixes,
+          pickupNotes: "",
+          feeRulesUri: null,
+        });
+        expect.fail("Should have thrown an error for too many prefixes");
+      } catch (err) {
+        expect(err.toString()).to.include("TooManyZipPrefixes");
+      }
+    });
+
+    it("should reject ZIP prefixes outside 3-5 digits", async () => {
+      for (const zip of [
+        { prefix: 12, len: 2 },
+        { prefix: 123456, len: 6 },
+        { prefix: 1000, len: 3 },
+      ]) {
+        try {
+          await createWarehouse(newWarehouseId(), { zipPrefixes: [zip] });
+          expect.fail(`Should have thrown an error for ${JSON.stringify(zip)}`);
+        } catch (err) {
+          expect(err.toString()).to.include("InvalidZipPrefix");
+        }
+      }
+    });
+
+    it("should reject a duplicate ZIP prefix", async () => {
+      try {
+        await createWarehouse(newWarehouseId(), {
+          zipPrefixes: [
+            { prefix: 123, len: 3 },
+            { prefix: 4567, len: 4 },
+            { prefix: 123, len: 3 },
+          ],
+        });
+        expect.fail("Should have thrown an error for duplicate prefix");
+      } catch (err) {
+        expect(err.toString()).to.include("DuplicateZipPrefix");
+      }
+    });
+
+    it("should reject a duplicate warehouse id", async () => {
+      const warehouseId = newWarehouseId();
+      await createWarehouse(warehouseId);
+
+      try {
+        await createWarehouse(warehouseId);
+        expect.fail("Should have thrown an error for duplicate id");
+      } catch (err) {
+        expect(err.toString()).to.include("already in use");
+      }
+    });
+
+    it("should fail when signer is not the admin", async () => {
+      const attacker = await newFunded(provider);
+
+      try {
+        await createWarehouse(newWarehouseId(), { signer: attacker });
+        expect.fail("Should have thrown an error for unauthorized admin");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedAdmin");
+      }
+    });
+  });
+});
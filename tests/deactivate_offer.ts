@@ -0,0 +1,340 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { createMint } from "./helpers/token";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+const CLOSED = { closed: {} };
+
+describe("deactivate_offer", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const {
+    getConfigPDA,
+    getWarehousePDA,
+    getFarmerPDA,
+    getAffiliationPDA,
+    getAllowedMintPDA,
+    getOfferPDA,
+  } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  // Registered farmer with a funded key
+  const newFarmer = async () => {
+    const farmer = await newFunded(provider);
+    await program.methods
+      .registerFarmer("Green Acres", "")
+      .accounts({
+        config: configPDA,
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        authority: farmer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([farmer])
+      .rpc();
+    return farmer;
+  };
+
+  const createWarehouse = async () => {
+    const warehouseId = new anchor.BN(
+      Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)
+    );
+    const operator = await newFunded(provider);
+    const [warehouse] = getWarehousePDA(warehouseId);
+
+    await program.methods
+      .createWarehouse(warehouseId, operator.publicKey, "Hub", "", 500, [], null)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return { warehouse, operator };
+  };
+
+  const requestAffiliation = (
+    farmer: Keypair,
+    warehouse: PublicKey,
+    signer: Keypair = farmer
+  ) =>
+    program.methods
+      .requestWarehouseAffiliation()
+      .accounts({
+        config: configPDA,
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        warehouse,
+        affiliation: getAffiliationPDA(farmer.publicKey, warehouse)[0],
+        authority: signer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc();
+
+  const approveAffiliation = (
+    farmer: PublicKey,
+    warehouse: PublicKey,
+    operator: Keypair
+  ) =>
+    program.methods
+      .approveAffiliation()
+      .accounts({
+        config: configPDA,
+        affiliation: getAffiliationPDA(farmer, warehouse)[0],
+        warehouse,
+        operator: operator.publicKey,
+      })
+      .signers([operator])
+      .rpc();
+
+  // Allowed payment mint, created once for the suite
+  let mint: PublicKey;
+
+  // Registered farmer with an approved affiliation to a new warehouse
+  const newAffiliatedFarmer = async () => {
+    const farmer = await newFarmer();
+    const { warehouse, operator } = await createWarehouse();
+    await requestAffiliation(farmer, warehouse);
+    await approveAffiliation(farmer.publicKey, warehouse, operator);
+    return { farmer, warehouse, operator };
+  };
+
+  const publishOffer = async (
+    farmer: Keypair,
+    warehouse: PublicKey,
+    {
+      cropName = "Tomato",
+      cultivarName = null as string | null,
+      qty = new anchor.BN(10_000),
+      priceMinor = new anchor.BN(5),
+      expiresAt = null as anchor.BN | null,
+      notesPublic = null as string | null,
+      allowedMint = mint,
+    } = {}
+  ) => {
+    const [farmerProfile] = getFarmerPDA(farmer.publicKey);
+    const { offerCounter } = await program.account.farmerProfile.fetch(
+      farmerProfile
+    );
+    const [offer] = getOfferPDA(farmer.publicKey, offerCounter);
+
+    await program.methods
+      .publishOffer(
+        cropName,
+        cultivarName,
+        2,
+        qty,
+        priceMinor,
+        expiresAt,
+        notesPublic
+      )
+      .accounts({
+        config: configPDA,
+        farmerProfile,
+        affiliation: getAffiliationPDA(farmer.publicKey, warehouse)[0],
+        warehouse,
+        allowedMint: getAllowedMintPDA(allowedMint)[0],
+        offer,
+        authority: farmer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([farmer])
+      .rpc();
+
+    return { offer, offerId: offerCounter };
+  };
+
+  const deactivateOffer = (
+    farmer: Keypair,
+    warehouse: PublicKey,
+    offerId: anchor.BN,
+    signer: Keypair = farmer
+  ) =>
+    program.methods
+      .deactivateOffer()
+      .accounts({
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        affiliation: getAffiliationPDA(farmer.publicKey, warehouse)[0],
+        offer: getOfferPDA(farmer.publicKey, offerId)[0],
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const setDelegate = (farmer: Keypair, delegate: PublicKey) =>
+    program.methods
+      .updateFarmerProfile(null, null, null, delegate)
+      .accounts({
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        authority: farmer.publicKey,
+      })
+      .signers([farmer])
+      .rpc();
+
+  const setStatus = (warehouse: PublicKey, status: object) =>
+    program.methods
+      .setWarehouseStatus(status as any)
+      .accounts({ config: configPDA, warehouse, admin: admin.publicKey })
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+
+    mint = await createMint(provider, 6);
+    await program.methods
+      .addAllowedMint(new anchor.BN(0), new anchor.BN(1_000_000_000), null)
+      .accounts({
+        config: configPDA,
+        allowedMint: getAllowedMintPDA(mint)[0],
+        mint,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  describe("success cases", () => {
+    it("should deactivate the offer and release the counters", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const { offer, offerId } = await publishOffer(farmer, warehouse);
+
+      await deactivateOffer(farmer, warehouse, offerId);
+
+      const lot = await program.account.lotOffer.fetch(offer);
+      expect(lot.active).to.be.false;
+      const profile = await program.account.farmerProfile.fetch(
+        getFarmerPDA(farmer.publicKey)[0]
+      );
+      expect(profile.activeOffers).to.equal(0);
+      expect(profile.offerCounter.toNumber()).to.equal(1);
+      const affiliation = await program.account.farmerAffiliation.fetch(
+        getAffiliationPDA(farmer.publicKey, warehouse)[0]
+      );
+      expect(affiliation.activeOffers).to.equal(0);
+    });
+
+    it("should still work once the warehouse is closed", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const { offer, offerId } = await publishOffer(farmer, warehouse);
+      await setStatus(warehouse, CLOSED);
+
+      await deactivateOffer(farmer, warehouse, offerId);
+
+      expect((await program.account.lotOffer.fetch(offer)).active).to.be.false;
+    });
+
+    it("should leave the offer account for close_offer", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const { offerId } = await publishOffer(farmer, warehouse);
+      const [farmerProfile] = getFarmerPDA(farmer.publicKey);
+      const closeProfile = () =>
+        program.methods
+          .closeFarmerProfile()
+          .accounts({ farmerProfile, authority: farmer.publicKey })
+          .signers([farmer])
+          .rpc();
+
+      try {
+        await closeProfile();
+        expect.fail("Should have thrown an error with an active offer");
+      } catch (err) {
+        expect(err.toString()).to.include("ActiveOffersRemaining");
+      }
+
+      await deactivateOffer(farmer, warehouse, offerId);
+
+      const profile = await program.account.farmerProfile.fetch(farmerProfile);
+      expect(profile.offerAccounts).to.equal(1);
+      try {
+        await closeProfile();
+        expect.fail("Should have thrown an error with an offer account left");
+      } catch (err) {
+        expect(err.toString()).to.include("OfferAccountsRemaining");
+      }
+    });
+
+    it("should let the delegate deactivate for the farmer", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const { offer, offerId } = await publishOffer(farmer, warehouse);
+      const delegate = await newFunded(provider);
+      await setDelegate(farmer, delegate.publicKey);
+
+      await deactivateOffer(farmer, warehouse, offerId, delegate);
+
+      expect((await program.account.lotOffer.fetch(offer)).active).to.be.false;
+    });
+
+    it("should emit OfferDeactivated", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const { offer, offerId } = await publishOffer(farmer, warehouse);
+
+      let event: any = null;
+      const listener = program.addEventListener("offerDeactivated", (e) => {
+        event = e;
+      });
+
+      await deactivateOffer(farmer, warehouse, offerId);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.offer.toString()).to.equal(offer.toString());
+      expect(event.farmer.toString()).to.equal(farmer.publicKey.toString());
+      expect(event.warehouse.toString()).to.equal(warehouse.toString());
+    });
+  });
+
+  describe("error cases", () => {
+    it("should fail for an offer that is already inactive", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const { offerId } = await publishOffer(farmer, warehouse);
+      await deactivateOffer(farmer, warehouse, offerId);
+
+      try {
+        await deactivateOffer(farmer, warehouse, offerId);
+        expect.fail("Should have thrown an error for an inactive offer");
+      } catch (err) {
+        expect(err.toString()).to.include("OfferNotActive");
+      }
+    });
+
+    it("should fail for another farmer's offer", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const { offerId } = await publishOffer(farmer, warehouse);
+      const other = await newFarmer();
+
+      try {
+        await deactivateOffer(farmer, warehouse, offerId, other);
+        expect.fail("Should have thrown an error for a foreign offer");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedOfferSigner");
+      }
+    });
+  });
+});
@@ -0,0 +1,314 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { createMint } from "./helpers/token";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+describe("end_affiliation", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const {
+    getConfigPDA,
+    getWarehousePDA,
+    getFarmerPDA,
+    getAffiliationPDA,
+    getAllowedMintPDA,
+    getOfferPDA,
+  } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  // Registered farmer with a funded key
+  const newFarmer = async () => {
+    const farmer = await newFunded(provider);
+    await program.methods
+      .registerFarmer("Green Acres", "")
+      .accounts({
+        config: configPDA,
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        authority: farmer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([farmer])
+      .rpc();
+    return farmer;
+  };
+
+  const createWarehouse = async () => {
+    const warehouseId = new anchor.BN(
+      Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)
+    );
+    const operator = await newFunded(provider);
+    const [warehouse] = getWarehousePDA(warehouseId);
+
+    await program.methods
+      .createWarehouse(warehouseId, operator.publicKey, "Hub", "", 500, [], null)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return { warehouse, operator };
+  };
+
+  const requestAffiliation = (
+    farmer: Keypair,
+    warehouse: PublicKey,
+    signer: Keypair = farmer
+  ) =>
+    program.methods
+      .requestWarehouseAffiliation()
+      .accounts({
+        config: configPDA,
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        warehouse,
+        affiliation: getAffiliationPDA(farmer.publicKey, warehouse)[0],
+        authority: signer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc();
+
+  const approveAffiliation = (
+    farmer: PublicKey,
+    warehouse: PublicKey,
+    operator: Keypair
+  ) =>
+    program.methods
+      .approveAffiliation()
+      .accounts({
+        config: configPDA,
+        affiliation: getAffiliationPDA(farmer, warehouse)[0],
+        warehouse,
+        operator: operator.publicKey,
+      })
+      .signers([operator])
+      .rpc();
+
+  // Allowed payment mint, created once for the suite
+  let mint: PublicKey;
+
+  const endAffiliation = (
+    farmer: PublicKey,
+    warehouse: PublicKey,
+    signer: Keypair
+  ) =>
+    program.methods
+      .endAffiliation()
+      .accounts({
+        affiliation: getAffiliationPDA(farmer, warehouse)[0],
+        warehouse,
+        farmer,
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  // Registered farmer with an approved affiliation to a new warehouse
+  const newAffiliatedFarmer = async () => {
+    const farmer = await newFarmer();
+    const { warehouse, operator } = await createWarehouse();
+    await requestAffiliation(farmer, warehouse);
+    await approveAffiliation(farmer.publicKey, warehouse, operator);
+    return { farmer, warehouse, operator };
+  };
+
+  const publishOffer = async (
+    farmer: Keypair,
+    warehouse: PublicKey,
+    {
+      cropName = "Tomato",
+      cultivarName = null as string | null,
+      qty = new anchor.BN(10_000),
+      priceMinor = new anchor.BN(5),
+      expiresAt = null as anchor.BN | null,
+      notesPublic = null as string | null,
+      allowedMint = mint,
+    } = {}
+  ) => {
+    const [farmerProfile] = getFarmerPDA(farmer.publicKey);
+    const { offerCounter } = await program.account.farmerProfile.fetch(
+      farmerProfile
+    );
+    const [offer] = getOfferPDA(farmer.publicKey, offerCounter);
+
+    await program.methods
+      .publishOffer(
+        cropName,
+        cultivarName,
+        2,
+        qty,
+        priceMinor,
+        expiresAt,
+        notesPublic
+      )
+      .accounts({
+        config: configPDA,
+        farmerProfile,
+        affiliation: getAffiliationPDA(farmer.publicKey, warehouse)[0],
+        warehouse,
+        allowedMint: getAllowedMintPDA(allowedMint)[0],
+        offer,
+        authority: farmer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([farmer])
+      .rpc();
+
+    return { offer, offerId: offerCounter };
+  };
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+
+    mint = await createMint(provider, 6);
+    await program.methods
+      .addAllowedMint(new anchor.BN(0), new anchor.BN(1_000_000_000), null)
+      .accounts({
+        config: configPDA,
+        allowedMint: getAllowedMintPDA(mint)[0],
+        mint,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  describe("success cases", () => {
+    it("should let the farmer leave and refund the rent", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const [affiliationPDA] = getAffiliationPDA(farmer.publicKey, warehouse);
+      const rent = (await provider.connection.getAccountInfo(affiliationPDA))
+        .lamports;
+      const balanceBefore = await provider.connection.getBalance(
+        farmer.publicKey
+      );
+
+      await endAffiliation(farmer.publicKey, warehouse, farmer);
+
+      expect(await provider.connection.getAccountInfo(affiliationPDA)).to.be
+        .null;
+      const balanceAfter = await provider.connection.getBalance(
+        farmer.publicKey
+      );
+      // Rent comes back, minus the transaction fee paid by the farmer
+      expect(balanceAfter).to.be.greaterThan(balanceBefore + rent - 10_000);
+    });
+
+    it("should let the operator drop a farmer, refunding the farmer", async () => {
+      const { farmer, warehouse, operator } = await newAffiliatedFarmer();
+      const balanceBefore = await provider.connection.getBalance(
+        farmer.publicKey
+      );
+
+      await endAffiliation(farmer.publicKey, warehouse, operator);
+
+      expect(
+        await provider.connection.getAccountInfo(
+          getAffiliationPDA(farmer.publicKey, warehouse)[0]
+        )
+      ).to.be.null;
+      expect(
+        await provider.connection.getBalance(farmer.publicKey)
+      ).to.be.greaterThan(balanceBefore);
+    });
+
+    it("should let the farmer withdraw a pending request", async () => {
+      const farmer = await newFarmer();
+      const { warehouse } = await createWarehouse();
+      await requestAffiliation(farmer, warehouse);
+
+      await endAffiliation(farmer.publicKey, warehouse, farmer);
+
+      expect(
+        await provider.connection.getAccountInfo(
+          getAffiliationPDA(farmer.publicKey, warehouse)[0]
+        )
+      ).to.be.null;
+    });
+
+    it("should allow a new request after leaving", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      await endAffiliation(farmer.publicKey, warehouse, farmer);
+
+      await requestAffiliation(farmer, warehouse);
+
+      const affiliation = await program.account.farmerAffiliation.fetch(
+        getAffiliationPDA(farmer.publicKey, warehouse)[0]
+      );
+      expect(affiliation.status).to.deep.equal({ pending: {} });
+    });
+
+    it("should emit AffiliationEnded", async () => {
+      const { farmer, warehouse, operator } = await newAffiliatedFarmer();
+
+      let event: any = null;
+      const listener = program.addEventListener("affiliationEnded", (e) => {
+        event = e;
+      });
+
+      await endAffiliation(farmer.publicKey, warehouse, operator);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.farmer.toString()).to.equal(farmer.publicKey.toString());
+      expect(event.warehouse.toString()).to.equal(warehouse.toString());
+      expect(event.authority.toString()).to.equal(
+        operator.publicKey.toString()
+      );
+    });
+  });
+
+  describe("error cases", () => {
+    it("should fail while offers at the warehouse are active", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      await publishOffer(farmer, warehouse);
+
+      try {
+        await endAffiliation(farmer.publicKey, warehouse, farmer);
+        expect.fail("Should have thrown an error with active offers");
+      } catch (err) {
+        expect(err.toString()).to.include("ActiveOffersAtWarehouse");
+      }
+    });
+
+    it("should fail for anyone but the farmer or the operator", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const stranger = await newFunded(provider);
+
+      try {
+        await endAffiliation(farmer.publicKey, warehouse, stranger);
+        expect.fail("Should have thrown an error for a stranger");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedAffiliationAuthority");
+      }
+    });
+  });
+});
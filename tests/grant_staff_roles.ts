@@ -0,0 +1,240 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+// Mirrors the ROLE_* constants in states.rs
+const ROLE_QUOTE = 1 << 1;
+const ROLE_DISPATCH = 1 << 2;
+const ROLE_ALL = 0x3f;
+const PAUSE_ONBOARDING = 1 << 3;
+
+describe("grant_staff_roles", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const { getConfigPDA, getWarehousePDA, getStaffPDA } = pdaHelpers(
+    program.programId
+  );
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  // Creates a warehouse run by a funded operator (it pays for grants)
+  const createWarehouse = async () => {
+    const warehouseId = new anchor.BN(
+      Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)
+    );
+    const operator = await newFunded(provider);
+    const [warehouse] = getWarehousePDA(warehouseId);
+
+    await program.methods
+      .createWarehouse(warehouseId, operator.publicKey, "Hub", "", 500, [], null)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return { warehouse, operator };
+  };
+
+  const grant = (
+    warehouse: PublicKey,
+    member: PublicKey,
+    roles: number,
+    signer: Keypair | null
+  ) => {
+    const builder = program.methods.grantStaffRoles(member, roles).accounts({
+      config: configPDA,
+      warehouse,
+      staff: getStaffPDA(warehouse, member)[0],
+      authority: signer ? signer.publicKey : admin.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    });
+    return signer ? builder.signers([signer]).rpc() : builder.rpc();
+  };
+
+  const setPauseFlags = (pauseFlags: number) =>
+    program.methods
+      .setPauseFlags(pauseFlags)
+      .accounts({ config: configPDA, admin: admin.publicKey })
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should let the operator create a grant", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const member = Keypair.generate().publicKey;
+
+      await grant(warehouse, member, ROLE_QUOTE, operator);
+
+      const [staffPDA, bump] = getStaffPDA(warehouse, member);
+      const staff = await program.account.warehouseStaff.fetch(staffPDA);
+      expect(staff.warehouse.toString()).to.equal(warehouse.toString());
+      expect(staff.member.toString()).to.equal(member.toString());
+      expect(staff.roles).to.equal(ROLE_QUOTE);
+      expect(staff.bump).to.equal(bump);
+    });
+
+    it("should add roles to an existing grant", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const member = Keypair.generate().publicKey;
+
+      await grant(warehouse, member, ROLE_QUOTE, operator);
+      await grant(warehouse, member, ROLE_DISPATCH, operator);
+
+      const staff = await program.account.warehouseStaff.fetch(
+        getStaffPDA(warehouse, member)[0]
+      );
+      expect(staff.roles).to.equal(ROLE_QUOTE | ROLE_DISPATCH);
+    });
+
+    it("should let the admin grant roles", async () => {
+      const { warehouse } = await createWarehouse();
+      const member = Keypair.generate().publicKey;
+
+      await grant(warehouse, member, ROLE_ALL, null);
+
+      const staff = await program.account.warehouseStaff.fetch(
+        getStaffPDA(warehouse, member)[0]
+      );
+      expect(staff.roles).to.equal(ROLE_ALL);
+    });
+
+    it("should emit StaffRolesGranted", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const member = Keypair.generate().publicKey;
+      await grant(warehouse, member, ROLE_QUOTE, operator);
+
+      let event: any = null;
+      const listener = program.addEventListener("staffRolesGranted", (e) => {
+        event = e;
+      });
+
+      await grant(warehouse, member, ROLE_DISPATCH, operator);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.warehouse.toString()).to.equal(warehouse.toString());
+      expect(event.member.toString()).to.equal(member.toString());
+      expect(event.authority.toString()).to.equal(
+        operator.publicKey.toString()
+      );
+      expect(event.roles).to.equal(ROLE_DISPATCH);
+      expect(event.newRoles).to.equal(ROLE_QUOTE | ROLE_DISPATCH);
+    });
+  });
+
+  describe("error cases", () => {
+    it("should reject an empty role mask", async () => {
+      const { warehouse, operator } = await createWarehouse();
+
+      try {
+        await grant(warehouse, Keypair.generate().publicKey, 0, operator);
+        expect.fail("Should have thrown an error for empty roles");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidRoles");
+      }
+    });
+
+    it("should reject unknown role bits", async () => {
+      const { warehouse, operator } = await createWarehouse();
+
+      try {
+        await grant(warehouse, Keypair.generate().publicKey, 1 << 6, operator);
+        expect.fail("Should have thrown an error for unknown roles");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidRoles");
+      }
+    });
+
+    it("should reject the default key as member", async () => {
+      const { warehouse, operator } = await createWarehouse();
+
+      try {
+        await grant(warehouse, PublicKey.default, ROLE_QUOTE, operator);
+        expect.fail("Should have thrown an error for an unset member");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidStaffMember");
+      }
+    });
+
+    it("should reject the operator as member", async () => {
+      const { warehouse, operator } = await createWarehouse();
+
+      try {
+        await grant(warehouse, operator.publicKey, ROLE_QUOTE, operator);
+        expect.fail("Should have thrown an error for granting the operator");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidStaffMember");
+      }
+    });
+
+    it("should not let staff grant roles", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const manager = await newFunded(provider);
+      await grant(warehouse, manager.publicKey, ROLE_ALL, operator);
+
+      try {
+        await grant(warehouse, Keypair.generate().publicKey, ROLE_QUOTE, manager);
+        expect.fail("Should have thrown an error for staff granting roles");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedWarehouseAuthority");
+      }
+    });
+
+    it("should fail when signer is neither operator nor admin", async () => {
+      const { warehouse } = await createWarehouse();
+      const attacker = await newFunded(provider);
+
+      try {
+        await grant(warehouse, attacker.publicKey, ROLE_ALL, attacker);
+        expect.fail("Should have thrown an error for unauthorized signer");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedWarehouseAuthority");
+      }
+    });
+
+    it("should fail while onboarding is paused", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      await setPauseFlags(PAUSE_ONBOARDING);
+
+      try {
+        await grant(warehouse, Keypair.generate().publicKey, ROLE_QUOTE, operator);
+        expect.fail("Should have thrown an error while paused");
+      } catch (err) {
+        expect(err.toString()).to.include("OnboardingPaused");
+      } finally {
+        await setPauseFlags(0);
+      }
+    });
+  });
+});
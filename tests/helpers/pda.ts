@@ -0,0 +1,90 @@
+import * as anchor from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+
+/**
+ * PDA derivations for every farmer-core seed, bound to a program id.
+ * Mirrors `farmer-core-client::pda`.
+ */
+export const pdaHelpers = (programId: PublicKey) => ({
+  // Program config singleton
+  getConfigPDA: (): [PublicKey, number] => {
+    return PublicKey.findProgramAddressSync([Buffer.from("config")], programId);
+  },
+
+  // Allowlist entry for a mint
+  getAllowedMintPDA: (mint: PublicKey): [PublicKey, number] => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("mint"), mint.toBuffer()],
+      programId
+    );
+  },
+
+  // Warehouse (warehouse_id as u64 LE)
+  getWarehousePDA: (warehouseId: anchor.BN): [PublicKey, number] => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("warehouse"), warehouseId.toArrayLike(Buffer, "le", 8)],
+      programId
+    );
+  },
+
+  // Staff grant of a member on a warehouse
+  getStaffPDA: (
+    warehouse: PublicKey,
+    member: PublicKey
+  ): [PublicKey, number] => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("staff"), warehouse.toBuffer(), member.toBuffer()],
+      programId
+    );
+  },
+
+  // Farmer profile
+  getFarmerPDA: (farmer: PublicKey): [PublicKey, number] => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("farmer"), farmer.toBuffer()],
+      programId
+    );
+  },
+
+  // Farmer-warehouse affiliation
+  getAffiliationPDA: (
+    farmer: PublicKey,
+    warehouse: PublicKey
+  ): [PublicKey, number] => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("affiliation"), farmer.toBuffer(), warehouse.toBuffer()],
+      programId
+    );
+  },
+
+  // Lot offer (offer_id as u64 LE)
+  getOfferPDA: (farmer: PublicKey, offerId: anchor.BN): [PublicKey, number] => {
+    return PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("offer"),
+        farmer.toBuffer(),
+        offerId.toArrayLike(Buffer, "le", 8),
+      ],
+      programId
+    );
+  },
+
+  // Customer profile
+  getCustomerPDA: (customer: PublicKey): [PublicKey, number] => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("customer"), customer.toBuffer()],
+      programId
+    );
+  },
+
+  // Customer entry on a warehouse
+  getWarehouseCustomerPDA: (
+    warehouse: PublicKey,
+    customer: PublicKey
+  ): [PublicKey, number] => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("wcustomer"), warehouse.toBuffer(), customer.toBuffer()],
+      programId
+    );
+  },
+});
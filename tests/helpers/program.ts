@@ -0,0 +1,15 @@
+import { PublicKey } from "@solana/web3.js";
+
+export const BPF_LOADER_UPGRADEABLE_PROGRAM_ID = new PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
+
+/**
+ * Program data account of an upgradeable program (holds its upgrade authority).
+ */
+export const getProgramDataAddress = (programId: PublicKey): PublicKey => {
+  return PublicKey.findProgramAddressSync(
+    [programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE_PROGRAM_ID
+  )[0];
+};
@@ -0,0 +1,60 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+  TransactionInstruction,
+} from "@solana/web3.js";
+
+export const TOKEN_PROGRAM_ID = new PublicKey(
+  "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+);
+export const TOKEN_2022_PROGRAM_ID = new PublicKey(
+  "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"
+);
+
+// Base mint account size (no Token-2022 extensions)
+const MINT_SIZE = 82;
+
+// InitializeMint2 instruction tag in both token programs
+const INITIALIZE_MINT2 = 20;
+
+/**
+ * Creates a new SPL Token (or Token-2022) mint owned by the provider wallet.
+ * Built by hand so the tests don't need `@solana/spl-token`.
+ */
+export const createMint = async (
+  provider: anchor.AnchorProvider,
+  decimals: number,
+  tokenProgram: PublicKey = TOKEN_PROGRAM_ID
+): Promise<PublicKey> => {
+  const mint = Keypair.generate();
+  const lamports =
+    await provider.connection.getMinimumBalanceForRentExemption(MINT_SIZE);
+
+  // [tag, decimals, mint_authority, freeze_authority option tag]
+  const data = Buffer.alloc(35);
+  data.writeUInt8(INITIALIZE_MINT2, 0);
+  data.writeUInt8(decimals, 1);
+  provider.wallet.publicKey.toBuffer().copy(data, 2);
+  data.writeUInt8(0, 34);
+
+  const tx = new Transaction().add(
+    SystemProgram.createAccount({
+      fromPubkey: provider.wallet.publicKey,
+      newAccountPubkey: mint.publicKey,
+      space: MINT_SIZE,
+      lamports,
+      programId: tokenProgram,
+    }),
+    new TransactionInstruction({
+      programId: tokenProgram,
+      keys: [{ pubkey: mint.publicKey, isSigner: false, isWritable: true }],
+      data,
+    })
+  );
+  await provider.sendAndConfirm(tx, [mint]);
+
+  return mint.publicKey;
+};
@@ -0,0 +1,17 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair } from "@solana/web3.js";
+
+/**
+ * Generates a keypair funded with 1 SOL from the local validator faucet.
+ */
+export const newFunded = async (
+  provider: anchor.AnchorProvider
+): Promise<Keypair> => {
+  const key = Keypair.generate();
+  const sig = await provider.connection.requestAirdrop(
+    key.publicKey,
+    anchor.web3.LAMPORTS_PER_SOL
+  );
+  await provider.connection.confirmTransaction(sig);
+  return key;
+};
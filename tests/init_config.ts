@@ -3,208 +3,132 @@ import { Program } from "@coral-xyz/anchor";
 import { FarmerCore } from "../target/types/farmer_core";
 import { expect } from "chai";
 import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+// Mirrors the PAUSE_* constants in states.rs
+const PAUSE_ALL = 0x3e;
 
 describe("init_config", () => {
   const provider = anchor.AnchorProvider.env();
   anchor.setProvider(provider);
   const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
 
-  // Helper to derive config PDA
-  const getConfigPDA = async (): Promise<[PublicKey, number]> => {
-    return PublicKey.findProgramAddressSync(
-      [Buffer.from("config")],
-      program.programId
-    );
-  };
-
-  describe("success cases", () => {
-    it("should initialize config with valid parameters", async () => {
-      const admin = provider.wallet;
-      const logisticsWallet = Keypair.generate().publicKey;
-      const paused = false;
-      const allowedMints: PublicKey[] = [];
-
-      const [configPDA] = await getConfigPDA();
-
-      const tx = await program.methods
-        .initConfig(logisticsWallet, paused, allowedMints)
-        .accounts({
-          config: configPDA,
-          admin: admin.publicKey,
-          systemProgram: anchor.web3.SystemProgram.programId,
-        })
-        .rpc();
-
-      console.log("Init config transaction signature", tx);
-
-      // Verify the config account was created and has correct data
-      const configAccount = await program.account.programConfig.fetch(
-        configPDA
-      );
+  const { getConfigPDA } = pdaHelpers(program.programId);
 
-      expect(configAccount.admin.toString()).to.equal(
-        admin.publicKey.toString()
-      );
-      expect(configAccount.logisticsWallet.toString()).to.equal(
-        logisticsWallet.toString()
-      );
-      expect(configAccount.paused).to.equal(paused);
-      expect(configAccount.allowedMints.length).to.equal(0);
-    });
-
-    it("should initialize config with paused set to true", async () => {
-      // This test should fail if config already exists, so we need a fresh setup
-      // For now, we'll test the paused state in the first test
-      // In a real scenario, you'd use a different test setup or clean up between tests
-      const admin = provider.wallet;
-      const logisticsWallet = Keypair.generate().publicKey;
-      const paused = true;
-      const allowedMints: PublicKey[] = [];
-
-      const [configPDA] = await getConfigPDA();
+  // Runs before anything creates the config, so the authority check is what fails
+  describe("access control", () => {
+    it("should reject a signer that is not the upgrade authority", async () => {
+      const attacker = await newFunded(provider);
+      const [configPDA] = getConfigPDA();
 
       try {
-        const tx = await program.methods
-          .initConfig(logisticsWallet, paused, allowedMints)
+        await program.methods
+          .initConfig(attacker.publicKey, 0)
           .accounts({
             config: configPDA,
-            admin: admin.publicKey,
+            admin: attacker.publicKey,
+            program: program.programId,
+            programData,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
+          .signers([attacker])
           .rpc();
 
-        const configAccount = await program.account.programConfig.fetch(
-          configPDA
-        );
-        expect(configAccount.paused).to.equal(true);
+        expect.fail("Should have thrown an error for unauthorized initializer");
       } catch (err) {
-        // If config already exists, that's expected - we test paused in the main test
-        expect(err.toString()).to.include("already in use");
+        expect(err.toString()).to.include("UnauthorizedInitializer");
       }
-    });
 
-    it("should initialize config with empty allowed_mints vector", async () => {
-      const admin = provider.wallet;
-      const logisticsWallet = Keypair.generate().publicKey;
-      const paused = false;
-      const allowedMints: PublicKey[] = [];
+      const config = await program.account.programConfig.fetchNullable(
+        configPDA
+      );
+      expect(config).to.be.null;
+    });
 
-      const [configPDA] = await getConfigPDA();
+    it("should reject a program data account of another program", async () => {
+      const [configPDA] = getConfigPDA();
 
       try {
-        const tx = await program.methods
-          .initConfig(logisticsWallet, paused, allowedMints)
+        await program.methods
+          .initConfig(Keypair.generate().publicKey, 0)
           .accounts({
             config: configPDA,
-            admin: admin.publicKey,
+            admin: provider.wallet.publicKey,
+            program: program.programId,
+            programData: Keypair.generate().publicKey,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
           .rpc();
 
-        const configAccount = await program.account.programConfig.fetch(
-          configPDA
-        );
-        expect(configAccount.allowedMints.length).to.equal(0);
+        expect.fail("Should have thrown an error for wrong program data");
       } catch (err) {
-        // Config might already exist from previous test
-        expect(err.toString()).to.include("already in use");
+        const errorStr = err.toString();
+        // A random key is not even a program data account
+        expect(
+          errorStr.includes("InvalidProgramData") ||
+            errorStr.includes("AccountNotInitialized")
+        ).to.be.true;
       }
     });
+  });
 
-    it("should initialize config with single allowed mint", async () => {
+  describe("success cases", () => {
+    it("should initialize config with valid parameters", async () => {
       const admin = provider.wallet;
       const logisticsWallet = Keypair.generate().publicKey;
-      const paused = false;
-      const mint1 = Keypair.generate().publicKey;
-      const allowedMints = [mint1];
+      const pauseFlags = 0;
 
-      const [configPDA] = await getConfigPDA();
+      const [configPDA] = getConfigPDA();
 
-      try {
-        const tx = await program.methods
-          .initConfig(logisticsWallet, paused, allowedMints)
-          .accounts({
-            config: configPDA,
-            admin: admin.publicKey,
-            systemProgram: anchor.web3.SystemProgram.programId,
-          })
-          .rpc();
+      const tx = await program.methods
+        .initConfig(logisticsWallet, pauseFlags)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
 
-        const configAccount = await program.account.programConfig.fetch(
-          configPDA
-        );
-        expect(configAccount.allowedMints.length).to.equal(1);
-        expect(configAccount.allowedMints[0].toString()).to.equal(
-          mint1.toString()
-        );
-      } catch (err) {
-        // Config might already exist
-        expect(err.toString()).to.include("already in use");
-      }
-    });
+      console.log("Init config transaction signature", tx);
 
-    it("should initialize config with multiple allowed mints (within limit)", async () => {
-      const admin = provider.wallet;
-      const logisticsWallet = Keypair.generate().publicKey;
-      const paused = false;
-      const allowedMints = Array.from({ length: 10 }, () =>
-        Keypair.generate().publicKey
+      // Verify the config account was created and has correct data
+      const configAccount = await program.account.programConfig.fetch(
+        configPDA
       );
 
-      const [configPDA] = await getConfigPDA();
-
-      try {
-        const tx = await program.methods
-          .initConfig(logisticsWallet, paused, allowedMints)
-          .accounts({
-            config: configPDA,
-            admin: admin.publicKey,
-            systemProgram: anchor.web3.SystemProgram.programId,
-          })
-          .rpc();
-
-        const configAccount = await program.account.programConfig.fetch(
-          configPDA
-        );
-        expect(configAccount.allowedMints.length).to.equal(10);
-      } catch (err) {
-        // Config might already exist
-        expect(err.toString()).to.include("already in use");
-      }
+      expect(configAccount.admin.toString()).to.equal(
+        admin.publicKey.toString()
+      );
+      expect(configAccount.logisticsWallet.toString()).to.equal(
+        logisticsWallet.toString()
+      );
+      expect(configAccount.pauseFlags).to.equal(pauseFlags);
+      expect(configAccount.pendingAdmin).to.be.null;
     });
 
-    it("should initialize config with max allowed mints (50)", async () => {
+    it("should initialize config with every pause flag set", async () => {
+      // This test should fail if config already exists, so we need a fresh setup
+      // For now, we'll test the pause flags in the first test
+      // In a real scenario, you'd use a different test setup or clean up between tests
       const admin = provider.wallet;
       const logisticsWallet = Keypair.generate().publicKey;
-      const paused = false;
-      const allowedMints = Array.from({ length: 50 }, () =>
-        Keypair.generate().publicKey
-      );
+      const pauseFlags = PAUSE_ALL;
 
-      const [configPDA] = await getConfigPDA();
+      const [configPDA] = getConfigPDA();
 
-      // Check if config already exists
-      let configExists = false;
       try {
-        await program.account.programConfig.fetch(configPDA);
-        configExists = true;
-      } catch (err) {
-        // Config doesn't exist, we can initialize
-      }
-
-      if (configExists) {
-        // If config exists, verify it has the correct structure
-        const configAccount = await program.account.programConfig.fetch(
-          configPDA
-        );
-        expect(configAccount.allowedMints.length).to.be.at.most(50);
-      } else {
-        // Initialize with max mints
         const tx = await program.methods
-          .initConfig(logisticsWallet, paused, allowedMints)
+          .initConfig(logisticsWallet, pauseFlags)
           .accounts({
             config: configPDA,
             admin: admin.publicKey,
+            program: program.programId,
+            programData,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
           .rpc();
@@ -212,43 +136,31 @@ describe("init_config", () => {
         const configAccount = await program.account.programConfig.fetch(
           configPDA
         );
-        expect(configAccount.allowedMints.length).to.equal(50);
+        expect(configAccount.pauseFlags).to.equal(PAUSE_ALL);
+      } catch (err) {
+        // If config already exists, that's expected - we test pause flags in the main test
+        expect(err.toString()).to.include("already in use");
       }
     });
+
   });
 
   describe("error cases", () => {
-    it("should fail with too many allowed mints (over MAX_ALLOWED_MINTS)", async () => {
+    it("should fail with unknown pause flag bits", async () => {
       const admin = provider.wallet;
       const logisticsWallet = Keypair.generate().publicKey;
-      const paused = false;
-      const allowedMints = Array.from({ length: 51 }, () =>
-        Keypair.generate().publicKey
-      ); // 51 > 50 (MAX_ALLOWED_MINTS)
-
-      const [configPDA] = await getConfigPDA();
-
-      // Check if config already exists - if so, skip this test
-      let configExists = false;
-      try {
-        await program.account.programConfig.fetch(configPDA);
-        configExists = true;
-      } catch (err) {
-        // Config doesn't exist, we can test
-      }
+      const pauseFlags = 0x80; // not a PAUSE_* bit
 
-      if (configExists) {
-        // Config already exists, can't test validation
-        // This is expected in a test suite where config is initialized once
-        return;
-      }
+      const [configPDA] = getConfigPDA();
 
       try {
         await program.methods
-          .initConfig(logisticsWallet, paused, allowedMints)
+          .initConfig(logisticsWallet, pauseFlags)
           .accounts({
             config: configPDA,
             admin: admin.publicKey,
+            program: program.programId,
+            programData,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
           .rpc();
@@ -256,11 +168,10 @@ describe("init_config", () => {
         expect.fail("Should have thrown an error");
       } catch (err) {
         const errorStr = err.toString();
-        // The error might be "TooManyAllowedMints" or a buffer error if Anchor tries to serialize first
+        // Config might already exist from previous tests
         expect(
-          errorStr.includes("TooManyAllowedMints") ||
-            errorStr.includes("encoding overruns") ||
-            errorStr.includes("Buffer")
+          errorStr.includes("InvalidPauseFlags") ||
+            errorStr.includes("already in use")
         ).to.be.true;
       }
     });
@@ -268,18 +179,19 @@ describe("init_config", () => {
     it("should fail when trying to initialize config twice", async () => {
       const admin = provider.wallet;
       const logisticsWallet = Keypair.generate().publicKey;
-      const paused = false;
-      const allowedMints: PublicKey[] = [];
+      const pauseFlags = 0;
 
-      const [configPDA] = await getConfigPDA();
+      const [configPDA] = getConfigPDA();
 
       // First initialization (might already exist from previous tests)
       try {
         await program.methods
-          .initConfig(logisticsWallet, paused, allowedMints)
+          .initConfig(logisticsWallet, pauseFlags)
           .accounts({
             config: configPDA,
             admin: admin.publicKey,
+            program: program.programId,
+            programData,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
           .rpc();
@@ -290,10 +202,12 @@ describe("init_config", () => {
       // Try to initialize again - should fail
       try {
         await program.methods
-          .initConfig(logisticsWallet, paused, allowedMints)
+          .initConfig(logisticsWallet, pauseFlags)
           .accounts({
             config: configPDA,
             admin: admin.publicKey,
+            program: program.programId,
+            programData,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
           .rpc();
@@ -307,17 +221,18 @@ describe("init_config", () => {
     it("should fail when admin is not a signer", async () => {
       const fakeAdmin = Keypair.generate().publicKey; // Not a signer
       const logisticsWallet = Keypair.generate().publicKey;
-      const paused = false;
-      const allowedMints: PublicKey[] = [];
+      const pauseFlags = 0;
 
-      const [configPDA] = await getConfigPDA();
+      const [configPDA] = getConfigPDA();
 
       try {
         await program.methods
-          .initConfig(logisticsWallet, paused, allowedMints)
+          .initConfig(logisticsWallet, pauseFlags)
           .accounts({
             config: configPDA,
             admin: fakeAdmin, // Not a signer
+            program: program.programId,
+            programData,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
           .rpc();
@@ -340,19 +255,20 @@ describe("init_config", () => {
       const admin = provider.wallet;
       const logisticsWallet1 = Keypair.generate().publicKey;
       const logisticsWallet2 = Keypair.generate().publicKey;
-      const paused = false;
-      const allowedMints: PublicKey[] = [];
+      const pauseFlags = 0;
 
-      const [configPDA] = await getConfigPDA();
+      const [configPDA] = getConfigPDA();
 
       // Test that we can set different logistics wallets
       // (This test verifies the field is stored correctly)
       try {
         const tx = await program.methods
-          .initConfig(logisticsWallet1, paused, allowedMints)
+          .initConfig(logisticsWallet1, pauseFlags)
           .accounts({
             config: configPDA,
             admin: admin.publicKey,
+            program: program.programId,
+            programData,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
           .rpc();
@@ -375,17 +291,18 @@ describe("init_config", () => {
     it("should handle admin and logistics wallet being the same", async () => {
       const admin = provider.wallet;
       const logisticsWallet = admin.publicKey; // Same as admin
-      const paused = false;
-      const allowedMints: PublicKey[] = [];
+      const pauseFlags = 0;
 
-      const [configPDA] = await getConfigPDA();
+      const [configPDA] = getConfigPDA();
 
       try {
         const tx = await program.methods
-          .initConfig(logisticsWallet, paused, allowedMints)
+          .initConfig(logisticsWallet, pauseFlags)
           .accounts({
             config: configPDA,
             admin: admin.publicKey,
+            program: program.programId,
+            programData,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
           .rpc();
@@ -403,7 +320,7 @@ describe("init_config", () => {
     });
 
     it("should correctly derive config PDA with seed 'config'", async () => {
-      const [configPDA, bump] = await getConfigPDA();
+      const [configPDA, bump] = getConfigPDA();
 
       // Verify PDA derivation
       const expectedPDA = PublicKey.findProgramAddressSync(
@@ -419,19 +336,18 @@ describe("init_config", () => {
     it("should persist all config fields correctly after initialization", async () => {
       const admin = provider.wallet;
       const logisticsWallet = Keypair.generate().publicKey;
-      const paused = false;
-      const mint1 = Keypair.generate().publicKey;
-      const mint2 = Keypair.generate().publicKey;
-      const allowedMints = [mint1, mint2];
+      const pauseFlags = 0;
 
-      const [configPDA] = await getConfigPDA();
+      const [configPDA] = getConfigPDA();
 
       try {
         const tx = await program.methods
-          .initConfig(logisticsWallet, paused, allowedMints)
+          .initConfig(logisticsWallet, pauseFlags)
           .accounts({
             config: configPDA,
             admin: admin.publicKey,
+            program: program.programId,
+            programData,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
           .rpc();
@@ -447,14 +363,14 @@ describe("init_config", () => {
         expect(configAccount.logisticsWallet.toString()).to.equal(
           logisticsWallet.toString()
         );
-        expect(configAccount.paused).to.equal(paused);
-        expect(configAccount.allowedMints.length).to.equal(2);
-        expect(configAccount.allowedMints[0].toString()).to.equal(
-          mint1.toString()
-        );
-        expect(configAccount.allowedMints[1].toString()).to.equal(
-          mint2.toString()
+        expect(configAccount.pauseFlags).to.equal(pauseFlags);
+        expect(configAccount.pendingAdmin).to.be.null;
+        expect(configAccount.version).to.equal(3);
+        expect(configAccount.feeNoticePeriod.toNumber()).to.equal(
+          7 * 24 * 60 * 60
         );
+        expect(configAccount.protocolFeeBps).to.equal(0);
+        expect(configAccount.reserved.every((b) => b === 0)).to.be.true;
       } catch (err) {
         // Config might already exist
         expect(err.toString()).to.include("already in use");
@@ -0,0 +1,77 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+
+describe("migrate_allowed_mints", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const { getConfigPDA } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("error cases", () => {
+    it("should reject a config that is already in the current layout", async () => {
+      try {
+        await program.methods
+          .migrateAllowedMints(new anchor.BN(1), new anchor.BN(1_000), null)
+          .accounts({
+            config: configPDA,
+            admin: admin.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .rpc();
+
+        expect.fail("Should have thrown an error for migrated config");
+      } catch (err) {
+        expect(err.toString()).to.include("ConfigAlreadyMigrated");
+      }
+    });
+
+    it("should leave the config untouched after a rejected migration", async () => {
+      const before = await provider.connection.getAccountInfo(configPDA);
+
+      try {
+        await program.methods
+          .migrateAllowedMints(new anchor.BN(1), new anchor.BN(1_000), null)
+          .accounts({
+            config: configPDA,
+            admin: admin.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .rpc();
+      } catch (err) {
+        // Expected, see above
+      }
+
+      const after = await provider.connection.getAccountInfo(configPDA);
+      expect(after.data.equals(before.data)).to.be.true;
+      expect(after.lamports).to.equal(before.lamports);
+    });
+  });
+});
@@ -0,0 +1,90 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+
+// Upgrades from older layouts are covered by the Rust fixture tests in
+// `programs/farmer-core/src/instructions/migrate_config.rs`; a local validator
+// only ever holds a config written in the current layout.
+describe("migrate_config", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const { getConfigPDA } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should create the config at the current layout version", async () => {
+      const config = await program.account.programConfig.fetch(configPDA);
+      const info = await provider.connection.getAccountInfo(configPDA);
+
+      expect(config.version).to.equal(3);
+      expect(info.data.length).to.equal(171);
+    });
+  });
+
+  describe("error cases", () => {
+    it("should reject a config that is already current", async () => {
+      try {
+        await program.methods
+          .migrateConfig()
+          .accounts({
+            config: configPDA,
+            admin: admin.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .rpc();
+
+        expect.fail("Should have thrown an error for migrated config");
+      } catch (err) {
+        expect(err.toString()).to.include("ConfigAlreadyMigrated");
+      }
+    });
+
+    it("should reject a config account that is not the config PDA", async () => {
+      try {
+        await program.methods
+          .migrateConfig()
+          .accounts({
+            config: Keypair.generate().publicKey,
+            admin: admin.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .rpc();
+
+        expect.fail("Should have thrown an error for wrong config account");
+      } catch (err) {
+        const errorStr = err.toString();
+        expect(
+          errorStr.includes("ConstraintSeeds") ||
+            errorStr.includes("AccountOwnedByWrongProgram")
+        ).to.be.true;
+      }
+    });
+  });
+});
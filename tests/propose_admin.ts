@@ -0,0 +1,111 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+
+describe("propose_admin", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const { getConfigPDA } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  afterEach(async () => {
+    // Leave no pending transfer behind for other test files
+    const config = await program.account.programConfig.fetch(configPDA);
+    if (config.pendingAdmin) {
+      await program.methods
+        .cancelAdminTransfer()
+        .accounts({ config: configPDA, admin: admin.publicKey })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should set pending_admin without changing admin", async () => {
+      const newAdmin = Keypair.generate().publicKey;
+
+      await program.methods
+        .proposeAdmin(newAdmin)
+        .accounts({ config: configPDA, admin: admin.publicKey })
+        .rpc();
+
+      const config = await program.account.programConfig.fetch(configPDA);
+      expect(config.admin.toString()).to.equal(admin.publicKey.toString());
+      expect(config.pendingAdmin.toString()).to.equal(newAdmin.toString());
+    });
+
+    it("should replace a previous proposal", async () => {
+      const first = Keypair.generate().publicKey;
+      const second = Keypair.generate().publicKey;
+
+      await program.methods
+        .proposeAdmin(first)
+        .accounts({ config: configPDA, admin: admin.publicKey })
+        .rpc();
+      await program.methods
+        .proposeAdmin(second)
+        .accounts({ config: configPDA, admin: admin.publicKey })
+        .rpc();
+
+      const config = await program.account.programConfig.fetch(configPDA);
+      expect(config.pendingAdmin.toString()).to.equal(second.toString());
+    });
+  });
+
+  describe("error cases", () => {
+    it("should fail when signer is not the admin", async () => {
+      const attacker = Keypair.generate();
+
+      try {
+        await program.methods
+          .proposeAdmin(attacker.publicKey)
+          .accounts({ config: configPDA, admin: attacker.publicKey })
+          .signers([attacker])
+          .rpc();
+
+        expect.fail("Should have thrown an error for unauthorized admin");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedAdmin");
+      }
+    });
+
+    it("should fail when proposing the current admin", async () => {
+      try {
+        await program.methods
+          .proposeAdmin(admin.publicKey)
+          .accounts({ config: configPDA, admin: admin.publicKey })
+          .rpc();
+
+        expect.fail("Should have thrown an error for unchanged admin");
+      } catch (err) {
+        expect(err.toString()).to.include("AdminUnchanged");
+      }
+    });
+  });
+});
@@ -0,0 +1,443 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { createMint } from "./helpers/token";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+const PAUSE_NEW_OFFERS = 1 << 2;
+const SUSPENDED = { suspended: {} };
+
+describe("publish_offer", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const {
+    getConfigPDA,
+    getWarehousePDA,
+    getFarmerPDA,
+    getAffiliationPDA,
+    getAllowedMintPDA,
+    getOfferPDA,
+  } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  // Registered farmer with a funded key
+  const newFarmer = async () => {
+    const farmer = await newFunded(provider);
+    await program.methods
+      .registerFarmer("Green Acres", "")
+      .accounts({
+        config: configPDA,
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        authority: farmer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([farmer])
+      .rpc();
+    return farmer;
+  };
+
+  const createWarehouse = async () => {
+    const warehouseId = new anchor.BN(
+      Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)
+    );
+    const operator = await newFunded(provider);
+    const [warehouse] = getWarehousePDA(warehouseId);
+
+    await program.methods
+      .createWarehouse(warehouseId, operator.publicKey, "Hub", "", 500, [], null)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return { warehouse, operator };
+  };
+
+  const requestAffiliation = (
+    farmer: Keypair,
+    warehouse: PublicKey,
+    signer: Keypair = farmer
+  ) =>
+    program.methods
+      .requestWarehouseAffiliation()
+      .accounts({
+        config: configPDA,
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        warehouse,
+        affiliation: getAffiliationPDA(farmer.publicKey, warehouse)[0],
+        authority: signer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc();
+
+  const approveAffiliation = (
+    farmer: PublicKey,
+    warehouse: PublicKey,
+    operator: Keypair
+  ) =>
+    program.methods
+      .approveAffiliation()
+      .accounts({
+        config: configPDA,
+        affiliation: getAffiliationPDA(farmer, warehouse)[0],
+        warehouse,
+        operator: operator.publicKey,
+      })
+      .signers([operator])
+      .rpc();
+
+  // Allowed payment mint, created once for the suite
+  let mint: PublicKey;
+
+  // Registered farmer with an approved affiliation to a new warehouse
+  const newAffiliatedFarmer = async () => {
+    const farmer = await newFarmer();
+    const { warehouse, operator } = await createWarehouse();
+    await requestAffiliation(farmer, warehouse);
+    await approveAffiliation(farmer.publicKey, warehouse, operator);
+    return { farmer, warehouse, operator };
+  };
+
+  const publishOffer = async (
+    farmer: Keypair,
+    warehouse: PublicKey,
+    {
+      cropName = "Tomato",
+      cultivarName = null as string | null,
+      qty = new anchor.BN(10_000),
+      priceMinor = new anchor.BN(5),
+      expiresAt = null as anchor.BN | null,
+      notesPublic = null as string | null,
+      allowedMint = mint,
+      signer = farmer,
+    } = {}
+  ) => {
+    const [farmerProfile] = getFarmerPDA(farmer.publicKey);
+    const { offerCounter } = await program.account.farmerProfile.fetch(
+      farmerProfile
+    );
+    const [offer] = getOfferPDA(farmer.publicKey, offerCounter);
+
+    await program.methods
+      .publishOffer(
+        cropName,
+        cultivarName,
+        2,
+        qty,
+        priceMinor,
+        expiresAt,
+        notesPublic
+      )
+      .accounts({
+        config: configPDA,
+        farmerProfile,
+        affiliation: getAffiliationPDA(farmer.publicKey, warehouse)[0],
+        warehouse,
+        allowedMint: getAllowedMintPDA(allowedMint)[0],
+        offer,
+        authority: signer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc();
+
+    return { offer, offerId: offerCounter };
+  };
+
+  const setDelegate = (farmer: Keypair, delegate: PublicKey) =>
+    program.methods
+      .updateFarmerProfile(null, null, null, delegate)
+      .accounts({
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        authority: farmer.publicKey,
+      })
+      .signers([farmer])
+      .rpc();
+
+  const setPauseFlags = (pauseFlags: number) =>
+    program.methods
+      .setPauseFlags(pauseFlags)
+      .accounts({ config: configPDA, admin: admin.publicKey })
+      .rpc();
+
+  const setStatus = (warehouse: PublicKey, status: object) =>
+    program.methods
+      .setWarehouseStatus(status as any)
+      .accounts({ config: configPDA, warehouse, admin: admin.publicKey })
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+
+    mint = await createMint(provider, 6);
+    await program.methods
+      .addAllowedMint(new anchor.BN(0), new anchor.BN(1_000_000_000), null)
+      .accounts({
+        config: configPDA,
+        allowedMint: getAllowedMintPDA(mint)[0],
+        mint,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  describe("success cases", () => {
+    it("should publish an offer with every field", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const expiresAt = new anchor.BN(Math.floor(Date.now() / 1000) + 86_400);
+
+      const { offer, offerId } = await publishOffer(farmer, warehouse, {
+        cultivarName: "San Marzano",
+        expiresAt,
+        notesPublic: "Picked on Friday",
+      });
+
+      const [, bump] = getOfferPDA(farmer.publicKey, offerId);
+      const lot = await program.account.lotOffer.fetch(offer);
+      expect(lot.farmer.toString()).to.equal(farmer.publicKey.toString());
+      expect(lot.warehouse.toString()).to.equal(warehouse.toString());
+      expect(lot.offerId.toNumber()).to.equal(0);
+      expect(lot.cropName).to.equal("Tomato");
+      expect(lot.cultivarName).to.equal("San Marzano");
+      expect(lot.unitCode).to.equal(2);
+      expect(lot.expiresAt.toString()).to.equal(expiresAt.toString());
+      expect(lot.notesPublic).to.equal("Picked on Friday");
+      expect(lot.mint.toString()).to.equal(mint.toString());
+      expect(lot.priceMinor.toNumber()).to.equal(5);
+      expect(lot.qtyRemaining.toNumber()).to.equal(10_000);
+      expect(lot.active).to.be.true;
+      expect(lot.rentPayer.toString()).to.equal(farmer.publicKey.toString());
+      expect(lot.bump).to.equal(bump);
+    });
+
+    it("should count offers on the profile and the affiliation", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+
+      await publishOffer(farmer, warehouse);
+      const { offerId } = await publishOffer(farmer, warehouse);
+
+      expect(offerId.toNumber()).to.equal(1);
+      const profile = await program.account.farmerProfile.fetch(
+        getFarmerPDA(farmer.publicKey)[0]
+      );
+      expect(profile.offerCounter.toNumber()).to.equal(2);
+      expect(profile.activeOffers).to.equal(2);
+      expect(profile.offerAccounts).to.equal(2);
+      const affiliation = await program.account.farmerAffiliation.fetch(
+        getAffiliationPDA(farmer.publicKey, warehouse)[0]
+      );
+      expect(affiliation.activeOffers).to.equal(2);
+    });
+
+    it("should let the farmer pick which warehouse fulfils each offer", async () => {
+      const { farmer, warehouse: first } = await newAffiliatedFarmer();
+      const second = await createWarehouse();
+      await requestAffiliation(farmer, second.warehouse);
+      await approveAffiliation(
+        farmer.publicKey,
+        second.warehouse,
+        second.operator
+      );
+
+      const a = await publishOffer(farmer, first);
+      const b = await publishOffer(farmer, second.warehouse);
+
+      expect(
+        (await program.account.lotOffer.fetch(a.offer)).warehouse.toString()
+      ).to.equal(first.toString());
+      expect(
+        (await program.account.lotOffer.fetch(b.offer)).warehouse.toString()
+      ).to.equal(second.warehouse.toString());
+      const affiliation = await program.account.farmerAffiliation.fetch(
+        getAffiliationPDA(farmer.publicKey, second.warehouse)[0]
+      );
+      expect(affiliation.activeOffers).to.equal(1);
+    });
+
+    it("should let the delegate publish for the farmer", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const delegate = await newFunded(provider);
+      await setDelegate(farmer, delegate.publicKey);
+
+      const { offer } = await publishOffer(farmer, warehouse, {
+        signer: delegate,
+      });
+
+      const lot = await program.account.lotOffer.fetch(offer);
+      expect(lot.farmer.toString()).to.equal(farmer.publicKey.toString());
+      expect(lot.rentPayer.toString()).to.equal(delegate.publicKey.toString());
+      const profile = await program.account.farmerProfile.fetch(
+        getFarmerPDA(farmer.publicKey)[0]
+      );
+      expect(profile.activeOffers).to.equal(1);
+    });
+
+    it("should emit OfferPublished", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+
+      let event: any = null;
+      const listener = program.addEventListener("offerPublished", (e) => {
+        event = e;
+      });
+
+      const { offer } = await publishOffer(farmer, warehouse);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.offer.toString()).to.equal(offer.toString());
+      expect(event.farmer.toString()).to.equal(farmer.publicKey.toString());
+      expect(event.warehouse.toString()).to.equal(warehouse.toString());
+      expect(event.cropName).to.equal("Tomato");
+      expect(event.qty.toNumber()).to.equal(10_000);
+      expect(event.priceMinor.toNumber()).to.equal(5);
+      expect(event.mint.toString()).to.equal(mint.toString());
+    });
+  });
+
+  describe("error cases", () => {
+    it("should fail while the affiliation is pending", async () => {
+      const farmer = await newFarmer();
+      const { warehouse } = await createWarehouse();
+      await requestAffiliation(farmer, warehouse);
+
+      try {
+        await publishOffer(farmer, warehouse);
+        expect.fail("Should have thrown an error for a pending affiliation");
+      } catch (err) {
+        expect(err.toString()).to.include("AffiliationNotActive");
+      }
+    });
+
+    it("should fail without an affiliation", async () => {
+      const farmer = await newFarmer();
+      const { warehouse } = await createWarehouse();
+
+      try {
+        await publishOffer(farmer, warehouse);
+        expect.fail("Should have thrown an error without an affiliation");
+      } catch (err) {
+        expect(err.toString()).to.include("AccountNotInitialized");
+      }
+    });
+
+    it("should fail for a signer that is neither the farmer nor its delegate", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const stranger = await newFunded(provider);
+
+      try {
+        await publishOffer(farmer, warehouse, { signer: stranger });
+        expect.fail("Should have thrown an error for a stranger");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedOfferSigner");
+      }
+    });
+
+    it("should fail for a suspended warehouse", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      await setStatus(warehouse, SUSPENDED);
+
+      try {
+        await publishOffer(farmer, warehouse);
+        expect.fail("Should have thrown an error for a suspended warehouse");
+      } catch (err) {
+        expect(err.toString()).to.include("WarehouseSuspended");
+      }
+    });
+
+    it("should fail while new offers are paused", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      await setPauseFlags(PAUSE_NEW_OFFERS);
+
+      try {
+        await publishOffer(farmer, warehouse);
+        expect.fail("Should have thrown an error while paused");
+      } catch (err) {
+        expect(err.toString()).to.include("NewOffersPaused");
+      } finally {
+        await setPauseFlags(0);
+      }
+    });
+
+    it("should fail for a mint that is not allowed", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+      const otherMint = await createMint(provider, 6);
+
+      try {
+        await publishOffer(farmer, warehouse, { allowedMint: otherMint });
+        expect.fail("Should have thrown an error for a disallowed mint");
+      } catch (err) {
+        expect(err.toString()).to.include("AccountNotInitialized");
+      }
+    });
+
+    it("should reject an empty crop name", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+
+      try {
+        await publishOffer(farmer, warehouse, { cropName: "" });
+        expect.fail("Should have thrown an error for an empty crop name");
+      } catch (err) {
+        expect(err.toString()).to.include("EmptyCropName");
+      }
+    });
+
+    it("should reject a zero quantity or price", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+
+      try {
+        await publishOffer(farmer, warehouse, { qty: new anchor.BN(0) });
+        expect.fail("Should have thrown an error for a zero quantity");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidQuantity");
+      }
+
+      try {
+        await publishOffer(farmer, warehouse, { priceMinor: new anchor.BN(0) });
+        expect.fail("Should have thrown an error for a zero price");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidPrice");
+      }
+    });
+
+    it("should reject an expiry in the past", async () => {
+      const { farmer, warehouse } = await newAffiliatedFarmer();
+
+      try {
+        await publishOffer(farmer, warehouse, { expiresAt: new anchor.BN(1) });
+        expect.fail("Should have thrown an error for a past expiry");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidExpiry");
+      }
+    });
+  });
+});
@@ -0,0 +1,180 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+// Mirrors MAX_URI_LEN in states.rs
+const MAX_URI_LEN = 200;
+
+// Mirrors PAUSE_ONBOARDING in states.rs
+const PAUSE_ONBOARDING = 1 << 3;
+
+describe("register_customer", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const { getConfigPDA, getCustomerPDA } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  const registerCustomer = (customer: Keypair, publicProfileUri = "") =>
+    program.methods
+      .registerCustomer(publicProfileUri)
+      .accounts({
+        config: configPDA,
+        customerProfile: getCustomerPDA(customer.publicKey)[0],
+        authority: customer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customer])
+      .rpc();
+
+  const setPauseFlags = (pauseFlags: number) =>
+    program.methods
+      .setPauseFlags(pauseFlags)
+      .accounts({ config: configPDA, admin: admin.publicKey })
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should register a customer with a public profile", async () => {
+      const customer = await newFunded(provider);
+      const [customerPDA, bump] = getCustomerPDA(customer.publicKey);
+
+      await registerCustomer(customer, "https://example.com/me");
+
+      const profile = await program.account.customerProfile.fetch(customerPDA);
+      expect(profile.authority.toString()).to.equal(
+        customer.publicKey.toString()
+      );
+      expect(profile.publicProfileUri).to.equal("https://example.com/me");
+      expect(profile.orderCounter.toNumber()).to.equal(0);
+      expect(profile.openOrders).to.equal(0);
+      expect(profile.linkedWarehouses).to.equal(0);
+      expect(profile.createdAt.toNumber()).to.be.greaterThan(0);
+      expect(profile.bump).to.equal(bump);
+    });
+
+    it("should accept ipfs and ar URIs, or none", async () => {
+      for (const uri of ["ipfs://bafybeigdyrzt", "ar://abc123", ""]) {
+        const customer = await newFunded(provider);
+        await registerCustomer(customer, uri);
+
+        const profile = await program.account.customerProfile.fetch(
+          getCustomerPDA(customer.publicKey)[0]
+        );
+        expect(profile.publicProfileUri).to.equal(uri);
+      }
+    });
+
+    it("should accept a URI of exactly MAX_URI_LEN", async () => {
+      const customer = await newFunded(provider);
+      const uri = "https://" + "u".repeat(MAX_URI_LEN - "https://".length);
+
+      await registerCustomer(customer, uri);
+
+      const profile = await program.account.customerProfile.fetch(
+        getCustomerPDA(customer.publicKey)[0]
+      );
+      expect(profile.publicProfileUri).to.have.length(MAX_URI_LEN);
+    });
+
+    it("should emit CustomerRegistered", async () => {
+      const customer = await newFunded(provider);
+
+      let event: any = null;
+      const listener = program.addEventListener("customerRegistered", (e) => {
+        event = e;
+      });
+
+      await registerCustomer(customer, "ar://abc123");
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.customer.toString()).to.equal(
+        getCustomerPDA(customer.publicKey)[0].toString()
+      );
+      expect(event.authority.toString()).to.equal(
+        customer.publicKey.toString()
+      );
+      expect(event.publicProfileUri).to.equal("ar://abc123");
+    });
+  });
+
+  describe("error cases", () => {
+    it("should reject a URI with another scheme", async () => {
+      for (const uri of ["http://example.com", "javascript:alert(1)"]) {
+        try {
+          await registerCustomer(await newFunded(provider), uri);
+          expect.fail(`Should have thrown an error for ${uri}`);
+        } catch (err) {
+          expect(err.toString()).to.include("InvalidProfileUriScheme");
+        }
+      }
+    });
+
+    it("should reject a URI over MAX_URI_LEN", async () => {
+      try {
+        await registerCustomer(
+          await newFunded(provider),
+          "https://" + "u".repeat(MAX_URI_LEN)
+        );
+        expect.fail("Should have thrown an error for long URI");
+      } catch (err) {
+        expect(err.toString()).to.include("ProfileUriTooLong");
+      }
+    });
+
+    it("should reject registering twice", async () => {
+      const customer = await newFunded(provider);
+      await registerCustomer(customer);
+
+      try {
+        await registerCustomer(customer);
+        expect.fail("Should have thrown an error for duplicate profile");
+      } catch (err) {
+        expect(err.toString()).to.include("already in use");
+      }
+    });
+
+    it("should fail while onboarding is paused", async () => {
+      const customer = await newFunded(provider);
+      await setPauseFlags(PAUSE_ONBOARDING);
+
+      try {
+        await registerCustomer(customer);
+        expect.fail("Should have thrown an error while paused");
+      } catch (err) {
+        expect(err.toString()).to.include("OnboardingPaused");
+      } finally {
+        await setPauseFlags(0);
+      }
+    });
+  });
+});
@@ -0,0 +1,211 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+// Mirrors the MAX_* constants in states.rs
+const MAX_NAME_LEN = 100;
+const MAX_URI_LEN = 200;
+
+// Mirrors PAUSE_ONBOARDING in states.rs
+const PAUSE_ONBOARDING = 1 << 3;
+
+describe("register_farmer", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const { getConfigPDA, getFarmerPDA } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  const registerFarmer = (
+    farmer: Keypair,
+    displayName = "Green Acres",
+    publicProfileUri = "https://example.com/green-acres"
+  ) =>
+    program.methods
+      .registerFarmer(displayName, publicProfileUri)
+      .accounts({
+        config: configPDA,
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        authority: farmer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([farmer])
+      .rpc();
+
+  const setPauseFlags = (pauseFlags: number) =>
+    program.methods
+      .setPauseFlags(pauseFlags)
+      .accounts({ config: configPDA, admin: admin.publicKey })
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should register a farmer with every field set", async () => {
+      const farmer = await newFunded(provider);
+      const [farmerPDA, bump] = getFarmerPDA(farmer.publicKey);
+
+      await registerFarmer(farmer);
+
+      const profile = await program.account.farmerProfile.fetch(farmerPDA);
+      expect(profile.authority.toString()).to.equal(
+        farmer.publicKey.toString()
+      );
+      expect(profile.payoutWallet.toString()).to.equal(
+        farmer.publicKey.toString()
+      );
+      expect(profile.delegate).to.be.null;
+      expect(profile.displayName).to.equal("Green Acres");
+      expect(profile.publicProfileUri).to.equal(
+        "https://example.com/green-acres"
+      );
+      expect(profile.offerCounter.toNumber()).to.equal(0);
+      expect(profile.activeOffers).to.equal(0);
+      expect(profile.openOrders).to.equal(0);
+      expect(profile.createdAt.toNumber()).to.be.greaterThan(0);
+      expect(profile.bump).to.equal(bump);
+    });
+
+    it("should accept ipfs and ar URIs, or none", async () => {
+      for (const uri of ["ipfs://bafybeigdyrzt", "ar://abc123", ""]) {
+        const farmer = await newFunded(provider);
+        await registerFarmer(farmer, "Green Acres", uri);
+
+        const profile = await program.account.farmerProfile.fetch(
+          getFarmerPDA(farmer.publicKey)[0]
+        );
+        expect(profile.publicProfileUri).to.equal(uri);
+      }
+    });
+
+    it("should accept the maximum bounds", async () => {
+      const farmer = await newFunded(provider);
+      const uri = "https://" + "u".repeat(MAX_URI_LEN - "https://".length);
+
+      await registerFarmer(farmer, "n".repeat(MAX_NAME_LEN), uri);
+
+      const profile = await program.account.farmerProfile.fetch(
+        getFarmerPDA(farmer.publicKey)[0]
+      );
+      expect(profile.displayName).to.have.length(MAX_NAME_LEN);
+      expect(profile.publicProfileUri).to.have.length(MAX_URI_LEN);
+    });
+
+    it("should emit FarmerRegistered", async () => {
+      const farmer = await newFunded(provider);
+
+      let event: any = null;
+      const listener = program.addEventListener("farmerRegistered", (e) => {
+        event = e;
+      });
+
+      await registerFarmer(farmer);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.farmer.toString()).to.equal(
+        getFarmerPDA(farmer.publicKey)[0].toString()
+      );
+      expect(event.authority.toString()).to.equal(farmer.publicKey.toString());
+      expect(event.displayName).to.equal("Green Acres");
+    });
+  });
+
+  describe("error cases", () => {
+    it("should reject a URI with another scheme", async () => {
+      for (const uri of ["http://example.com", "javascript:alert(1)"]) {
+        try {
+          await registerFarmer(await newFunded(provider), "Green Acres", uri);
+          expect.fail(`Should have thrown an error for ${uri}`);
+        } catch (err) {
+          expect(err.toString()).to.include("InvalidProfileUriScheme");
+        }
+      }
+    });
+
+    it("should reject an empty or too long display name", async () => {
+      try {
+        await registerFarmer(await newFunded(provider), "");
+        expect.fail("Should have thrown an error for empty name");
+      } catch (err) {
+        expect(err.toString()).to.include("EmptyDisplayName");
+      }
+
+      try {
+        await registerFarmer(
+          await newFunded(provider),
+          "n".repeat(MAX_NAME_LEN + 1)
+        );
+        expect.fail("Should have thrown an error for long name");
+      } catch (err) {
+        expect(err.toString()).to.include("DisplayNameTooLong");
+      }
+    });
+
+    it("should reject a URI over MAX_URI_LEN", async () => {
+      try {
+        await registerFarmer(
+          await newFunded(provider),
+          "Green Acres",
+          "https://" + "u".repeat(MAX_URI_LEN)
+        );
+        expect.fail("Should have thrown an error for long URI");
+      } catch (err) {
+        expect(err.toString()).to.include("ProfileUriTooLong");
+      }
+    });
+
+    it("should reject registering twice", async () => {
+      const farmer = await newFunded(provider);
+      await registerFarmer(farmer);
+
+      try {
+        await registerFarmer(farmer);
+        expect.fail("Should have thrown an error for duplicate profile");
+      } catch (err) {
+        expect(err.toString()).to.include("already in use");
+      }
+    });
+
+    it("should fail while onboarding is paused", async () => {
+      const farmer = await newFunded(provider);
+      await setPauseFlags(PAUSE_ONBOARDING);
+
+      try {
+        await registerFarmer(farmer);
+        expect.fail("Should have thrown an error while paused");
+      } catch (err) {
+        expect(err.toString()).to.include("OnboardingPaused");
+      } finally {
+        await setPauseFlags(0);
+      }
+    });
+  });
+});
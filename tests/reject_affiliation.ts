@@ -0,0 +1,238 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+describe("reject_affiliation", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const {
+    getConfigPDA,
+    getWarehousePDA,
+    getFarmerPDA,
+    getAffiliationPDA,
+  } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  // Registered farmer with a funded key
+  const newFarmer = async () => {
+    const farmer = await newFunded(provider);
+    await program.methods
+      .registerFarmer("Green Acres", "")
+      .accounts({
+        config: configPDA,
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        authority: farmer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([farmer])
+      .rpc();
+    return farmer;
+  };
+
+  const createWarehouse = async () => {
+    const warehouseId = new anchor.BN(
+      Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)
+    );
+    const operator = await newFunded(provider);
+    const [warehouse] = getWarehousePDA(warehouseId);
+
+    await program.methods
+      .createWarehouse(warehouseId, operator.publicKey, "Hub", "", 500, [], null)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return { warehouse, operator };
+  };
+
+  const requestAffiliation = (
+    farmer: Keypair,
+    warehouse: PublicKey,
+    signer: Keypair = farmer
+  ) =>
+    program.methods
+      .requestWarehouseAffiliation()
+      .accounts({
+        config: configPDA,
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        warehouse,
+        affiliation: getAffiliationPDA(farmer.publicKey, warehouse)[0],
+        authority: signer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc();
+
+  const approveAffiliation = (
+    farmer: PublicKey,
+    warehouse: PublicKey,
+    operator: Keypair
+  ) =>
+    program.methods
+      .approveAffiliation()
+      .accounts({
+        config: configPDA,
+        affiliation: getAffiliationPDA(farmer, warehouse)[0],
+        warehouse,
+        operator: operator.publicKey,
+      })
+      .signers([operator])
+      .rpc();
+
+  const rejectAffiliation = (
+    farmer: PublicKey,
+    warehouse: PublicKey,
+    operator: Keypair
+  ) =>
+    program.methods
+      .rejectAffiliation()
+      .accounts({
+        affiliation: getAffiliationPDA(farmer, warehouse)[0],
+        warehouse,
+        operator: operator.publicKey,
+      })
+      .signers([operator])
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should reject a pending request", async () => {
+      const farmer = await newFarmer();
+      const { warehouse, operator } = await createWarehouse();
+      await requestAffiliation(farmer, warehouse);
+
+      await rejectAffiliation(farmer.publicKey, warehouse, operator);
+
+      const affiliation = await program.account.farmerAffiliation.fetch(
+        getAffiliationPDA(farmer.publicKey, warehouse)[0]
+      );
+      expect(affiliation.status).to.deep.equal({ rejected: {} });
+      expect(affiliation.decidedAt.toNumber()).to.be.greaterThan(0);
+    });
+
+    it("should leave the farmer's other affiliations alone", async () => {
+      const farmer = await newFarmer();
+      const first = await createWarehouse();
+      const second = await createWarehouse();
+      await requestAffiliation(farmer, first.warehouse);
+      await approveAffiliation(
+        farmer.publicKey,
+        first.warehouse,
+        first.operator
+      );
+      await requestAffiliation(farmer, second.warehouse);
+
+      await rejectAffiliation(
+        farmer.publicKey,
+        second.warehouse,
+        second.operator
+      );
+
+      const affiliation = await program.account.farmerAffiliation.fetch(
+        getAffiliationPDA(farmer.publicKey, first.warehouse)[0]
+      );
+      expect(affiliation.status).to.deep.equal({ approved: {} });
+    });
+
+    it("should let the farmer request again after a rejection", async () => {
+      const farmer = await newFarmer();
+      const { warehouse, operator } = await createWarehouse();
+      await requestAffiliation(farmer, warehouse);
+      await rejectAffiliation(farmer.publicKey, warehouse, operator);
+
+      await requestAffiliation(farmer, warehouse);
+
+      const affiliation = await program.account.farmerAffiliation.fetch(
+        getAffiliationPDA(farmer.publicKey, warehouse)[0]
+      );
+      expect(affiliation.status).to.deep.equal({ pending: {} });
+      expect(affiliation.decidedAt.toNumber()).to.equal(0);
+    });
+
+    it("should emit AffiliationRejected", async () => {
+      const farmer = await newFarmer();
+      const { warehouse, operator } = await createWarehouse();
+      await requestAffiliation(farmer, warehouse);
+
+      let event: any = null;
+      const listener = program.addEventListener(
+        "affiliationRejected",
+        (e) => {
+          event = e;
+        }
+      );
+
+      await rejectAffiliation(farmer.publicKey, warehouse, operator);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.farmer.toString()).to.equal(farmer.publicKey.toString());
+      expect(event.operator.toString()).to.equal(
+        operator.publicKey.toString()
+      );
+      expect(event.warehouse.toString()).to.equal(warehouse.toString());
+    });
+  });
+
+  describe("error cases", () => {
+    it("should fail when signer is not the operator", async () => {
+      const farmer = await newFarmer();
+      const { warehouse } = await createWarehouse();
+      await requestAffiliation(farmer, warehouse);
+
+      try {
+        await rejectAffiliation(farmer.publicKey, warehouse, farmer);
+        expect.fail("Should have thrown an error for a non-operator");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedOperator");
+      }
+    });
+
+    it("should fail for a request that is no longer pending", async () => {
+      const farmer = await newFarmer();
+      const { warehouse, operator } = await createWarehouse();
+      await requestAffiliation(farmer, warehouse);
+      await approveAffiliation(farmer.publicKey, warehouse, operator);
+
+      try {
+        await rejectAffiliation(farmer.publicKey, warehouse, operator);
+        expect.fail("Should have thrown an error for an approved request");
+      } catch (err) {
+        expect(err.toString()).to.include("AffiliationNotPending");
+      }
+    });
+  });
+});
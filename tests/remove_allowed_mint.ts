@@ -0,0 +1,161 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { createMint } from "./helpers/token";
+import { pdaHelpers } from "./helpers/pda";
+
+describe("remove_allowed_mint", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const { getConfigPDA, getAllowedMintPDA } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  const addAllowedMint = (mint: PublicKey) =>
+    program.methods
+      .addAllowedMint(new anchor.BN(0), new anchor.BN(1_000_000_000), null)
+      .accounts({
+        config: configPDA,
+        allowedMint: getAllowedMintPDA(mint)[0],
+        mint,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+  const removeAllowedMint = (mint: PublicKey) =>
+    program.methods
+      .removeAllowedMint()
+      .accounts({
+        config: configPDA,
+        allowedMint: getAllowedMintPDA(mint)[0],
+        admin: admin.publicKey,
+      })
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should close only the given entry", async () => {
+      const keep = await createMint(provider, 6);
+      const drop = await createMint(provider, 6);
+      await addAllowedMint(keep);
+      await addAllowedMint(drop);
+
+      await removeAllowedMint(drop);
+
+      const kept = await program.account.allowedMint.fetchNullable(
+        getAllowedMintPDA(keep)[0]
+      );
+      const dropped = await program.account.allowedMint.fetchNullable(
+        getAllowedMintPDA(drop)[0]
+      );
+      expect(kept).to.not.be.null;
+      expect(dropped).to.be.null;
+    });
+
+    it("should return the rent to the admin", async () => {
+      const mint = await createMint(provider, 6);
+      await addAllowedMint(mint);
+
+      const [allowedMintPDA] = getAllowedMintPDA(mint);
+      const rent = await provider.connection.getBalance(allowedMintPDA);
+      const before = await provider.connection.getBalance(admin.publicKey);
+
+      await removeAllowedMint(mint);
+
+      const after = await provider.connection.getBalance(admin.publicKey);
+      // Admin also paid the transaction fee
+      expect(after).to.be.greaterThan(before + rent - 10_000);
+    });
+
+    it("should allow re-adding a removed mint", async () => {
+      const mint = await createMint(provider, 6);
+      await addAllowedMint(mint);
+      await removeAllowedMint(mint);
+
+      await addAllowedMint(mint);
+
+      const entry = await program.account.allowedMint.fetch(
+        getAllowedMintPDA(mint)[0]
+      );
+      expect(entry.mint.toString()).to.equal(mint.toString());
+    });
+
+    it("should emit AllowedMintRemoved", async () => {
+      const mint = await createMint(provider, 6);
+      await addAllowedMint(mint);
+
+      let event: any = null;
+      const listener = program.addEventListener("allowedMintRemoved", (e) => {
+        event = e;
+      });
+
+      await removeAllowedMint(mint);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.mint.toString()).to.equal(mint.toString());
+    });
+  });
+
+  describe("error cases", () => {
+    it("should reject a mint that is not in the allowlist", async () => {
+      const mint = await createMint(provider, 6);
+
+      try {
+        await removeAllowedMint(mint);
+        expect.fail("Should have thrown an error for missing mint");
+      } catch (err) {
+        expect(err.toString()).to.include("AccountNotInitialized");
+      }
+    });
+
+    it("should fail when signer is not the admin", async () => {
+      const attacker = Keypair.generate();
+      const mint = await createMint(provider, 6);
+      await addAllowedMint(mint);
+
+      try {
+        await program.methods
+          .removeAllowedMint()
+          .accounts({
+            config: configPDA,
+            allowedMint: getAllowedMintPDA(mint)[0],
+            admin: attacker.publicKey,
+          })
+          .signers([attacker])
+          .rpc();
+
+        expect.fail("Should have thrown an error for unauthorized admin");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedAdmin");
+      }
+    });
+  });
+});
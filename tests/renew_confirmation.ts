@@ -0,0 +1,468 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+// Mirrors the ROLE_* constants in states.rs
+const ROLES = {
+  confirmCustomers: 1 << 0,
+  quote: 1 << 1,
+  dispatch: 1 << 2,
+  complete: 1 << 3,
+  refund: 1 << 4,
+  manage: 1 << 5,
+};
+
+const PAUSE_ONBOARDING = 1 << 3;
+const CLOSED = { closed: {} };
+
+describe("renew_confirmation", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const {
+    getConfigPDA,
+    getWarehousePDA,
+    getStaffPDA,
+    getCustomerPDA,
+    getWarehouseCustomerPDA,
+  } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  const registerCustomer = (customer: Keypair, publicProfileUri = "") =>
+    program.methods
+      .registerCustomer(publicProfileUri)
+      .accounts({
+        config: configPDA,
+        customerProfile: getCustomerPDA(customer.publicKey)[0],
+        authority: customer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customer])
+      .rpc();
+
+  // Registered customer with a funded key
+  const newCustomer = async () => {
+    const customer = await newFunded(provider);
+    await registerCustomer(customer);
+    return customer;
+  };
+
+  const createWarehouse = async () => {
+    const warehouseId = new anchor.BN(
+      Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)
+    );
+    const operator = await newFunded(provider);
+    const [warehouse] = getWarehousePDA(warehouseId);
+
+    await program.methods
+      .createWarehouse(warehouseId, operator.publicKey, "Hub", "", 500, [], null)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return { warehouse, operator };
+  };
+
+  const requestConfirmation = (customer: Keypair, warehouse: PublicKey) =>
+    program.methods
+      .requestCustomerConfirmation(null)
+      .accounts({
+        config: configPDA,
+        customerProfile: getCustomerPDA(customer.publicKey)[0],
+        warehouse,
+        warehouseCustomer: getWarehouseCustomerPDA(
+          warehouse,
+          customer.publicKey
+        )[0],
+        authority: customer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customer])
+      .rpc();
+
+  const confirmCustomer = (
+    warehouse: PublicKey,
+    customer: PublicKey,
+    signer: Keypair,
+    notesHash: number[] | null = null,
+    asStaff = false,
+    validUntil: number | null = null
+  ) =>
+    program.methods
+      .confirmCustomer(
+        notesHash,
+        validUntil !== null ? new anchor.BN(validUntil) : null,
+        null
+      )
+      .accounts({
+        config: configPDA,
+        warehouse,
+        staff: asStaff ? getStaffPDA(warehouse, signer.publicKey)[0] : null,
+        warehouseCustomer: getWarehouseCustomerPDA(warehouse, customer)[0],
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const renewConfirmation = (
+    warehouse: PublicKey,
+    customer: PublicKey,
+    signer: Keypair,
+    notesHash: number[] | null = null,
+    validUntil: number | null = null,
+    asStaff = false
+  ) =>
+    program.methods
+      .renewConfirmation(
+        notesHash,
+        validUntil !== null ? new anchor.BN(validUntil) : null
+      )
+      .accounts({
+        config: configPDA,
+        warehouse,
+        staff: asStaff ? getStaffPDA(warehouse, signer.publicKey)[0] : null,
+        warehouseCustomer: getWarehouseCustomerPDA(warehouse, customer)[0],
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const revokeCustomer = (
+    warehouse: PublicKey,
+    customer: PublicKey,
+    signer: Keypair,
+    asStaff = false
+  ) =>
+    program.methods
+      .revokeCustomer()
+      .accounts({
+        warehouse,
+        staff: asStaff ? getStaffPDA(warehouse, signer.publicKey)[0] : null,
+        warehouseCustomer: getWarehouseCustomerPDA(warehouse, customer)[0],
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const grant = (
+    warehouse: PublicKey,
+    operator: Keypair,
+    member: PublicKey,
+    roles: number
+  ) =>
+    program.methods
+      .grantStaffRoles(member, roles)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        staff: getStaffPDA(warehouse, member)[0],
+        authority: operator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([operator])
+      .rpc();
+
+  const setPauseFlags = (pauseFlags: number) =>
+    program.methods
+      .setPauseFlags(pauseFlags)
+      .accounts({ config: configPDA, admin: admin.publicKey })
+      .rpc();
+
+  const setStatus = (warehouse: PublicKey, status: object) =>
+    program.methods
+      .setWarehouseStatus(status as any)
+      .accounts({ config: configPDA, warehouse, admin: admin.publicKey })
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  // Sets the warehouse's default confirmation lifetime, in seconds
+  const setValidity = (
+    warehouse: PublicKey,
+    operator: Keypair,
+    seconds: number
+  ) =>
+    program.methods
+      .updateWarehouse(
+        null,
+        null,
+        null,
+        null,
+        null,
+        null,
+        null,
+        new anchor.BN(seconds),
+        null
+      )
+      .accounts({
+        config: configPDA,
+        warehouse,
+        staff: null,
+        authority: operator.publicKey,
+      })
+      .signers([operator])
+      .rpc();
+
+  const chainTime = async () =>
+    provider.connection.getBlockTime(await provider.connection.getSlot());
+
+  // Customer confirmed at a fresh warehouse until `validUntil` (default: never)
+  const confirmedCustomer = async (validUntil: number | null = null) => {
+    const customer = await newCustomer();
+    const { warehouse, operator } = await createWarehouse();
+    await requestConfirmation(customer, warehouse);
+    await confirmCustomer(
+      warehouse,
+      customer.publicKey,
+      operator,
+      [...Buffer.alloc(32, 1)],
+      false,
+      validUntil
+    );
+    return { customer, warehouse, operator };
+  };
+
+  // Waits until the cluster clock reaches `timestamp`
+  const waitUntil = async (timestamp: number) => {
+    while ((await chainTime()) < timestamp) {
+      await new Promise((resolve) => setTimeout(resolve, 500));
+    }
+  };
+
+  describe("success cases", () => {
+    it("should renew an expired confirmation", async () => {
+      const validUntil = (await chainTime()) + 2;
+      const { customer, warehouse, operator } = await confirmedCustomer(
+        validUntil
+      );
+      await waitUntil(validUntil);
+      const renewedUntil = (await chainTime()) + 365 * 86_400;
+
+      await renewConfirmation(
+        warehouse,
+        customer.publicKey,
+        operator,
+        null,
+        renewedUntil
+      );
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.status).to.deep.equal({ confirmed: {} });
+      expect(entry.validUntil.toNumber()).to.equal(renewedUntil);
+      expect(entry.confirmedAt.toNumber()).to.be.at.least(validUntil);
+      expect(entry.notesHash).to.deep.equal([...Buffer.alloc(32, 1)]);
+    });
+
+    it("should apply the warehouse default and replace the notes hash", async () => {
+      const { customer, warehouse, operator } = await confirmedCustomer();
+      const year = 365 * 86_400;
+      await setValidity(warehouse, operator, year);
+      const notesHash = [...Buffer.alloc(32, 2)];
+
+      await renewConfirmation(
+        warehouse,
+        customer.publicKey,
+        operator,
+        notesHash
+      );
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.validUntil.toNumber()).to.equal(
+        entry.confirmedAt.toNumber() + year
+      );
+      expect(entry.notesHash).to.deep.equal(notesHash);
+    });
+
+    it("should let staff with the confirm-customers role renew", async () => {
+      const { customer, warehouse, operator } = await confirmedCustomer();
+      const clerk = await newFunded(provider);
+      await grant(warehouse, operator, clerk.publicKey, ROLES.confirmCustomers);
+      const renewedUntil = (await chainTime()) + 86_400;
+
+      await renewConfirmation(
+        warehouse,
+        customer.publicKey,
+        clerk,
+        null,
+        renewedUntil,
+        true
+      );
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.validUntil.toNumber()).to.equal(renewedUntil);
+    });
+
+    it("should emit CustomerConfirmationRenewed", async () => {
+      const oldValidUntil = (await chainTime()) + 3_600;
+      const { customer, warehouse, operator } = await confirmedCustomer(
+        oldValidUntil
+      );
+      const newValidUntil = oldValidUntil + 86_400;
+
+      let event: any = null;
+      const listener = program.addEventListener(
+        "customerConfirmationRenewed",
+        (e) => {
+          event = e;
+        }
+      );
+
+      await renewConfirmation(
+        warehouse,
+        customer.publicKey,
+        operator,
+        null,
+        newValidUntil
+      );
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.warehouseCustomer.toString()).to.equal(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0].toString()
+      );
+      expect(event.customer.toString()).to.equal(customer.publicKey.toString());
+      expect(event.authority.toString()).to.equal(
+        operator.publicKey.toString()
+      );
+      expect(event.notesHash).to.deep.equal([...Buffer.alloc(32, 1)]);
+      expect(event.oldValidUntil.toNumber()).to.equal(oldValidUntil);
+      expect(event.newValidUntil.toNumber()).to.equal(newValidUntil);
+    });
+  });
+
+  describe("error cases", () => {
+    it("should fail when signer is neither operator nor staff", async () => {
+      const { customer, warehouse } = await confirmedCustomer();
+
+      try {
+        await renewConfirmation(warehouse, customer.publicKey, customer);
+        expect.fail("Should have thrown an error for the customer");
+      } catch (err) {
+        expect(err.toString()).to.include("MissingStaffRole");
+      }
+    });
+
+    it("should reject staff without the confirm-customers role", async () => {
+      const { customer, warehouse, operator } = await confirmedCustomer();
+      const member = await newFunded(provider);
+      await grant(warehouse, operator, member.publicKey, ROLES.quote);
+
+      try {
+        await renewConfirmation(
+          warehouse,
+          customer.publicKey,
+          member,
+          null,
+          null,
+          true
+        );
+        expect.fail("Should have thrown an error for the quote role");
+      } catch (err) {
+        expect(err.toString()).to.include("MissingStaffRole");
+      }
+    });
+
+    it("should fail for a pending or revoked customer", async () => {
+      const customer = await newCustomer();
+      const { warehouse, operator } = await createWarehouse();
+      await requestConfirmation(customer, warehouse);
+
+      try {
+        await renewConfirmation(warehouse, customer.publicKey, operator);
+        expect.fail("Should have thrown an error for a pending customer");
+      } catch (err) {
+        expect(err.toString()).to.include("CustomerNotConfirmed");
+      }
+
+      await revokeCustomer(warehouse, customer.publicKey, operator);
+
+      try {
+        await renewConfirmation(warehouse, customer.publicKey, operator);
+        expect.fail("Should have thrown an error for a revoked customer");
+      } catch (err) {
+        expect(err.toString()).to.include("CustomerNotConfirmed");
+      }
+    });
+
+    it("should reject an expiry that is not in the future", async () => {
+      const { customer, warehouse, operator } = await confirmedCustomer();
+      const past = (await chainTime()) - 60;
+
+      try {
+        await renewConfirmation(
+          warehouse,
+          customer.publicKey,
+          operator,
+          null,
+          past
+        );
+        expect.fail("Should have thrown an error for a past expiry");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidConfirmationExpiry");
+      }
+    });
+
+    it("should fail once the warehouse is closed", async () => {
+      const { customer, warehouse, operator } = await confirmedCustomer();
+      await setStatus(warehouse, CLOSED);
+
+      try {
+        await renewConfirmation(warehouse, customer.publicKey, operator);
+        expect.fail("Should have thrown an error for a closed warehouse");
+      } catch (err) {
+        expect(err.toString()).to.include("WarehouseClosed");
+      }
+    });
+
+    it("should fail while onboarding is paused", async () => {
+      const { customer, warehouse, operator } = await confirmedCustomer();
+      await setPauseFlags(PAUSE_ONBOARDING);
+
+      try {
+        await renewConfirmation(warehouse, customer.publicKey, operator);
+        expect.fail("Should have thrown an error while paused");
+      } catch (err) {
+        expect(err.toString()).to.include("OnboardingPaused");
+      } finally {
+        await setPauseFlags(0);
+      }
+    });
+  });
+});
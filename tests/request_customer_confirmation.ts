@@ -0,0 +1,487 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+const PAUSE_ONBOARDING = 1 << 3;
+const SUSPENDED = { suspended: {} };
+const CLOSED = { closed: {} };
+
+describe("request_customer_confirmation", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const {
+    getConfigPDA,
+    getWarehousePDA,
+    getCustomerPDA,
+    getWarehouseCustomerPDA,
+  } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  const registerCustomer = (customer: Keypair, publicProfileUri = "") =>
+    program.methods
+      .registerCustomer(publicProfileUri)
+      .accounts({
+        config: configPDA,
+        customerProfile: getCustomerPDA(customer.publicKey)[0],
+        authority: customer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customer])
+      .rpc();
+
+  // Registered customer with a funded key
+  const newCustomer = async () => {
+    const customer = await newFunded(provider);
+    await registerCustomer(customer);
+    return customer;
+  };
+
+  const createWarehouse = async () => {
+    const warehouseId = new anchor.BN(
+      Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)
+    );
+    const operator = await newFunded(provider);
+    const [warehouse] = getWarehousePDA(warehouseId);
+
+    await program.methods
+      .createWarehouse(warehouseId, operator.publicKey, "Hub", "", 500, [], null)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return { warehouse, operator };
+  };
+
+  const requestConfirmation = (
+    customer: Keypair,
+    warehouse: PublicKey,
+    addressEnvelope: object | null = null
+  ) =>
+    program.methods
+      .requestCustomerConfirmation(addressEnvelope as any)
+      .accounts({
+        config: configPDA,
+        customerProfile: getCustomerPDA(customer.publicKey)[0],
+        warehouse,
+        warehouseCustomer: getWarehouseCustomerPDA(
+          warehouse,
+          customer.publicKey
+        )[0],
+        authority: customer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customer])
+      .rpc();
+
+  const confirmCustomer = (
+    warehouse: PublicKey,
+    customer: PublicKey,
+    signer: Keypair,
+    notesHash: number[] | null = null,
+    asStaff = false,
+    validUntil: number | null = null
+  ) =>
+    program.methods
+      .confirmCustomer(
+        notesHash,
+        validUntil !== null ? new anchor.BN(validUntil) : null,
+        null
+      )
+      .accounts({
+        config: configPDA,
+        warehouse,
+        staff: asStaff ? getStaffPDA(warehouse, signer.publicKey)[0] : null,
+        warehouseCustomer: getWarehouseCustomerPDA(warehouse, customer)[0],
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const revokeCustomer = (
+    warehouse: PublicKey,
+    customer: PublicKey,
+    signer: Keypair,
+    asStaff = false
+  ) =>
+    program.methods
+      .revokeCustomer()
+      .accounts({
+        warehouse,
+        staff: asStaff ? getStaffPDA(warehouse, signer.publicKey)[0] : null,
+        warehouseCustomer: getWarehouseCustomerPDA(warehouse, customer)[0],
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const setPauseFlags = (pauseFlags: number) =>
+    program.methods
+      .setPauseFlags(pauseFlags)
+      .accounts({ config: configPDA, admin: admin.publicKey })
+      .rpc();
+
+  const setStatus = (warehouse: PublicKey, status: object) =>
+    program.methods
+      .setWarehouseStatus(status as any)
+      .accounts({ config: configPDA, warehouse, admin: admin.publicKey })
+      .rpc();
+
+  const setEncryptionKey = (
+    warehouse: PublicKey,
+    operator: Keypair,
+    key: number[]
+  ) =>
+    program.methods
+      .updateWarehouse(null, null, null, null, null, null, null, null, key)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        staff: null,
+        authority: operator.publicKey,
+      })
+      .signers([operator])
+      .rpc();
+
+  const ENCRYPTION_KEY = [...Buffer.alloc(32, 5)];
+
+  // Pointer to an address sealed to `encryptionKey`; the ciphertext itself
+  // never reaches the program
+  const envelope = (
+    encryptionKey = ENCRYPTION_KEY,
+    uri = "ipfs://bafyaddress"
+  ) => ({
+    uri,
+    ciphertextHash: [...Buffer.alloc(32, 9)],
+    encryptionKey,
+  });
+
+  const chainTime = async () =>
+    provider.connection.getBlockTime(await provider.connection.getSlot());
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should create a pending entry", async () => {
+      const customer = await newCustomer();
+      const { warehouse } = await createWarehouse();
+      const [entryPDA, bump] = getWarehouseCustomerPDA(
+        warehouse,
+        customer.publicKey
+      );
+
+      await requestConfirmation(customer, warehouse);
+
+      const entry = await program.account.warehouseCustomer.fetch(entryPDA);
+      expect(entry.warehouse.toString()).to.equal(warehouse.toString());
+      expect(entry.customer.toString()).to.equal(customer.publicKey.toString());
+      expect(entry.status).to.deep.equal({ pending: {} });
+      expect(entry.requestedAt.toNumber()).to.be.greaterThan(0);
+      expect(entry.confirmedAt).to.be.null;
+      expect(entry.notesHash).to.be.null;
+      expect(entry.addressEnvelope).to.be.null;
+      expect(entry.openOrders).to.equal(0);
+      expect(entry.bump).to.equal(bump);
+
+      const profile = await program.account.customerProfile.fetch(
+        getCustomerPDA(customer.publicKey)[0]
+      );
+      expect(profile.linkedWarehouses).to.equal(1);
+    });
+
+    it("should store an address envelope sealed to the warehouse key", async () => {
+      const customer = await newCustomer();
+      const { warehouse, operator } = await createWarehouse();
+      await setEncryptionKey(warehouse, operator, ENCRYPTION_KEY);
+
+      await requestConfirmation(customer, warehouse, envelope());
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.status).to.deep.equal({ pending: {} });
+      expect(entry.addressEnvelope).to.deep.equal(envelope());
+    });
+
+    it("should replace the envelope when requesting again", async () => {
+      const customer = await newCustomer();
+      const { warehouse, operator } = await createWarehouse();
+      await setEncryptionKey(warehouse, operator, ENCRYPTION_KEY);
+      await requestConfirmation(customer, warehouse, envelope());
+      await revokeCustomer(warehouse, customer.publicKey, operator);
+
+      await requestConfirmation(customer, warehouse);
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.addressEnvelope).to.be.null;
+    });
+
+    it("should let one customer request several warehouses", async () => {
+      const customer = await newCustomer();
+      const first = await createWarehouse();
+      const second = await createWarehouse();
+
+      await requestConfirmation(customer, first.warehouse);
+      await requestConfirmation(customer, second.warehouse);
+
+      for (const { warehouse } of [first, second]) {
+        const entry = await program.account.warehouseCustomer.fetch(
+          getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+        );
+        expect(entry.status).to.deep.equal({ pending: {} });
+      }
+    });
+
+    it("should request again after a revocation", async () => {
+      const customer = await newCustomer();
+      const { warehouse, operator } = await createWarehouse();
+      await requestConfirmation(customer, warehouse);
+      await confirmCustomer(warehouse, customer.publicKey, operator, [
+        ...Buffer.alloc(32, 7),
+      ]);
+      await revokeCustomer(warehouse, customer.publicKey, operator);
+
+      await requestConfirmation(customer, warehouse);
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.status).to.deep.equal({ pending: {} });
+      expect(entry.confirmedAt).to.be.null;
+      expect(entry.notesHash).to.be.null;
+
+      // Same entry, so it is still counted once
+      const profile = await program.account.customerProfile.fetch(
+        getCustomerPDA(customer.publicKey)[0]
+      );
+      expect(profile.linkedWarehouses).to.equal(1);
+    });
+
+    it("should request again once the confirmation has expired", async () => {
+      const customer = await newCustomer();
+      const { warehouse, operator } = await createWarehouse();
+      await requestConfirmation(customer, warehouse);
+      const validUntil = (await chainTime()) + 2;
+      await confirmCustomer(
+        warehouse,
+        customer.publicKey,
+        operator,
+        null,
+        false,
+        validUntil
+      );
+      while ((await chainTime()) < validUntil) {
+        await new Promise((resolve) => setTimeout(resolve, 500));
+      }
+
+      await requestConfirmation(customer, warehouse);
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.status).to.deep.equal({ pending: {} });
+      expect(entry.validUntil).to.be.null;
+    });
+
+    it("should allow a suspended warehouse", async () => {
+      const customer = await newCustomer();
+      const { warehouse } = await createWarehouse();
+      await setStatus(warehouse, SUSPENDED);
+
+      await requestConfirmation(customer, warehouse);
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.status).to.deep.equal({ pending: {} });
+    });
+
+    it("should emit CustomerConfirmationRequested", async () => {
+      const customer = await newCustomer();
+      const { warehouse } = await createWarehouse();
+
+      let event: any = null;
+      const listener = program.addEventListener(
+        "customerConfirmationRequested",
+        (e) => {
+          event = e;
+        }
+      );
+
+      await requestConfirmation(customer, warehouse);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.warehouseCustomer.toString()).to.equal(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0].toString()
+      );
+      expect(event.warehouse.toString()).to.equal(warehouse.toString());
+      expect(event.customer.toString()).to.equal(customer.publicKey.toString());
+      expect(event.addressEnvelope).to.be.null;
+    });
+  });
+
+  describe("error cases", () => {
+    it("should fail without a customer profile", async () => {
+      const stranger = await newFunded(provider);
+      const { warehouse } = await createWarehouse();
+
+      try {
+        await requestConfirmation(stranger, warehouse);
+        expect.fail("Should have thrown an error without a profile");
+      } catch (err) {
+        expect(err.toString()).to.include("AccountNotInitialized");
+      }
+    });
+
+    it("should fail while a request is pending", async () => {
+      const customer = await newCustomer();
+      const { warehouse } = await createWarehouse();
+      await requestConfirmation(customer, warehouse);
+
+      try {
+        await requestConfirmation(customer, warehouse);
+        expect.fail("Should have thrown an error for a pending request");
+      } catch (err) {
+        expect(err.toString()).to.include("ConfirmationAlreadyPending");
+      }
+    });
+
+    it("should fail once confirmed", async () => {
+      const customer = await newCustomer();
+      const { warehouse, operator } = await createWarehouse();
+      await requestConfirmation(customer, warehouse);
+      await confirmCustomer(warehouse, customer.publicKey, operator);
+
+      try {
+        await requestConfirmation(customer, warehouse);
+        expect.fail("Should have thrown an error for a confirmed customer");
+      } catch (err) {
+        expect(err.toString()).to.include("AlreadyConfirmed");
+      }
+    });
+
+    it("should fail once the warehouse is closed", async () => {
+      const customer = await newCustomer();
+      const { warehouse } = await createWarehouse();
+      await setStatus(warehouse, CLOSED);
+
+      try {
+        await requestConfirmation(customer, warehouse);
+        expect.fail("Should have thrown an error for a closed warehouse");
+      } catch (err) {
+        expect(err.toString()).to.include("WarehouseClosed");
+      }
+    });
+
+    it("should fail with an envelope when the warehouse has no key", async () => {
+      const customer = await newCustomer();
+      const { warehouse } = await createWarehouse();
+
+      try {
+        await requestConfirmation(customer, warehouse, envelope());
+        expect.fail("Should have thrown an error without an encryption key");
+      } catch (err) {
+        expect(err.toString()).to.include("EncryptionKeyNotSet");
+      }
+    });
+
+    it("should fail with an envelope sealed to a rotated key", async () => {
+      const customer = await newCustomer();
+      const { warehouse, operator } = await createWarehouse();
+      await setEncryptionKey(warehouse, operator, ENCRYPTION_KEY);
+      await setEncryptionKey(warehouse, operator, [...Buffer.alloc(32, 6)]);
+
+      try {
+        await requestConfirmation(customer, warehouse, envelope());
+        expect.fail("Should have thrown an error for the old key");
+      } catch (err) {
+        expect(err.toString()).to.include("EnvelopeKeyMismatch");
+      }
+    });
+
+    it("should fail with an envelope URI outside the allowed schemes", async () => {
+      const customer = await newCustomer();
+      const { warehouse, operator } = await createWarehouse();
+      await setEncryptionKey(warehouse, operator, ENCRYPTION_KEY);
+
+      for (const uri of ["", "http://example.com/address"]) {
+        try {
+          await requestConfirmation(
+            customer,
+            warehouse,
+            envelope(ENCRYPTION_KEY, uri)
+          );
+          expect.fail("Should have thrown an error for the URI");
+        } catch (err) {
+          expect(err.toString()).to.include("InvalidEnvelopeUri");
+        }
+      }
+    });
+
+    it("should fail with an envelope URI over 200 bytes", async () => {
+      const customer = await newCustomer();
+      const { warehouse, operator } = await createWarehouse();
+      await setEncryptionKey(warehouse, operator, ENCRYPTION_KEY);
+
+      try {
+        await requestConfirmation(
+          customer,
+          warehouse,
+          envelope(ENCRYPTION_KEY, "https://" + "u".repeat(200))
+        );
+        expect.fail("Should have thrown an error for a long URI");
+      } catch (err) {
+        expect(err.toString()).to.include("EnvelopeUriTooLong");
+      }
+    });
+
+    it("should fail while onboarding is paused", async () => {
+      const customer = await newCustomer();
+      const { warehouse } = await createWarehouse();
+      await setPauseFlags(PAUSE_ONBOARDING);
+
+      try {
+        await requestConfirmation(customer, warehouse);
+        expect.fail("Should have thrown an error while paused");
+      } catch (err) {
+        expect(err.toString()).to.include("OnboardingPaused");
+      } finally {
+        await setPauseFlags(0);
+      }
+    });
+  });
+});
@@ -0,0 +1,301 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+const PAUSE_ONBOARDING = 1 << 3;
+const SUSPENDED = { suspended: {} };
+const CLOSED = { closed: {} };
+
+describe("request_warehouse_affiliation", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const {
+    getConfigPDA,
+    getWarehousePDA,
+    getFarmerPDA,
+    getAffiliationPDA,
+  } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  // Registered farmer with a funded key
+  const newFarmer = async () => {
+    const farmer = await newFunded(provider);
+    await program.methods
+      .registerFarmer("Green Acres", "")
+      .accounts({
+        config: configPDA,
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        authority: farmer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([farmer])
+      .rpc();
+    return farmer;
+  };
+
+  const createWarehouse = async () => {
+    const warehouseId = new anchor.BN(
+      Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)
+    );
+    const operator = await newFunded(provider);
+    const [warehouse] = getWarehousePDA(warehouseId);
+
+    await program.methods
+      .createWarehouse(warehouseId, operator.publicKey, "Hub", "", 500, [], null)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return { warehouse, operator };
+  };
+
+  const requestAffiliation = (
+    farmer: Keypair,
+    warehouse: PublicKey,
+    signer: Keypair = farmer
+  ) =>
+    program.methods
+      .requestWarehouseAffiliation()
+      .accounts({
+        config: configPDA,
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        warehouse,
+        affiliation: getAffiliationPDA(farmer.publicKey, warehouse)[0],
+        authority: signer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc();
+
+  const approveAffiliation = (
+    farmer: PublicKey,
+    warehouse: PublicKey,
+    operator: Keypair
+  ) =>
+    program.methods
+      .approveAffiliation()
+      .accounts({
+        config: configPDA,
+        affiliation: getAffiliationPDA(farmer, warehouse)[0],
+        warehouse,
+        operator: operator.publicKey,
+      })
+      .signers([operator])
+      .rpc();
+
+  const setPauseFlags = (pauseFlags: number) =>
+    program.methods
+      .setPauseFlags(pauseFlags)
+      .accounts({ config: configPDA, admin: admin.publicKey })
+      .rpc();
+
+  const setStatus = (warehouse: PublicKey, status: object) =>
+    program.methods
+      .setWarehouseStatus(status as any)
+      .accounts({ config: configPDA, warehouse, admin: admin.publicKey })
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should create a pending affiliation", async () => {
+      const farmer = await newFarmer();
+      const { warehouse } = await createWarehouse();
+
+      await requestAffiliation(farmer, warehouse);
+
+      const [affiliationPDA, bump] = getAffiliationPDA(
+        farmer.publicKey,
+        warehouse
+      );
+      const affiliation = await program.account.farmerAffiliation.fetch(
+        affiliationPDA
+      );
+      expect(affiliation.warehouse.toString()).to.equal(warehouse.toString());
+      expect(affiliation.farmer.toString()).to.equal(
+        farmer.publicKey.toString()
+      );
+      expect(affiliation.status).to.deep.equal({ pending: {} });
+      expect(affiliation.requestedAt.toNumber()).to.be.greaterThan(0);
+      expect(affiliation.decidedAt.toNumber()).to.equal(0);
+      expect(affiliation.activeOffers).to.equal(0);
+      expect(affiliation.bump).to.equal(bump);
+    });
+
+    it("should let a farmer be affiliated with several warehouses", async () => {
+      const farmer = await newFarmer();
+      const first = await createWarehouse();
+      const second = await createWarehouse();
+
+      await requestAffiliation(farmer, first.warehouse);
+      await requestAffiliation(farmer, second.warehouse);
+      await approveAffiliation(
+        farmer.publicKey,
+        first.warehouse,
+        first.operator
+      );
+      await approveAffiliation(
+        farmer.publicKey,
+        second.warehouse,
+        second.operator
+      );
+
+      for (const { warehouse } of [first, second]) {
+        const affiliation = await program.account.farmerAffiliation.fetch(
+          getAffiliationPDA(farmer.publicKey, warehouse)[0]
+        );
+        expect(affiliation.status).to.deep.equal({ approved: {} });
+      }
+    });
+
+    it("should refresh a pending request", async () => {
+      const farmer = await newFarmer();
+      const { warehouse } = await createWarehouse();
+
+      await requestAffiliation(farmer, warehouse);
+      await requestAffiliation(farmer, warehouse);
+
+      const affiliation = await program.account.farmerAffiliation.fetch(
+        getAffiliationPDA(farmer.publicKey, warehouse)[0]
+      );
+      expect(affiliation.status).to.deep.equal({ pending: {} });
+    });
+
+    it("should allow a suspended warehouse", async () => {
+      const farmer = await newFarmer();
+      const { warehouse } = await createWarehouse();
+      await setStatus(warehouse, SUSPENDED);
+
+      await requestAffiliation(farmer, warehouse);
+
+      const affiliation = await program.account.farmerAffiliation.fetch(
+        getAffiliationPDA(farmer.publicKey, warehouse)[0]
+      );
+      expect(affiliation.status).to.deep.equal({ pending: {} });
+    });
+
+    it("should emit AffiliationRequested", async () => {
+      const farmer = await newFarmer();
+      const { warehouse } = await createWarehouse();
+
+      let event: any = null;
+      const listener = program.addEventListener(
+        "affiliationRequested",
+        (e) => {
+          event = e;
+        }
+      );
+
+      await requestAffiliation(farmer, warehouse);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.affiliation.toString()).to.equal(
+        getAffiliationPDA(farmer.publicKey, warehouse)[0].toString()
+      );
+      expect(event.farmer.toString()).to.equal(farmer.publicKey.toString());
+      expect(event.warehouse.toString()).to.equal(warehouse.toString());
+    });
+  });
+
+  describe("error cases", () => {
+    it("should fail for an unregistered farmer", async () => {
+      const farmer = await newFunded(provider);
+      const { warehouse } = await createWarehouse();
+
+      try {
+        await requestAffiliation(farmer, warehouse);
+        expect.fail("Should have thrown an error for a missing profile");
+      } catch (err) {
+        expect(err.toString()).to.include("AccountNotInitialized");
+      }
+    });
+
+    it("should fail when signer is not the farmer", async () => {
+      const farmer = await newFarmer();
+      const attacker = await newFunded(provider);
+      const { warehouse } = await createWarehouse();
+
+      try {
+        await requestAffiliation(farmer, warehouse, attacker);
+        expect.fail("Should have thrown an error for a foreign signer");
+      } catch (err) {
+        expect(err.toString()).to.include("ConstraintSeeds");
+      }
+    });
+
+    it("should fail for a closed warehouse", async () => {
+      const farmer = await newFarmer();
+      const { warehouse } = await createWarehouse();
+      await setStatus(warehouse, CLOSED);
+
+      try {
+        await requestAffiliation(farmer, warehouse);
+        expect.fail("Should have thrown an error for a closed warehouse");
+      } catch (err) {
+        expect(err.toString()).to.include("WarehouseClosed");
+      }
+    });
+
+    it("should fail while onboarding is paused", async () => {
+      const farmer = await newFarmer();
+      const { warehouse } = await createWarehouse();
+      await setPauseFlags(PAUSE_ONBOARDING);
+
+      try {
+        await requestAffiliation(farmer, warehouse);
+        expect.fail("Should have thrown an error while paused");
+      } catch (err) {
+        expect(err.toString()).to.include("OnboardingPaused");
+      } finally {
+        await setPauseFlags(0);
+      }
+    });
+
+    it("should fail for a warehouse that already approved the farmer", async () => {
+      const farmer = await newFarmer();
+      const { warehouse, operator } = await createWarehouse();
+      await requestAffiliation(farmer, warehouse);
+      await approveAffiliation(farmer.publicKey, warehouse, operator);
+
+      try {
+        await requestAffiliation(farmer, warehouse);
+        expect.fail("Should have thrown an error for an approved affiliation");
+      } catch (err) {
+        expect(err.toString()).to.include("AlreadyAffiliated");
+      }
+    });
+  });
+});
@@ -0,0 +1,307 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+// Mirrors the ROLE_* constants in states.rs
+const ROLES = {
+  confirmCustomers: 1 << 0,
+  quote: 1 << 1,
+  dispatch: 1 << 2,
+  complete: 1 << 3,
+  refund: 1 << 4,
+  manage: 1 << 5,
+};
+
+const PAUSE_ONBOARDING = 1 << 3;
+const CLOSED = { closed: {} };
+
+describe("revoke_customer", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const {
+    getConfigPDA,
+    getWarehousePDA,
+    getStaffPDA,
+    getCustomerPDA,
+    getWarehouseCustomerPDA,
+  } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  const registerCustomer = (customer: Keypair, publicProfileUri = "") =>
+    program.methods
+      .registerCustomer(publicProfileUri)
+      .accounts({
+        config: configPDA,
+        customerProfile: getCustomerPDA(customer.publicKey)[0],
+        authority: customer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customer])
+      .rpc();
+
+  // Registered customer with a funded key
+  const newCustomer = async () => {
+    const customer = await newFunded(provider);
+    await registerCustomer(customer);
+    return customer;
+  };
+
+  const createWarehouse = async () => {
+    const warehouseId = new anchor.BN(
+      Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)
+    );
+    const operator = await newFunded(provider);
+    const [warehouse] = getWarehousePDA(warehouseId);
+
+    await program.methods
+      .createWarehouse(warehouseId, operator.publicKey, "Hub", "", 500, [], null)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return { warehouse, operator };
+  };
+
+  const requestConfirmation = (customer: Keypair, warehouse: PublicKey) =>
+    program.methods
+      .requestCustomerConfirmation(null)
+      .accounts({
+        config: configPDA,
+        customerProfile: getCustomerPDA(customer.publicKey)[0],
+        warehouse,
+        warehouseCustomer: getWarehouseCustomerPDA(
+          warehouse,
+          customer.publicKey
+        )[0],
+        authority: customer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customer])
+      .rpc();
+
+  const confirmCustomer = (
+    warehouse: PublicKey,
+    customer: PublicKey,
+    signer: Keypair,
+    notesHash: number[] | null = null,
+    asStaff = false
+  ) =>
+    program.methods
+      .confirmCustomer(notesHash, null, null)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        staff: asStaff ? getStaffPDA(warehouse, signer.publicKey)[0] : null,
+        warehouseCustomer: getWarehouseCustomerPDA(warehouse, customer)[0],
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const revokeCustomer = (
+    warehouse: PublicKey,
+    customer: PublicKey,
+    signer: Keypair,
+    asStaff = false
+  ) =>
+    program.methods
+      .revokeCustomer()
+      .accounts({
+        warehouse,
+        staff: asStaff ? getStaffPDA(warehouse, signer.publicKey)[0] : null,
+        warehouseCustomer: getWarehouseCustomerPDA(warehouse, customer)[0],
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const grant = (
+    warehouse: PublicKey,
+    operator: Keypair,
+    member: PublicKey,
+    roles: number
+  ) =>
+    program.methods
+      .grantStaffRoles(member, roles)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        staff: getStaffPDA(warehouse, member)[0],
+        authority: operator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([operator])
+      .rpc();
+
+  const setPauseFlags = (pauseFlags: number) =>
+    program.methods
+      .setPauseFlags(pauseFlags)
+      .accounts({ config: configPDA, admin: admin.publicKey })
+      .rpc();
+
+  const setStatus = (warehouse: PublicKey, status: object) =>
+    program.methods
+      .setWarehouseStatus(status as any)
+      .accounts({ config: configPDA, warehouse, admin: admin.publicKey })
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  // Customer confirmed at a fresh warehouse
+  const confirmedCustomer = async () => {
+    const customer = await newCustomer();
+    const { warehouse, operator } = await createWarehouse();
+    await requestConfirmation(customer, warehouse);
+    await confirmCustomer(warehouse, customer.publicKey, operator);
+    return { customer, warehouse, operator };
+  };
+
+  describe("success cases", () => {
+    it("should revoke a confirmed customer", async () => {
+      const { customer, warehouse, operator } = await confirmedCustomer();
+
+      await revokeCustomer(warehouse, customer.publicKey, operator);
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.status).to.deep.equal({ revoked: {} });
+    });
+
+    it("should decline a pending request", async () => {
+      const customer = await newCustomer();
+      const { warehouse, operator } = await createWarehouse();
+      await requestConfirmation(customer, warehouse);
+
+      await revokeCustomer(warehouse, customer.publicKey, operator);
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.status).to.deep.equal({ revoked: {} });
+      expect(entry.confirmedAt).to.be.null;
+    });
+
+    it("should revoke even when the warehouse is closed and onboarding paused", async () => {
+      const { customer, warehouse, operator } = await confirmedCustomer();
+      await setStatus(warehouse, CLOSED);
+      await setPauseFlags(PAUSE_ONBOARDING);
+
+      try {
+        await revokeCustomer(warehouse, customer.publicKey, operator);
+      } finally {
+        await setPauseFlags(0);
+      }
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.status).to.deep.equal({ revoked: {} });
+    });
+
+    it("should let staff with the confirm-customers role revoke", async () => {
+      const { customer, warehouse, operator } = await confirmedCustomer();
+      const clerk = await newFunded(provider);
+      await grant(warehouse, operator, clerk.publicKey, ROLES.confirmCustomers);
+
+      await revokeCustomer(warehouse, customer.publicKey, clerk, true);
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.status).to.deep.equal({ revoked: {} });
+    });
+
+    it("should emit CustomerRevoked", async () => {
+      const { customer, warehouse, operator } = await confirmedCustomer();
+
+      let event: any = null;
+      const listener = program.addEventListener("customerRevoked", (e) => {
+        event = e;
+      });
+
+      await revokeCustomer(warehouse, customer.publicKey, operator);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.warehouseCustomer.toString()).to.equal(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0].toString()
+      );
+      expect(event.warehouse.toString()).to.equal(warehouse.toString());
+      expect(event.customer.toString()).to.equal(customer.publicKey.toString());
+      expect(event.authority.toString()).to.equal(
+        operator.publicKey.toString()
+      );
+    });
+  });
+
+  describe("error cases", () => {
+    it("should fail when signer is neither operator nor staff", async () => {
+      const { customer, warehouse } = await confirmedCustomer();
+
+      try {
+        await revokeCustomer(warehouse, customer.publicKey, customer);
+        expect.fail("Should have thrown an error for the customer");
+      } catch (err) {
+        expect(err.toString()).to.include("MissingStaffRole");
+      }
+    });
+
+    it("should reject staff without the confirm-customers role", async () => {
+      const { customer, warehouse, operator } = await confirmedCustomer();
+      const member = await newFunded(provider);
+      await grant(warehouse, operator, member.publicKey, ROLES.dispatch);
+
+      try {
+        await revokeCustomer(warehouse, customer.publicKey, member, true);
+        expect.fail("Should have thrown an error for the dispatch role");
+      } catch (err) {
+        expect(err.toString()).to.include("MissingStaffRole");
+      }
+    });
+
+    it("should fail for a customer already revoked", async () => {
+      const { customer, warehouse, operator } = await confirmedCustomer();
+      await revokeCustomer(warehouse, customer.publicKey, operator);
+
+      try {
+        await revokeCustomer(warehouse, customer.publicKey, operator);
+        expect.fail("Should have thrown an error for a revoked customer");
+      } catch (err) {
+        expect(err.toString()).to.include("AlreadyRevoked");
+      }
+    });
+  });
+});
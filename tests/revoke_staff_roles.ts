@@ -0,0 +1,173 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+
+// Mirrors the ROLE_* constants in states.rs
+const ROLE_QUOTE = 1 << 1;
+const ROLE_DISPATCH = 1 << 2;
+
+describe("revoke_staff_roles", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const { getConfigPDA, getWarehousePDA, getStaffPDA } = pdaHelpers(
+    program.programId
+  );
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  // Creates a warehouse run by the provider wallet, with one staff member
+  // holding quote + dispatch
+  const setup = async () => {
+    const warehouseId = new anchor.BN(
+      Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)
+    );
+    const [warehouse] = getWarehousePDA(warehouseId);
+    const member = Keypair.generate().publicKey;
+
+    await program.methods
+      .createWarehouse(warehouseId, admin.publicKey, "Hub", "", 500, [], null)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .grantStaffRoles(member, ROLE_QUOTE | ROLE_DISPATCH)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        staff: getStaffPDA(warehouse, member)[0],
+        authority: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return { warehouse, member };
+  };
+
+  const revoke = (
+    warehouse: PublicKey,
+    member: PublicKey,
+    roles: number,
+    signer: Keypair | null = null
+  ) => {
+    const builder = program.methods.revokeStaffRoles(member, roles).accounts({
+      config: configPDA,
+      warehouse,
+      staff: getStaffPDA(warehouse, member)[0],
+      authority: signer ? signer.publicKey : admin.publicKey,
+    });
+    return signer ? builder.signers([signer]).rpc() : builder.rpc();
+  };
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should remove only the given roles", async () => {
+      const { warehouse, member } = await setup();
+
+      await revoke(warehouse, member, ROLE_QUOTE);
+
+      const staff = await program.account.warehouseStaff.fetch(
+        getStaffPDA(warehouse, member)[0]
+      );
+      expect(staff.roles).to.equal(ROLE_DISPATCH);
+    });
+
+    it("should close the grant and refund rent when no role is left", async () => {
+      const { warehouse, member } = await setup();
+      const [staffPDA] = getStaffPDA(warehouse, member);
+      const rent = await provider.connection.getBalance(staffPDA);
+      const before = await provider.connection.getBalance(admin.publicKey);
+
+      await revoke(warehouse, member, ROLE_QUOTE | ROLE_DISPATCH);
+
+      expect(await provider.connection.getAccountInfo(staffPDA)).to.be.null;
+      const after = await provider.connection.getBalance(admin.publicKey);
+      // Rent comes back minus the transaction fee
+      expect(after).to.be.greaterThan(before + rent - 10_000);
+    });
+
+    it("should emit StaffRolesRevoked", async () => {
+      const { warehouse, member } = await setup();
+
+      let event: any = null;
+      const listener = program.addEventListener("staffRolesRevoked", (e) => {
+        event = e;
+      });
+
+      await revoke(warehouse, member, ROLE_DISPATCH);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.member.toString()).to.equal(member.toString());
+      expect(event.roles).to.equal(ROLE_DISPATCH);
+      expect(event.newRoles).to.equal(ROLE_QUOTE);
+    });
+  });
+
+  describe("error cases", () => {
+    it("should fail for a member without a grant", async () => {
+      const { warehouse } = await setup();
+
+      try {
+        await revoke(warehouse, Keypair.generate().publicKey, ROLE_QUOTE);
+        expect.fail("Should have thrown an error for missing grant");
+      } catch (err) {
+        expect(err.toString()).to.include("AccountNotInitialized");
+      }
+    });
+
+    it("should reject an empty role mask", async () => {
+      const { warehouse, member } = await setup();
+
+      try {
+        await revoke(warehouse, member, 0);
+        expect.fail("Should have thrown an error for empty roles");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidRoles");
+      }
+    });
+
+    it("should fail when signer is neither operator nor admin", async () => {
+      const { warehouse, member } = await setup();
+      const attacker = Keypair.generate();
+
+      try {
+        await revoke(warehouse, member, ROLE_QUOTE, attacker);
+        expect.fail("Should have thrown an error for unauthorized signer");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedWarehouseAuthority");
+      }
+    });
+  });
+});
@@ -0,0 +1,129 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+
+// Mirrors the PAUSE_* constants in states.rs
+const PAUSE_LEGACY_ALL = 1 << 0;
+const PAUSE_NEW_ORDERS = 1 << 1;
+const PAUSE_NEW_OFFERS = 1 << 2;
+const PAUSE_ALL = 0x3e;
+
+describe("set_pause_flags", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const { getConfigPDA } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  const setPauseFlags = (flags: number) =>
+    program.methods
+      .setPauseFlags(flags)
+      .accounts({ config: configPDA, admin: admin.publicKey })
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  after(async () => {
+    // Leave the program unpaused for other test files
+    await setPauseFlags(0);
+  });
+
+  describe("success cases", () => {
+    it("should pause only new orders and new offers", async () => {
+      await setPauseFlags(PAUSE_NEW_ORDERS | PAUSE_NEW_OFFERS);
+
+      const config = await program.account.programConfig.fetch(configPDA);
+      expect(config.pauseFlags).to.equal(PAUSE_NEW_ORDERS | PAUSE_NEW_OFFERS);
+    });
+
+    it("should pause everything with PAUSE_ALL", async () => {
+      await setPauseFlags(PAUSE_ALL);
+
+      const config = await program.account.programConfig.fetch(configPDA);
+      expect(config.pauseFlags).to.equal(PAUSE_ALL);
+    });
+
+    it("should unpause everything with 0", async () => {
+      await setPauseFlags(0);
+
+      const config = await program.account.programConfig.fetch(configPDA);
+      expect(config.pauseFlags).to.equal(0);
+    });
+
+    it("should emit PauseFlagsUpdated with old and new masks", async () => {
+      let event: any = null;
+      const listener = program.addEventListener("pauseFlagsUpdated", (e) => {
+        event = e;
+      });
+
+      await setPauseFlags(PAUSE_NEW_OFFERS);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.oldPauseFlags).to.equal(0);
+      expect(event.newPauseFlags).to.equal(PAUSE_NEW_OFFERS);
+    });
+  });
+
+  describe("error cases", () => {
+    it("should reject the legacy bit", async () => {
+      try {
+        await setPauseFlags(PAUSE_LEGACY_ALL);
+        expect.fail("Should have thrown an error for invalid pause flags");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidPauseFlags");
+      }
+    });
+
+    it("should reject unknown bits", async () => {
+      try {
+        await setPauseFlags(0x80);
+        expect.fail("Should have thrown an error for invalid pause flags");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidPauseFlags");
+      }
+    });
+
+    it("should fail when signer is not the admin", async () => {
+      const attacker = Keypair.generate();
+
+      try {
+        await program.methods
+          .setPauseFlags(0)
+          .accounts({ config: configPDA, admin: attacker.publicKey })
+          .signers([attacker])
+          .rpc();
+
+        expect.fail("Should have thrown an error for unauthorized admin");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedAdmin");
+      }
+    });
+  });
+});
@@ -0,0 +1,212 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+
+const ACTIVE = { active: {} };
+const SUSPENDED = { suspended: {} };
+const CLOSED = { closed: {} };
+
+describe("set_warehouse_status", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const { getConfigPDA, getWarehousePDA, getStaffPDA } = pdaHelpers(
+    program.programId
+  );
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  // Creates an active warehouse operated by the provider wallet
+  const createWarehouse = async () => {
+    const warehouseId = new anchor.BN(
+      Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)
+    );
+    const [warehouse] = getWarehousePDA(warehouseId);
+
+    await program.methods
+      .createWarehouse(warehouseId, admin.publicKey, "Hub", "", 500, [], null)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return warehouse;
+  };
+
+  const setStatus = (
+    warehouse: PublicKey,
+    status: object,
+    signer: Keypair | null = null
+  ) => {
+    const builder = program.methods.setWarehouseStatus(status as any).accounts({
+      config: configPDA,
+      warehouse,
+      admin: signer ? signer.publicKey : admin.publicKey,
+    });
+    return signer ? builder.signers([signer]).rpc() : builder.rpc();
+  };
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should create warehouses as active", async () => {
+      const warehouse = await createWarehouse();
+
+      const account = await program.account.warehouse.fetch(warehouse);
+      expect(account.status).to.deep.equal(ACTIVE);
+    });
+
+    it("should suspend and reactivate a warehouse", async () => {
+      const warehouse = await createWarehouse();
+
+      await setStatus(warehouse, SUSPENDED);
+      let account = await program.account.warehouse.fetch(warehouse);
+      expect(account.status).to.deep.equal(SUSPENDED);
+
+      await setStatus(warehouse, ACTIVE);
+      account = await program.account.warehouse.fetch(warehouse);
+      expect(account.status).to.deep.equal(ACTIVE);
+    });
+
+    it("should close a suspended warehouse", async () => {
+      const warehouse = await createWarehouse();
+
+      await setStatus(warehouse, SUSPENDED);
+      await setStatus(warehouse, CLOSED);
+
+      const account = await program.account.warehouse.fetch(warehouse);
+      expect(account.status).to.deep.equal(CLOSED);
+    });
+
+    it("should still allow staff grants while suspended", async () => {
+      const warehouse = await createWarehouse();
+      const member = Keypair.generate().publicKey;
+      await setStatus(warehouse, SUSPENDED);
+
+      await program.methods
+        .grantStaffRoles(member, 1 << 2)
+        .accounts({
+          config: configPDA,
+          warehouse,
+          staff: getStaffPDA(warehouse, member)[0],
+          authority: admin.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+
+      const staff = await program.account.warehouseStaff.fetch(
+        getStaffPDA(warehouse, member)[0]
+      );
+      expect(staff.roles).to.equal(1 << 2);
+    });
+
+    it("should emit WarehouseStatusChanged", async () => {
+      const warehouse = await createWarehouse();
+
+      let event: any = null;
+      const listener = program.addEventListener(
+        "warehouseStatusChanged",
+        (e) => {
+          event = e;
+        }
+      );
+
+      await setStatus(warehouse, SUSPENDED);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.warehouse.toString()).to.equal(warehouse.toString());
+      expect(event.oldStatus).to.deep.equal(ACTIVE);
+      expect(event.newStatus).to.deep.equal(SUSPENDED);
+    });
+  });
+
+  describe("error cases", () => {
+    it("should not reopen a closed warehouse", async () => {
+      const warehouse = await createWarehouse();
+      await setStatus(warehouse, CLOSED);
+
+      for (const status of [ACTIVE, SUSPENDED]) {
+        try {
+          await setStatus(warehouse, status);
+          expect.fail("Should have thrown an error for reopening");
+        } catch (err) {
+          expect(err.toString()).to.include("InvalidStatusTransition");
+        }
+      }
+    });
+
+    it("should reject setting the current status", async () => {
+      const warehouse = await createWarehouse();
+
+      try {
+        await setStatus(warehouse, ACTIVE);
+        expect.fail("Should have thrown an error for unchanged status");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidStatusTransition");
+      }
+    });
+
+    it("should block staff grants once closed", async () => {
+      const warehouse = await createWarehouse();
+      const member = Keypair.generate().publicKey;
+      await setStatus(warehouse, CLOSED);
+
+      try {
+        await program.methods
+          .grantStaffRoles(member, 1 << 4)
+          .accounts({
+            config: configPDA,
+            warehouse,
+            staff: getStaffPDA(warehouse, member)[0],
+            authority: admin.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .rpc();
+        expect.fail("Should have thrown an error for closed warehouse");
+      } catch (err) {
+        expect(err.toString()).to.include("WarehouseClosed");
+      }
+    });
+
+    it("should fail when signer is not the admin", async () => {
+      const warehouse = await createWarehouse();
+      const attacker = Keypair.generate();
+
+      try {
+        await setStatus(warehouse, SUSPENDED, attacker);
+        expect.fail("Should have thrown an error for unauthorized admin");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedAdmin");
+      }
+    });
+  });
+});
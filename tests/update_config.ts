@@ -0,0 +1,206 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+
+describe("update_config", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const { getConfigPDA } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: provider.wallet.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should update only the logistics wallet", async () => {
+      const before = await program.account.programConfig.fetch(configPDA);
+      const newWallet = Keypair.generate().publicKey;
+
+      await program.methods
+        .updateConfig(newWallet, null, null)
+        .accounts({
+          config: configPDA,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      const after = await program.account.programConfig.fetch(configPDA);
+      expect(after.logisticsWallet.toString()).to.equal(newWallet.toString());
+      expect(after.pauseFlags).to.equal(before.pauseFlags);
+      expect(after.pendingAdmin).to.deep.equal(before.pendingAdmin);
+    });
+
+    it("should leave every field unchanged when all arguments are null", async () => {
+      const before = await program.account.programConfig.fetch(configPDA);
+
+      await program.methods
+        .updateConfig(null, null, null)
+        .accounts({
+          config: configPDA,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      const after = await program.account.programConfig.fetch(configPDA);
+      expect(after.logisticsWallet.toString()).to.equal(
+        before.logisticsWallet.toString()
+      );
+      expect(after.pauseFlags).to.equal(before.pauseFlags);
+      expect(after.pendingAdmin).to.deep.equal(before.pendingAdmin);
+    });
+
+    it("should update only the fee notice period", async () => {
+      const before = await program.account.programConfig.fetch(configPDA);
+
+      await program.methods
+        .updateConfig(null, new anchor.BN(3600), null)
+        .accounts({
+          config: configPDA,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      const after = await program.account.programConfig.fetch(configPDA);
+      expect(after.feeNoticePeriod.toNumber()).to.equal(3600);
+      expect(after.logisticsWallet.toString()).to.equal(
+        before.logisticsWallet.toString()
+      );
+    });
+
+    it("should update only the protocol fee", async () => {
+      const before = await program.account.programConfig.fetch(configPDA);
+
+      await program.methods
+        .updateConfig(null, null, 1_000)
+        .accounts({
+          config: configPDA,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      const after = await program.account.programConfig.fetch(configPDA);
+      expect(after.protocolFeeBps).to.equal(1_000);
+      expect(after.feeNoticePeriod.toString()).to.equal(
+        before.feeNoticePeriod.toString()
+      );
+
+      // Restore the default so other suites settle without a protocol cut
+      await program.methods
+        .updateConfig(null, null, 0)
+        .accounts({
+          config: configPDA,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc();
+    });
+
+    it("should emit ConfigUpdated with old and new values", async () => {
+      const before = await program.account.programConfig.fetch(configPDA);
+      const newWallet = Keypair.generate().publicKey;
+
+      let event: any = null;
+      const listener = program.addEventListener("configUpdated", (e) => {
+        event = e;
+      });
+
+      await program.methods
+        .updateConfig(newWallet, null, null)
+        .accounts({
+          config: configPDA,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.oldLogisticsWallet.toString()).to.equal(
+        before.logisticsWallet.toString()
+      );
+      expect(event.newLogisticsWallet.toString()).to.equal(
+        newWallet.toString()
+      );
+      expect(event.newFeeNoticePeriod.toString()).to.equal(
+        before.feeNoticePeriod.toString()
+      );
+      expect(event.newProtocolFeeBps).to.equal(before.protocolFeeBps);
+    });
+  });
+
+  describe("error cases", () => {
+    it("should reject a negative fee notice period", async () => {
+      try {
+        await program.methods
+          .updateConfig(null, new anchor.BN(-1), null)
+          .accounts({
+            config: configPDA,
+            admin: provider.wallet.publicKey,
+          })
+          .rpc();
+
+        expect.fail("Should have thrown an error for negative notice period");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidFeeNoticePeriod");
+      }
+    });
+
+    it("should reject a protocol fee above 10_000 bps", async () => {
+      try {
+        await program.methods
+          .updateConfig(null, null, 10_001)
+          .accounts({
+            config: configPDA,
+            admin: provider.wallet.publicKey,
+          })
+          .rpc();
+
+        expect.fail("Should have thrown an error for protocol fee above 100%");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidProtocolFeeBps");
+      }
+    });
+
+    it("should fail when signer is not the admin", async () => {
+      const attacker = Keypair.generate();
+
+      try {
+        await program.methods
+          .updateConfig(attacker.publicKey, null, null)
+          .accounts({
+            config: configPDA,
+            admin: attacker.publicKey,
+          })
+          .signers([attacker])
+          .rpc();
+
+        expect.fail("Should have thrown an error for unauthorized admin");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedAdmin");
+      }
+    });
+  });
+});
@@ -0,0 +1,294 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+// Mirrors the MAX_* constants in states.rs
+const MAX_NAME_LEN = 100;
+
+describe("update_farmer_profile", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const { getConfigPDA, getFarmerPDA } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  const registerFarmer = (
+    farmer: Keypair,
+    displayName = "Green Acres",
+    publicProfileUri = "https://example.com/green-acres"
+  ) =>
+    program.methods
+      .registerFarmer(displayName, publicProfileUri)
+      .accounts({
+        config: configPDA,
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        authority: farmer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([farmer])
+      .rpc();
+
+  const updateFarmerProfile = (
+    farmer: Keypair,
+    displayName: string | null,
+    publicProfileUri: string | null,
+    signer: Keypair = farmer
+  ) =>
+    program.methods
+      .updateFarmerProfile(displayName, publicProfileUri, null, null)
+      .accounts({
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  // Payout settings only; PublicKey.default as the delegate removes it
+  const updatePayout = (
+    farmer: Keypair,
+    payoutWallet: PublicKey | null,
+    delegate: PublicKey | null,
+    signer: Keypair = farmer
+  ) =>
+    program.methods
+      .updateFarmerProfile(null, null, payoutWallet, delegate)
+      .accounts({
+        farmerProfile: getFarmerPDA(farmer.publicKey)[0],
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  describe("success cases", () => {
+    it("should update only the display name", async () => {
+      const farmer = await newFunded(provider);
+      await registerFarmer(farmer);
+
+      await updateFarmerProfile(farmer, "Blue Hills", null);
+
+      const profile = await program.account.farmerProfile.fetch(
+        getFarmerPDA(farmer.publicKey)[0]
+      );
+      expect(profile.displayName).to.equal("Blue Hills");
+      expect(profile.publicProfileUri).to.equal(
+        "https://example.com/green-acres"
+      );
+    });
+
+    it("should replace and clear the profile URI", async () => {
+      const farmer = await newFunded(provider);
+      await registerFarmer(farmer);
+      const [farmerPDA] = getFarmerPDA(farmer.publicKey);
+
+      await updateFarmerProfile(farmer, null, "ar://abc123");
+      let profile = await program.account.farmerProfile.fetch(farmerPDA);
+      expect(profile.publicProfileUri).to.equal("ar://abc123");
+
+      await updateFarmerProfile(farmer, null, "");
+      profile = await program.account.farmerProfile.fetch(farmerPDA);
+      expect(profile.publicProfileUri).to.equal("");
+      expect(profile.displayName).to.equal("Green Acres");
+    });
+
+    it("should emit FarmerProfileUpdated", async () => {
+      const farmer = await newFunded(provider);
+      await registerFarmer(farmer);
+
+      let event: any = null;
+      const listener = program.addEventListener(
+        "farmerProfileUpdated",
+        (e) => {
+          event = e;
+        }
+      );
+
+      await updateFarmerProfile(farmer, "Blue Hills", null);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.authority.toString()).to.equal(farmer.publicKey.toString());
+      expect(event.displayName).to.equal("Blue Hills");
+      expect(event.publicProfileUri).to.equal("https://example.com/green-acres");
+    });
+
+    it("should set the payout wallet and the delegate", async () => {
+      const farmer = await newFunded(provider);
+      await registerFarmer(farmer);
+      const payoutWallet = Keypair.generate().publicKey;
+      const delegate = Keypair.generate().publicKey;
+
+      await updatePayout(farmer, payoutWallet, delegate);
+
+      const profile = await program.account.farmerProfile.fetch(
+        getFarmerPDA(farmer.publicKey)[0]
+      );
+      expect(profile.payoutWallet.toString()).to.equal(payoutWallet.toString());
+      expect(profile.delegate.toString()).to.equal(delegate.toString());
+      expect(profile.displayName).to.equal("Green Acres");
+    });
+
+    it("should remove the delegate with the default key", async () => {
+      const farmer = await newFunded(provider);
+      await registerFarmer(farmer);
+      await updatePayout(farmer, null, Keypair.generate().publicKey);
+
+      await updatePayout(farmer, null, PublicKey.default);
+
+      const profile = await program.account.farmerProfile.fetch(
+        getFarmerPDA(farmer.publicKey)[0]
+      );
+      expect(profile.delegate).to.be.null;
+      expect(profile.payoutWallet.toString()).to.equal(
+        farmer.publicKey.toString()
+      );
+    });
+
+    it("should emit FarmerPayoutWalletChanged and FarmerDelegateChanged", async () => {
+      const farmer = await newFunded(provider);
+      await registerFarmer(farmer);
+      const payoutWallet = Keypair.generate().publicKey;
+      const delegate = Keypair.generate().publicKey;
+
+      let payoutEvent: any = null;
+      let delegateEvent: any = null;
+      const payoutListener = program.addEventListener(
+        "farmerPayoutWalletChanged",
+        (e) => {
+          payoutEvent = e;
+        }
+      );
+      const delegateListener = program.addEventListener(
+        "farmerDelegateChanged",
+        (e) => {
+          delegateEvent = e;
+        }
+      );
+
+      await updatePayout(farmer, payoutWallet, delegate);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(payoutListener);
+      await program.removeEventListener(delegateListener);
+
+      expect(payoutEvent).to.not.be.null;
+      expect(payoutEvent.oldPayoutWallet.toString()).to.equal(
+        farmer.publicKey.toString()
+      );
+      expect(payoutEvent.newPayoutWallet.toString()).to.equal(
+        payoutWallet.toString()
+      );
+      expect(delegateEvent).to.not.be.null;
+      expect(delegateEvent.oldDelegate).to.be.null;
+      expect(delegateEvent.newDelegate.toString()).to.equal(
+        delegate.toString()
+      );
+    });
+  });
+
+  describe("error cases", () => {
+    it("should reject a URI with another scheme", async () => {
+      const farmer = await newFunded(provider);
+      await registerFarmer(farmer);
+
+      try {
+        await updateFarmerProfile(farmer, null, "http://example.com");
+        expect.fail("Should have thrown an error for http URI");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidProfileUriScheme");
+      }
+    });
+
+    it("should reject an invalid display name", async () => {
+      const farmer = await newFunded(provider);
+      await registerFarmer(farmer);
+
+      try {
+        await updateFarmerProfile(farmer, "n".repeat(MAX_NAME_LEN + 1), null);
+        expect.fail("Should have thrown an error for long name");
+      } catch (err) {
+        expect(err.toString()).to.include("DisplayNameTooLong");
+      }
+    });
+
+    it("should reject another key's signature", async () => {
+      const farmer = await newFunded(provider);
+      await registerFarmer(farmer);
+      const attacker = await newFunded(provider);
+
+      try {
+        await updateFarmerProfile(farmer, "Hijacked", null, attacker);
+        expect.fail("Should have thrown an error for another signer");
+      } catch (err) {
+        // The profile PDA is derived from the signer, so the seeds no longer match
+        expect(err.toString()).to.include("ConstraintSeeds");
+      }
+    });
+
+    it("should not let the delegate change the payout wallet", async () => {
+      const farmer = await newFunded(provider);
+      await registerFarmer(farmer);
+      const delegate = await newFunded(provider);
+      await updatePayout(farmer, null, delegate.publicKey);
+
+      try {
+        await updatePayout(farmer, delegate.publicKey, null, delegate);
+        expect.fail("Should have thrown an error for the delegate");
+      } catch (err) {
+        expect(err.toString()).to.include("ConstraintSeeds");
+      }
+    });
+
+    it("should reject the default key as payout wallet", async () => {
+      const farmer = await newFunded(provider);
+      await registerFarmer(farmer);
+
+      try {
+        await updatePayout(farmer, PublicKey.default, null);
+        expect.fail("Should have thrown an error for the default key");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidPayoutWallet");
+      }
+    });
+
+    it("should reject the farmer as its own delegate", async () => {
+      const farmer = await newFunded(provider);
+      await registerFarmer(farmer);
+
+      try {
+        await updatePayout(farmer, null, farmer.publicKey);
+        expect.fail("Should have thrown an error for the farmer as delegate");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidDelegate");
+      }
+    });
+  });
+});
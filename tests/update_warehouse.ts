@@ -0,0 +1,622 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+
+// Short notice period so scheduled increases mature within the test
+const NOTICE_PERIOD = 2;
+const DEFAULT_NOTICE_PERIOD = 7 * 24 * 60 * 60;
+
+// Mirrors the ROLE_* constants in states.rs
+const ROLES = {
+  confirmCustomers: 1 << 0,
+  quote: 1 << 1,
+  dispatch: 1 << 2,
+  complete: 1 << 3,
+  refund: 1 << 4,
+  manage: 1 << 5,
+};
+
+describe("update_warehouse", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const { getConfigPDA, getWarehousePDA, getStaffPDA } = pdaHelpers(
+    program.programId
+  );
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  const setNoticePeriod = (seconds: number) =>
+    program.methods
+      .updateConfig(null, new anchor.BN(seconds), null)
+      .accounts({ config: configPDA, admin: admin.publicKey })
+      .rpc();
+
+  // Creates a warehouse run by a fresh operator at 500 bps
+  const createWarehouse = async () => {
+    const warehouseId = new anchor.BN(
+      Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)
+    );
+    const operator = Keypair.generate();
+    const [warehouse] = getWarehousePDA(warehouseId);
+
+    await program.methods
+      .createWarehouse(
+        warehouseId,
+        operator.publicKey,
+        "North Hub",
+        "Dock 3",
+        500,
+        [{ prefix: 123, len: 3 }],
+        "https://example.com/fees.json"
+      )
+      .accounts({
+        config: configPDA,
+        warehouse,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return { warehouse, operator };
+  };
+
+  type Update = {
+    name?: string;
+    pickupNotes?: string;
+    feeBps?: number;
+    zipPrefixes?: { prefix: number; len: number }[];
+    feeRulesUri?: string;
+    operator?: PublicKey;
+    feeReceiver?: PublicKey;
+    confirmationValidity?: number;
+    encryptionKey?: number[];
+  };
+
+  // `asStaff` passes the signer's `WarehouseStaff` PDA
+  const updateWarehouse = (
+    warehouse: PublicKey,
+    update: Update,
+    signer: Keypair | null,
+    asStaff = false
+  ) => {
+    const builder = program.methods
+      .updateWarehouse(
+        update.name ?? null,
+        update.pickupNotes ?? null,
+        update.feeBps ?? null,
+        update.zipPrefixes ?? null,
+        update.feeRulesUri ?? null,
+        update.operator ?? null,
+        update.feeReceiver ?? null,
+        update.confirmationValidity !== undefined
+          ? new anchor.BN(update.confirmationValidity)
+          : null,
+        update.encryptionKey ?? null
+      )
+      .accounts({
+        config: configPDA,
+        warehouse,
+        staff: asStaff ? getStaffPDA(warehouse, signer.publicKey)[0] : null,
+        authority: signer ? signer.publicKey : admin.publicKey,
+      });
+    return signer ? builder.signers([signer]).rpc() : builder.rpc();
+  };
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+    await setNoticePeriod(NOTICE_PERIOD);
+  });
+
+  after(async () => {
+    await setNoticePeriod(DEFAULT_NOTICE_PERIOD);
+  });
+
+  describe("success cases", () => {
+    it("should let the operator update public details", async () => {
+      const { warehouse, operator } = await createWarehouse();
+
+      await updateWarehouse(
+        warehouse,
+        {
+          name: "North Hub 2",
+          pickupNotes: "Dock 5",
+          zipPrefixes: [{ prefix: 4567, len: 4 }],
+        },
+        operator
+      );
+
+      const account = await program.account.warehouse.fetch(warehouse);
+      expect(account.name).to.equal("North Hub 2");
+      expect(account.pickupNotes).to.equal("Dock 5");
+      expect(account.deliverZipPrefixes).to.deep.equal([
+        { prefix: 4567, len: 4 },
+      ]);
+      // Untouched fields keep their value
+      expect(account.feeBps).to.equal(500);
+      expect(account.deliveryFeeRulesUri).to.equal(
+        "https://example.com/fees.json"
+      );
+      expect(account.operator.toString()).to.equal(
+        operator.publicKey.toString()
+      );
+    });
+
+    it("should clear the fee rules URI with an empty string", async () => {
+      const { warehouse, operator } = await createWarehouse();
+
+      await updateWarehouse(warehouse, { feeRulesUri: "" }, operator);
+
+      const account = await program.account.warehouse.fetch(warehouse);
+      expect(account.deliveryFeeRulesUri).to.be.null;
+    });
+
+    it("should set and remove the confirmation validity", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const year = 365 * 86_400;
+
+      let account = await program.account.warehouse.fetch(warehouse);
+      expect(account.confirmationValidity).to.be.null;
+
+      await updateWarehouse(
+        warehouse,
+        { confirmationValidity: year },
+        operator
+      );
+      account = await program.account.warehouse.fetch(warehouse);
+      expect(account.confirmationValidity.toNumber()).to.equal(year);
+
+      await updateWarehouse(warehouse, { confirmationValidity: 0 }, operator);
+      account = await program.account.warehouse.fetch(warehouse);
+      expect(account.confirmationValidity).to.be.null;
+    });
+
+    it("should publish, rotate and remove the encryption key", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const first = [...Buffer.alloc(32, 1)];
+      const second = [...Buffer.alloc(32, 2)];
+
+      let account = await program.account.warehouse.fetch(warehouse);
+      expect(account.encryptionKey).to.be.null;
+
+      const events: any[] = [];
+      const listener = program.addEventListener(
+        "warehouseEncryptionKeyChanged",
+        (e) => {
+          events.push(e);
+        }
+      );
+
+      await updateWarehouse(warehouse, { encryptionKey: first }, operator);
+      account = await program.account.warehouse.fetch(warehouse);
+      expect(account.encryptionKey).to.deep.equal(first);
+
+      await updateWarehouse(warehouse, { encryptionKey: second }, operator);
+      account = await program.account.warehouse.fetch(warehouse);
+      expect(account.encryptionKey).to.deep.equal(second);
+
+      // All zeroes removes the key
+      await updateWarehouse(
+        warehouse,
+        { encryptionKey: [...Buffer.alloc(32)] },
+        operator
+      );
+      account = await program.account.warehouse.fetch(warehouse);
+      expect(account.encryptionKey).to.be.null;
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(events).to.have.length(3);
+      expect(events[0].oldKey).to.be.null;
+      expect(events[0].newKey).to.deep.equal(first);
+      expect(events[1].oldKey).to.deep.equal(first);
+      expect(events[1].newKey).to.deep.equal(second);
+      expect(events[2].oldKey).to.deep.equal(second);
+      expect(events[2].newKey).to.be.null;
+      expect(events[2].authority.toString()).to.equal(
+        operator.publicKey.toString()
+      );
+    });
+
+    it("should let the admin update a warehouse", async () => {
+      const { warehouse } = await createWarehouse();
+
+      await updateWarehouse(warehouse, { pickupNotes: "Closed Sundays" }, null);
+
+      const account = await program.account.warehouse.fetch(warehouse);
+      expect(account.pickupNotes).to.equal("Closed Sundays");
+    });
+
+    it("should apply a fee cut immediately", async () => {
+      const { warehouse, operator } = await createWarehouse();
+
+      await updateWarehouse(warehouse, { feeBps: 300 }, operator);
+
+      const account = await program.account.warehouse.fetch(warehouse);
+      expect(account.feeBps).to.equal(300);
+      expect(account.pendingFeeBps).to.be.null;
+      expect(account.feeEffectiveAt.toNumber()).to.equal(0);
+    });
+
+    it("should hold a fee increase until the notice period passes", async () => {
+      const { warehouse, operator } = await createWarehouse();
+
+      await updateWarehouse(warehouse, { feeBps: 800 }, operator);
+
+      let account = await program.account.warehouse.fetch(warehouse);
+      expect(account.feeBps).to.equal(500);
+      expect(account.pendingFeeBps).to.equal(800);
+      const now = Math.floor(Date.now() / 1000);
+      expect(account.feeEffectiveAt.toNumber()).to.be.greaterThan(now - 30);
+      expect(account.feeEffectiveAt.toNumber()).to.be.at.most(
+        now + NOTICE_PERIOD + 30
+      );
+
+      // Any later update applies the increase once it has matured
+      await new Promise((resolve) =>
+        setTimeout(resolve, (NOTICE_PERIOD + 2) * 1000)
+      );
+      await updateWarehouse(warehouse, { name: "Renamed" }, operator);
+
+      account = await program.account.warehouse.fetch(warehouse);
+      expect(account.feeBps).to.equal(800);
+      expect(account.pendingFeeBps).to.be.null;
+      expect(account.feeEffectiveAt.toNumber()).to.equal(0);
+    });
+
+    it("should replace a pending increase with a later cut", async () => {
+      const { warehouse, operator } = await createWarehouse();
+
+      await updateWarehouse(warehouse, { feeBps: 900 }, operator);
+      await updateWarehouse(warehouse, { feeBps: 400 }, operator);
+
+      const account = await program.account.warehouse.fetch(warehouse);
+      expect(account.feeBps).to.equal(400);
+      expect(account.pendingFeeBps).to.be.null;
+    });
+
+    it("should let the admin rotate a lost operator key", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const newOperator = Keypair.generate();
+
+      await updateWarehouse(
+        warehouse,
+        { operator: newOperator.publicKey },
+        null
+      );
+
+      const account = await program.account.warehouse.fetch(warehouse);
+      expect(account.operator.toString()).to.equal(
+        newOperator.publicKey.toString()
+      );
+
+      // The new key works, the old one no longer does
+      await updateWarehouse(warehouse, { pickupNotes: "New key" }, newOperator);
+      try {
+        await updateWarehouse(warehouse, { pickupNotes: "Old key" }, operator);
+        expect.fail("Should have thrown an error for the old operator");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedWarehouseAuthority");
+      }
+    });
+
+    it("should redirect fees to a new receiver", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const feeReceiver = Keypair.generate().publicKey;
+
+      let event: any = null;
+      const listener = program.addEventListener(
+        "warehouseFeeReceiverChanged",
+        (e) => {
+          event = e;
+        }
+      );
+
+      await updateWarehouse(warehouse, { feeReceiver }, operator);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      const account = await program.account.warehouse.fetch(warehouse);
+      expect(account.feeReceiver.toString()).to.equal(feeReceiver.toString());
+      expect(account.operator.toString()).to.equal(
+        operator.publicKey.toString()
+      );
+
+      expect(event).to.not.be.null;
+      expect(event.oldFeeReceiver.toString()).to.equal(
+        operator.publicKey.toString()
+      );
+      expect(event.newFeeReceiver.toString()).to.equal(feeReceiver.toString());
+    });
+
+    it("should emit fee and operator events", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const newOperator = Keypair.generate().publicKey;
+
+      let feeEvent: any = null;
+      let operatorEvent: any = null;
+      let updatedEvent: any = null;
+      const listeners = [
+        program.addEventListener("warehouseFeeChanged", (e) => {
+          feeEvent = e;
+        }),
+        program.addEventListener("warehouseOperatorChanged", (e) => {
+          operatorEvent = e;
+        }),
+        program.addEventListener("warehouseUpdated", (e) => {
+          updatedEvent = e;
+        }),
+      ];
+
+      await updateWarehouse(
+        warehouse,
+        { feeBps: 700, operator: newOperator },
+        operator
+      );
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      for (const listener of listeners) {
+        await program.removeEventListener(listener);
+      }
+
+      expect(feeEvent).to.not.be.null;
+      expect(feeEvent.warehouse.toString()).to.equal(warehouse.toString());
+      expect(feeEvent.oldFeeBps).to.equal(500);
+      expect(feeEvent.newFeeBps).to.equal(700);
+      expect(feeEvent.effectiveAt.toNumber()).to.be.greaterThan(0);
+
+      expect(operatorEvent).to.not.be.null;
+      expect(operatorEvent.authority.toString()).to.equal(
+        operator.publicKey.toString()
+      );
+      expect(operatorEvent.oldOperator.toString()).to.equal(
+        operator.publicKey.toString()
+      );
+      expect(operatorEvent.newOperator.toString()).to.equal(
+        newOperator.toString()
+      );
+
+      expect(updatedEvent).to.not.be.null;
+      expect(updatedEvent.warehouse.toString()).to.equal(warehouse.toString());
+    });
+  });
+
+  describe("staff privileges", () => {
+    const grant = (
+      warehouse: PublicKey,
+      operator: Keypair,
+      member: PublicKey,
+      roles: number
+    ) =>
+      program.methods
+        .grantStaffRoles(member, roles)
+        .accounts({
+          config: configPDA,
+          warehouse,
+          staff: getStaffPDA(warehouse, member)[0],
+          authority: operator.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([operator])
+        .rpc();
+
+    it("should let staff with the manage role update public details", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const manager = Keypair.generate();
+      await grant(warehouse, operator, manager.publicKey, ROLES.manage);
+
+      await updateWarehouse(
+        warehouse,
+        { pickupNotes: "Managed", zipPrefixes: [] },
+        manager,
+        true
+      );
+
+      const account = await program.account.warehouse.fetch(warehouse);
+      expect(account.pickupNotes).to.equal("Managed");
+      expect(account.deliverZipPrefixes).to.have.length(0);
+    });
+
+    for (const [name, role] of Object.entries(ROLES)) {
+      if (name === "manage") continue;
+
+      it(`should reject staff holding only the ${name} role`, async () => {
+        const { warehouse, operator } = await createWarehouse();
+        const member = Keypair.generate();
+        await grant(warehouse, operator, member.publicKey, role);
+
+        try {
+          await updateWarehouse(warehouse, { name: "Nope" }, member, true);
+          expect.fail("Should have thrown an error for missing role");
+        } catch (err) {
+          expect(err.toString()).to.include("MissingStaffRole");
+        }
+      });
+    }
+
+    it("should keep fee changes with the operator", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const manager = Keypair.generate();
+      await grant(warehouse, operator, manager.publicKey, 0x3f);
+
+      try {
+        await updateWarehouse(warehouse, { feeBps: 100 }, manager, true);
+        expect.fail("Should have thrown an error for staff fee change");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedWarehouseAuthority");
+      }
+    });
+
+    it("should keep operator rotation with the operator", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const manager = Keypair.generate();
+      await grant(warehouse, operator, manager.publicKey, ROLES.manage);
+
+      try {
+        await updateWarehouse(
+          warehouse,
+          { operator: manager.publicKey },
+          manager,
+          true
+        );
+        expect.fail("Should have thrown an error for staff operator change");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedWarehouseAuthority");
+      }
+    });
+
+    it("should keep the encryption key with the operator", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const manager = Keypair.generate();
+      await grant(warehouse, operator, manager.publicKey, ROLES.manage);
+
+      try {
+        await updateWarehouse(
+          warehouse,
+          { encryptionKey: [...Buffer.alloc(32, 1)] },
+          manager,
+          true
+        );
+        expect.fail("Should have thrown an error for staff key change");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedWarehouseAuthority");
+      }
+    });
+
+    it("should keep the fee receiver with the operator", async () => {
+      const { warehouse, operator } = await createWarehouse();
+      const manager = Keypair.generate();
+      await grant(warehouse, operator, manager.publicKey, ROLES.manage);
+
+      try {
+        await updateWarehouse(
+          warehouse,
+          { feeReceiver: manager.publicKey },
+          manager,
+          true
+        );
+        expect.fail("Should have thrown an error for staff fee receiver change");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedWarehouseAuthority");
+      }
+    });
+
+    it("should not accept another warehouse's grant", async () => {
+      const first = await createWarehouse();
+      const second = await createWarehouse();
+      const manager = Keypair.generate();
+      await grant(first.warehouse, first.operator, manager.publicKey, ROLES.manage);
+
+      try {
+        await program.methods
+          .updateWarehouse(
+            "Nope",
+            null,
+            null,
+            null,
+            null,
+            null,
+            null,
+            null,
+            null
+          )
+          .accounts({
+            config: configPDA,
+            warehouse: second.warehouse,
+            staff: getStaffPDA(first.warehouse, manager.publicKey)[0],
+            authority: manager.publicKey,
+          })
+          .signers([manager])
+          .rpc();
+        expect.fail("Should have thrown an error for foreign grant");
+      } catch (err) {
+        expect(err.toString()).to.include("ConstraintSeeds");
+      }
+    });
+  });
+
+  describe("error cases", () => {
+    it("should fail when signer is neither operator nor admin", async () => {
+      const { warehouse } = await createWarehouse();
+      const attacker = Keypair.generate();
+
+      try {
+        await updateWarehouse(warehouse, { name: "Hijacked" }, attacker);
+        expect.fail("Should have thrown an error for unauthorized signer");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedWarehouseAuthority");
+      }
+    });
+
+    it("should reject fee_bps above 10_000", async () => {
+      const { warehouse, operator } = await createWarehouse();
+
+      try {
+        await updateWarehouse(warehouse, { feeBps: 10_001 }, operator);
+        expect.fail("Should have thrown an error for invalid fee");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidFeeBps");
+      }
+    });
+
+    it("should reject a name over MAX_NAME_LEN", async () => {
+      const { warehouse, operator } = await createWarehouse();
+
+      try {
+        await updateWarehouse(warehouse, { name: "n".repeat(101) }, operator);
+        expect.fail("Should have thrown an error for long name");
+      } catch (err) {
+        expect(err.toString()).to.include("NameTooLong");
+      }
+    });
+
+    it("should reject a negative confirmation validity", async () => {
+      const { warehouse, operator } = await createWarehouse();
+
+      try {
+        await updateWarehouse(
+          warehouse,
+          { confirmationValidity: -1 },
+          operator
+        );
+        expect.fail("Should have thrown an error for negative validity");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidConfirmationValidity");
+      }
+    });
+
+    it("should reject the default key as operator", async () => {
+      const { warehouse } = await createWarehouse();
+
+      try {
+        await updateWarehouse(warehouse, { operator: PublicKey.default }, null);
+        expect.fail("Should have thrown an error for an unset operator");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidOperator");
+      }
+    });
+  });
+});
@@ -0,0 +1,326 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { FarmerCore } from "../target/types/farmer_core";
+import { expect } from "chai";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getProgramDataAddress } from "./helpers/program";
+import { pdaHelpers } from "./helpers/pda";
+import { newFunded } from "./helpers/wallet";
+
+const PAUSE_ONBOARDING = 1 << 3;
+const CLOSED = { closed: {} };
+
+describe("withdraw_from_warehouse", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.farmerCore as Program<FarmerCore>;
+  const programData = getProgramDataAddress(program.programId);
+
+  const {
+    getConfigPDA,
+    getWarehousePDA,
+    getCustomerPDA,
+    getWarehouseCustomerPDA,
+  } = pdaHelpers(program.programId);
+
+  const [configPDA] = getConfigPDA();
+  const admin = provider.wallet;
+
+  const registerCustomer = (customer: Keypair, publicProfileUri = "") =>
+    program.methods
+      .registerCustomer(publicProfileUri)
+      .accounts({
+        config: configPDA,
+        customerProfile: getCustomerPDA(customer.publicKey)[0],
+        authority: customer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customer])
+      .rpc();
+
+  // Registered customer with a funded key
+  const newCustomer = async () => {
+    const customer = await newFunded(provider);
+    await registerCustomer(customer);
+    return customer;
+  };
+
+  const createWarehouse = async () => {
+    const warehouseId = new anchor.BN(
+      Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)
+    );
+    const operator = await newFunded(provider);
+    const [warehouse] = getWarehousePDA(warehouseId);
+
+    await program.methods
+      .createWarehouse(warehouseId, operator.publicKey, "Hub", "", 500, [], null)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return { warehouse, operator };
+  };
+
+  const requestConfirmation = (customer: Keypair, warehouse: PublicKey) =>
+    program.methods
+      .requestCustomerConfirmation(null)
+      .accounts({
+        config: configPDA,
+        customerProfile: getCustomerPDA(customer.publicKey)[0],
+        warehouse,
+        warehouseCustomer: getWarehouseCustomerPDA(
+          warehouse,
+          customer.publicKey
+        )[0],
+        authority: customer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customer])
+      .rpc();
+
+  const confirmCustomer = (
+    warehouse: PublicKey,
+    customer: PublicKey,
+    signer: Keypair,
+    notesHash: number[] | null = null,
+    asStaff = false
+  ) =>
+    program.methods
+      .confirmCustomer(notesHash, null, null)
+      .accounts({
+        config: configPDA,
+        warehouse,
+        staff: asStaff ? getStaffPDA(warehouse, signer.publicKey)[0] : null,
+        warehouseCustomer: getWarehouseCustomerPDA(warehouse, customer)[0],
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const revokeCustomer = (
+    warehouse: PublicKey,
+    customer: PublicKey,
+    signer: Keypair,
+    asStaff = false
+  ) =>
+    program.methods
+      .revokeCustomer()
+      .accounts({
+        warehouse,
+        staff: asStaff ? getStaffPDA(warehouse, signer.publicKey)[0] : null,
+        warehouseCustomer: getWarehouseCustomerPDA(warehouse, customer)[0],
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const withdraw = (
+    customer: Keypair,
+    warehouse: PublicKey,
+    signer: Keypair = customer
+  ) =>
+    program.methods
+      .withdrawFromWarehouse()
+      .accounts({
+        customerProfile: getCustomerPDA(customer.publicKey)[0],
+        warehouse,
+        warehouseCustomer: getWarehouseCustomerPDA(
+          warehouse,
+          customer.publicKey
+        )[0],
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const setPauseFlags = (pauseFlags: number) =>
+    program.methods
+      .setPauseFlags(pauseFlags)
+      .accounts({ config: configPDA, admin: admin.publicKey })
+      .rpc();
+
+  const setStatus = (warehouse: PublicKey, status: object) =>
+    program.methods
+      .setWarehouseStatus(status as any)
+      .accounts({ config: configPDA, warehouse, admin: admin.publicKey })
+      .rpc();
+
+  before(async () => {
+    // Make sure the config exists (init_config may have run already)
+    try {
+      await program.account.programConfig.fetch(configPDA);
+    } catch (err) {
+      await program.methods
+        .initConfig(Keypair.generate().publicKey, 0)
+        .accounts({
+          config: configPDA,
+          admin: admin.publicKey,
+          program: program.programId,
+          programData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  // Customer confirmed at a fresh warehouse, with a notes hash on record
+  const confirmedCustomer = async () => {
+    const customer = await newCustomer();
+    const { warehouse, operator } = await createWarehouse();
+    await requestConfirmation(customer, warehouse);
+    await confirmCustomer(warehouse, customer.publicKey, operator, [
+      ...Buffer.alloc(32, 9),
+    ]);
+    return { customer, warehouse, operator };
+  };
+
+  describe("success cases", () => {
+    it("should close the entry and refund the rent", async () => {
+      const { customer, warehouse } = await confirmedCustomer();
+      const [entryPDA] = getWarehouseCustomerPDA(warehouse, customer.publicKey);
+      const rent = (await provider.connection.getAccountInfo(entryPDA))
+        .lamports;
+      const balanceBefore = await provider.connection.getBalance(
+        customer.publicKey
+      );
+
+      await withdraw(customer, warehouse);
+
+      expect(await provider.connection.getAccountInfo(entryPDA)).to.be.null;
+      const balanceAfter = await provider.connection.getBalance(
+        customer.publicKey
+      );
+      // Rent comes back, minus the transaction fee paid by the customer
+      expect(balanceAfter).to.be.greaterThan(balanceBefore + rent - 10_000);
+
+      const profile = await program.account.customerProfile.fetch(
+        getCustomerPDA(customer.publicKey)[0]
+      );
+      expect(profile.linkedWarehouses).to.equal(0);
+    });
+
+    it("should withdraw a pending or revoked entry", async () => {
+      const customer = await newCustomer();
+      const pending = await createWarehouse();
+      const revoked = await createWarehouse();
+      await requestConfirmation(customer, pending.warehouse);
+      await requestConfirmation(customer, revoked.warehouse);
+      await revokeCustomer(
+        revoked.warehouse,
+        customer.publicKey,
+        revoked.operator
+      );
+
+      await withdraw(customer, pending.warehouse);
+      await withdraw(customer, revoked.warehouse);
+
+      for (const { warehouse } of [pending, revoked]) {
+        expect(
+          await provider.connection.getAccountInfo(
+            getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+          )
+        ).to.be.null;
+      }
+    });
+
+    it("should leave other warehouses untouched", async () => {
+      const customer = await newCustomer();
+      const first = await createWarehouse();
+      const second = await createWarehouse();
+      await requestConfirmation(customer, first.warehouse);
+      await requestConfirmation(customer, second.warehouse);
+
+      await withdraw(customer, first.warehouse);
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(second.warehouse, customer.publicKey)[0]
+      );
+      expect(entry.status).to.deep.equal({ pending: {} });
+      const profile = await program.account.customerProfile.fetch(
+        getCustomerPDA(customer.publicKey)[0]
+      );
+      expect(profile.linkedWarehouses).to.equal(1);
+    });
+
+    it("should allow a new request after withdrawing", async () => {
+      const { customer, warehouse } = await confirmedCustomer();
+      await withdraw(customer, warehouse);
+
+      await requestConfirmation(customer, warehouse);
+
+      const entry = await program.account.warehouseCustomer.fetch(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+      );
+      expect(entry.status).to.deep.equal({ pending: {} });
+      expect(entry.notesHash).to.be.null;
+    });
+
+    it("should withdraw from a closed warehouse while onboarding is paused", async () => {
+      const { customer, warehouse } = await confirmedCustomer();
+      await setStatus(warehouse, CLOSED);
+      await setPauseFlags(PAUSE_ONBOARDING);
+
+      try {
+        await withdraw(customer, warehouse);
+      } finally {
+        await setPauseFlags(0);
+      }
+
+      expect(
+        await provider.connection.getAccountInfo(
+          getWarehouseCustomerPDA(warehouse, customer.publicKey)[0]
+        )
+      ).to.be.null;
+    });
+
+    it("should emit CustomerWithdrawn", async () => {
+      const { customer, warehouse } = await confirmedCustomer();
+
+      let event: any = null;
+      const listener = program.addEventListener("customerWithdrawn", (e) => {
+        event = e;
+      });
+
+      await withdraw(customer, warehouse);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event).to.not.be.null;
+      expect(event.warehouseCustomer.toString()).to.equal(
+        getWarehouseCustomerPDA(warehouse, customer.publicKey)[0].toString()
+      );
+      expect(event.warehouse.toString()).to.equal(warehouse.toString());
+      expect(event.customer.toString()).to.equal(customer.publicKey.toString());
+    });
+  });
+
+  describe("error cases", () => {
+    it("should fail for a warehouse the customer never requested", async () => {
+      const customer = await newCustomer();
+      const { warehouse } = await createWarehouse();
+
+      try {
+        await withdraw(customer, warehouse);
+        expect.fail("Should have thrown an error without an entry");
+      } catch (err) {
+        expect(err.toString()).to.include("AccountNotInitialized");
+      }
+    });
+
+    it("should reject the operator's signature", async () => {
+      const { customer, warehouse, operator } = await confirmedCustomer();
+
+      try {
+        await withdraw(customer, warehouse, operator);
+        expect.fail("Should have thrown an error for the operator");
+      } catch (err) {
+        expect(err.toString()).to.include("ConstraintSeeds");
+      }
+    });
+  });
+});